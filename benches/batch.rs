@@ -0,0 +1,85 @@
+mod common;
+
+use common::xorshift;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use timelane::subsecond::{nanosecond_to_day, nanosecond_to_second};
+use timelane::{batch, day_to_month, hour_to_day, minute_to_hour, second_to_minute, Mark};
+
+/// The chain of scalar functions converting a nanosecond to a day, as a baseline.
+fn nanosecond_to_day_by_chain(nanosecond: Mark) -> Mark {
+    hour_to_day(minute_to_hour(second_to_minute(nanosecond_to_second(
+        nanosecond,
+    ))))
+}
+
+/// A million nanoseconds from `start` to `end` years after 2000, in order like the rows of a
+/// time series, or shuffled.
+fn nanoseconds(start: Mark, end: Mark, sorted: bool) -> Vec<Mark> {
+    let year = 365 * 86_400 * 1_000_000_000;
+    let span = ((end - start) * year) as u64;
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut nanoseconds: Vec<_> = (0..1 << 20)
+        .map(|_| start * year + (xorshift(&mut state) % span) as Mark)
+        .collect();
+    if sorted {
+        nanoseconds.sort_unstable();
+    }
+    nanoseconds
+}
+
+fn conversions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("batch");
+    for (name, nanoseconds) in [
+        ("2020_2025", nanoseconds(20, 25, false)),
+        ("1970_2030/sorted", nanoseconds(-30, 30, true)),
+        ("1970_2030/shuffled", nanoseconds(-30, 30, false)),
+    ] {
+        group.throughput(Throughput::Elements(nanoseconds.len() as u64));
+        let mut output = vec![0; nanoseconds.len()];
+        group.bench_function(format!("chain/nanosecond_to_day/{}", name), |b| {
+            b.iter(|| {
+                for (nanosecond, day) in black_box(&nanoseconds).iter().zip(&mut output) {
+                    *day = nanosecond_to_day_by_chain(*nanosecond);
+                }
+            })
+        });
+        group.bench_function(format!("scalar/nanosecond_to_day/{}", name), |b| {
+            b.iter(|| {
+                for (nanosecond, day) in black_box(&nanoseconds).iter().zip(&mut output) {
+                    *day = nanosecond_to_day(*nanosecond);
+                }
+            })
+        });
+        group.bench_function(format!("batch/nanosecond_to_day/{}", name), |b| {
+            b.iter(|| batch::nanosecond_to_day(black_box(&nanoseconds), &mut output))
+        });
+        let mut seconds = vec![0; nanoseconds.len()];
+        batch::nanosecond_to_second(&nanoseconds, &mut seconds);
+        group.bench_function(format!("scalar/second_to_minute/{}", name), |b| {
+            b.iter(|| {
+                for (second, minute) in black_box(&seconds).iter().zip(&mut output) {
+                    *minute = second_to_minute(*second);
+                }
+            })
+        });
+        group.bench_function(format!("batch/second_to_minute/{}", name), |b| {
+            b.iter(|| batch::second_to_minute(black_box(&seconds), &mut output))
+        });
+        let mut days = vec![0; nanoseconds.len()];
+        batch::nanosecond_to_day(&nanoseconds, &mut days);
+        group.bench_function(format!("scalar/day_to_month/{}", name), |b| {
+            b.iter(|| {
+                for (day, month) in black_box(&days).iter().zip(&mut output) {
+                    *month = day_to_month(*day);
+                }
+            })
+        });
+        group.bench_function(format!("batch/day_to_month/{}", name), |b| {
+            b.iter(|| batch::day_to_month(black_box(&days), &mut output))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, conversions);
+criterion_main!(benches);
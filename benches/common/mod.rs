@@ -0,0 +1,9 @@
+//! Helpers shared by the benchmarks.
+
+/// Returns a pseudo-random number for each call.
+pub fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
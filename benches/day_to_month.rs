@@ -0,0 +1,98 @@
+mod common;
+
+use common::xorshift;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use timelane::{day_to_month, day_to_month_up, leap_days_before_year, Mark, EPOCH_YEAR};
+
+const ZMONTH_STARTS: [Mark; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+
+const ZMONTH_STARTS_LEAP_YEAR: [Mark; 12] = [0, 31, 60, 91, 121, 152, 182, 213, 244, 274, 305, 335];
+
+/// The search of the year before the algorithm of Neri and Schneider, as a baseline.
+fn day_to_zyear_and_days(day: Mark) -> (Mark, Mark, bool) {
+    let zday = day - 1;
+    let mut zyear = (zday - zday.div_euclid(97 + 400 * 365) * 97).div_euclid(365);
+    let mut leap_days =
+        leap_days_before_year(zyear + EPOCH_YEAR) - leap_days_before_year(EPOCH_YEAR);
+    let mut zstart_of_year = zyear * 365 + leap_days;
+    if zstart_of_year > zday {
+        zyear -= 1;
+        leap_days = leap_days_before_year(zyear + EPOCH_YEAR) - leap_days_before_year(EPOCH_YEAR);
+        zstart_of_year = zyear * 365 + leap_days;
+    }
+    let is_leap_year = (leap_days_before_year(zyear + 1 + EPOCH_YEAR)
+        - leap_days_before_year(EPOCH_YEAR))
+        > leap_days;
+    (zyear, zday - zstart_of_year, is_leap_year)
+}
+
+/// The search of the month before the algorithm of Neri and Schneider, as a baseline. It is not
+/// inlined, like the library functions called from another crate.
+#[inline(never)]
+fn day_to_month_by_search(day: Mark) -> Mark {
+    let (zyear, zdays_in_year, is_leap_year) = day_to_zyear_and_days(day);
+    let month_starts = if is_leap_year {
+        ZMONTH_STARTS_LEAP_YEAR
+    } else {
+        ZMONTH_STARTS
+    };
+    let mut month = 1;
+    while month < month_starts.len() && zdays_in_year >= month_starts[month] {
+        month += 1;
+    }
+    zyear * 12 + month as Mark
+}
+
+/// The rounded up search of the month, as a baseline.
+#[inline(never)]
+fn day_to_month_up_by_search(day: Mark) -> Mark {
+    let (zyear, zdays_in_year, is_leap_year) = day_to_zyear_and_days(day);
+    let month_starts = if is_leap_year {
+        ZMONTH_STARTS_LEAP_YEAR
+    } else {
+        ZMONTH_STARTS
+    };
+    let mut month = 1;
+    while month <= month_starts.len() && zdays_in_year > month_starts[month - 1] {
+        month += 1;
+    }
+    zyear * 12 + month as Mark
+}
+
+/// The days from 1900 to 2100, in order.
+fn sequential_days() -> Vec<Mark> {
+    (-36_524..36_525).collect()
+}
+
+/// Days spread over a million years, in a shuffled order.
+fn random_days() -> Vec<Mark> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    (0..73_049)
+        .map(|_| (xorshift(&mut state) % 730_485_000) as Mark - 365_242_500)
+        .collect()
+}
+
+fn conversions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day_to_month");
+    for (name, days) in [("sequential", sequential_days()), ("random", random_days())] {
+        for (function, convert) in [
+            ("search", day_to_month_by_search as fn(Mark) -> Mark),
+            ("neri_schneider", day_to_month),
+            ("search_up", day_to_month_up_by_search),
+            ("neri_schneider_up", day_to_month_up),
+        ] {
+            group.bench_function(format!("{}/{}", function, name), |b| {
+                b.iter(|| {
+                    black_box(&days)
+                        .iter()
+                        .map(|&day| convert(day))
+                        .fold(0, Mark::wrapping_add)
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, conversions);
+criterion_main!(benches);
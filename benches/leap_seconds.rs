@@ -0,0 +1,95 @@
+mod common;
+
+use common::xorshift;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use timelane::{
+    leap_seconds_before_minute, minute_to_second, second_to_minute, Mark, EPOCH_YEAR,
+    LEAP_SECONDS_MARKS,
+};
+
+/// The previous lookup of leap seconds, a linear scan of the table from its end, as a baseline.
+/// It is not inlined, like the library functions called from another crate.
+#[inline(never)]
+fn leap_seconds_before_minute_by_scan(minute: Mark) -> Mark {
+    let epoch_minute = 0;
+    let mut leap_seconds = LEAP_SECONDS_MARKS.len();
+    while leap_seconds > 0 && minute < LEAP_SECONDS_MARKS[leap_seconds - 1] {
+        leap_seconds -= 1;
+    }
+    let mut leap_seconds_offset = LEAP_SECONDS_MARKS.len();
+    while leap_seconds_offset > 0 && epoch_minute < LEAP_SECONDS_MARKS[leap_seconds_offset - 1] {
+        leap_seconds_offset -= 1;
+    }
+    leap_seconds as Mark - leap_seconds_offset as Mark
+}
+
+/// Minutes before the first leap second, among them, and after the last one.
+fn minutes() -> [(&'static str, Mark); 3] {
+    let year = 365 * 24 * 60;
+    [
+        ("1960", (1960 - EPOCH_YEAR) * year),
+        ("1990", (1990 - EPOCH_YEAR) * year),
+        ("2024", (2024 - EPOCH_YEAR) * year),
+    ]
+}
+
+/// Minutes spread from 1960 to 2030, in a shuffled order.
+fn mixed_minutes() -> Vec<Mark> {
+    let start = (1960 - EPOCH_YEAR) * 365 * 24 * 60;
+    let span = 70 * 365 * 24 * 60;
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    (0..1024)
+        .map(|_| start + (xorshift(&mut state) % span as u64) as Mark)
+        .collect()
+}
+
+fn lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("leap_seconds_before_minute");
+    let mixed = mixed_minutes();
+    group.bench_function("scan/mixed", |b| {
+        b.iter(|| {
+            black_box(&mixed)
+                .iter()
+                .map(|&minute| leap_seconds_before_minute_by_scan(minute))
+                .sum::<Mark>()
+        })
+    });
+    group.bench_function("lookup/mixed", |b| {
+        b.iter(|| {
+            black_box(&mixed)
+                .iter()
+                .map(|&minute| leap_seconds_before_minute(minute))
+                .sum::<Mark>()
+        })
+    });
+    for (name, minute) in minutes() {
+        group.bench_with_input(BenchmarkId::new("scan", name), &minute, |b, &minute| {
+            b.iter(|| leap_seconds_before_minute_by_scan(black_box(minute)))
+        });
+        group.bench_with_input(BenchmarkId::new("lookup", name), &minute, |b, &minute| {
+            b.iter(|| leap_seconds_before_minute(black_box(minute)))
+        });
+    }
+    group.finish();
+}
+
+fn conversions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("conversions");
+    for (name, minute) in minutes() {
+        group.bench_with_input(
+            BenchmarkId::new("minute_to_second", name),
+            &minute,
+            |b, &minute| b.iter(|| minute_to_second(black_box(minute))),
+        );
+        let second = minute_to_second(minute) + 30;
+        group.bench_with_input(
+            BenchmarkId::new("second_to_minute", name),
+            &second,
+            |b, &second| b.iter(|| second_to_minute(black_box(second))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, lookup, conversions);
+criterion_main!(benches);
@@ -0,0 +1,384 @@
+//! Checks that `timelane` builds without `std`, and that its scalers work with the `isize` of the
+//! target.
+//!
+//! All checks are evaluated at compile time: building this crate for a target, like the 32-bit
+//! `thumbv7em-none-eabi`, runs them with the `isize` of that target without running anything on
+//! it. An overflow or a failed assertion fails the build.
+#![no_std]
+
+use timelane::hebrew;
+use timelane::islamic::{self, Epoch, Intercalation, Variant};
+use timelane::julian::{self, Changeover};
+use timelane::lane::{
+    Day, HebrewMonth, HebrewYear, Hour, IslamicMonth, IslamicYear, JulianMonth, JulianYear, Lane,
+    Microsecond, Millisecond, Minute, Month, Nanosecond, Second, Year,
+};
+use timelane::subsecond::{
+    day_to_nanosecond, microsecond_to_second, microsecond_to_second_up, millisecond_to_second,
+    millisecond_to_second_up, nanosecond_to_day, nanosecond_to_day_up, nanosecond_to_second,
+    nanosecond_to_second_up, second_to_microsecond, second_to_millisecond, second_to_nanosecond,
+};
+use timelane::{
+    day_to_hour, day_to_month, day_to_month_up, day_to_second, hour_to_day, hour_to_day_up,
+    hour_to_minute, leap_days_before_year, leap_seconds_before_minute, minute_to_hour,
+    minute_to_hour_up, minute_to_second, month_to_day, month_to_year, month_to_year_up,
+    second_to_day, second_to_day_up, second_to_minute, second_to_minute_up, second_to_year,
+    second_to_year_up, year_to_month, year_to_second, Mark, LEAP_SECONDS_MARKS,
+};
+
+/// Checks that a rounded down and a rounded up scaler bracket `mark` between two consecutive marks
+/// of the larger lane, converted back with `back`.
+macro_rules! check_bracket {
+    ($down:path, $up:path, $back:path, $mark:expr $(,)?) => {{
+        let mark = $mark;
+        let low = $down(mark);
+        let high = $up(mark);
+        assert!($back(low) <= mark);
+        if $back(low) == mark {
+            assert!(high == low);
+        } else {
+            assert!(high == low + 1);
+            assert!($back(high) > mark);
+        }
+    }};
+}
+
+/// Checks that a rounded down and a rounded up scaler invert `back` on `mark`.
+macro_rules! check_roundtrip {
+    ($down:path, $up:path, $back:path, $mark:expr $(,)?) => {{
+        let mark = $mark;
+        assert!($down($back(mark)) == mark);
+        assert!($up($back(mark)) == mark);
+    }};
+}
+
+/// Checks that scaling the smallest and the largest marks down to a larger lane, and back, does
+/// not overflow.
+macro_rules! check_extremes {
+    ($down:path, $up:path, $back:path) => {{
+        assert!($back($up(Mark::MIN)) >= Mark::MIN);
+        assert!($back($down(Mark::MAX)) <= Mark::MAX);
+        assert!($down(Mark::MIN) <= $up(Mark::MIN));
+        assert!($down(Mark::MAX) <= $up(Mark::MAX));
+    }};
+}
+
+const fn check_lane<L: Lane>() {
+    assert!(L::MIN_SECOND < 0);
+    assert!(L::MAX_SECOND > 0);
+}
+
+// The values of the examples, which fit in 32 bits.
+const _: () = {
+    assert!(year_to_month(1999) == -11);
+    assert!(year_to_month(2001) == 13);
+    assert!(month_to_day(2) == 32);
+    assert!(month_to_day(14) == 398);
+    assert!(day_to_hour(0) == -24);
+    assert!(hour_to_minute(-1) == -60);
+    assert!(minute_to_second(-1) == -60);
+    assert!(second_to_minute(-61) == -2);
+    assert!(second_to_minute_up(-59) == 0);
+    assert!(minute_to_hour(-1) == -1);
+    assert!(minute_to_hour_up(-1) == 0);
+    assert!(hour_to_day(-1) == 0);
+    assert!(hour_to_day_up(-1) == 1);
+    assert!(day_to_month(32) == 2);
+    assert!(day_to_month_up(33) == 3);
+    assert!(month_to_year(0) == 1999);
+    assert!(month_to_year_up(2) == 2001);
+    assert!(leap_days_before_year(2001) - leap_days_before_year(2000) == 1);
+    assert!(leap_seconds_before_minute(0) == 0);
+    assert!(nanosecond_to_second(-1) == -1);
+    assert!(nanosecond_to_second_up(1) == 1);
+    assert!(second_to_nanosecond(2) == 2_000_000_000);
+    assert!(microsecond_to_second(-1) == -1);
+    assert!(microsecond_to_second_up(1) == 1);
+    assert!(second_to_microsecond(-1) == -1_000_000);
+    assert!(millisecond_to_second(-1) == -1);
+    assert!(millisecond_to_second_up(1) == 1);
+    assert!(second_to_millisecond(-1) == -1_000);
+    assert!(second_to_day(-1) == 0);
+    assert!(second_to_day_up(1) == 2);
+    assert!(day_to_second(2) == 86_400);
+    assert!(second_to_year(-1) == 1999);
+    assert!(second_to_year_up(1) == 2001);
+    assert!(year_to_second(2001) == 366 * 86_400);
+    assert!(nanosecond_to_day(-1) == 0);
+    assert!(nanosecond_to_day_up(1) == 2);
+    assert!(day_to_nanosecond(1) == 0);
+    assert!(julian::month_to_day(1) == 14);
+    assert!(julian::day_to_month(44) == 1);
+    assert!(julian::day_to_month_up(15) == 2);
+    assert!(julian::leap_days_before_year(0) == -1);
+};
+
+// The julian days and months, over four centuries around the epoch, and across the changeover of
+// 1582.
+const _: () = {
+    let mut month = -2412;
+    while month <= 2412 {
+        check_roundtrip!(
+            julian::day_to_month,
+            julian::day_to_month_up,
+            julian::month_to_day,
+            month
+        );
+        let day = julian::month_to_day(month);
+        check_bracket!(
+            julian::day_to_month,
+            julian::day_to_month_up,
+            julian::month_to_day,
+            day + 15
+        );
+        month += 1;
+    }
+    let changeover = Changeover::GREGORIAN;
+    let first_gregorian_day = changeover.first_gregorian_day();
+    assert!(matches!(
+        changeover.date_to_day(1582, 10, 4),
+        Some(day) if day == first_gregorian_day - 1
+    ));
+    assert!(changeover.date_to_day(1582, 10, 5).is_none());
+    assert!(changeover.day_to_month(first_gregorian_day) == year_to_month(1582) + 9);
+};
+
+// The hebrew days and months, over a century around 5760, the year ending in 2000.
+const _: () = {
+    let mut month = hebrew::year_to_month(5710);
+    while month <= hebrew::year_to_month(5810) {
+        check_roundtrip!(
+            hebrew::day_to_month,
+            hebrew::day_to_month_up,
+            hebrew::month_to_day,
+            month
+        );
+        check_bracket!(
+            hebrew::month_to_year,
+            hebrew::month_to_year_up,
+            hebrew::year_to_month,
+            month
+        );
+        let day = hebrew::month_to_day(month);
+        check_bracket!(
+            hebrew::day_to_month,
+            hebrew::day_to_month_up,
+            hebrew::month_to_day,
+            day + 15
+        );
+        month += 1;
+    }
+    assert!(hebrew::month_to_day(hebrew::year_to_month(5761)) == 274);
+    assert!(hebrew::days_in_year(5784) == 383);
+    assert!(matches!(hebrew::date_to_day(5785, 7, 1), Some(9043)));
+};
+
+// The islamic days and months, over four centuries around 1421, the year starting in 2000.
+const _: () = {
+    let mut month = islamic::year_to_month(1221);
+    while month <= islamic::year_to_month(1621) {
+        check_roundtrip!(
+            islamic::day_to_month,
+            islamic::day_to_month_up,
+            islamic::month_to_day,
+            month
+        );
+        check_bracket!(
+            islamic::month_to_year,
+            islamic::month_to_year_up,
+            islamic::year_to_month,
+            month
+        );
+        let day = islamic::month_to_day(month);
+        check_bracket!(
+            islamic::day_to_month,
+            islamic::day_to_month_up,
+            islamic::month_to_day,
+            day + 15
+        );
+        month += 1;
+    }
+    assert!(islamic::month_to_day(islamic::year_to_month(1421)) == 97);
+    assert!(Variant::ASTRONOMICAL.day_to_month(96) == islamic::year_to_month(1421));
+    let fatimid = Variant::new(Intercalation::Fatimid, Epoch::Friday);
+    assert!(fatimid.is_leap_year(1418) && !Variant::CIVIL.is_leap_year(1418));
+};
+
+// All leap seconds fit in the second lane of 32 bits, which spans from 1932 to 2068.
+const _: () = {
+    let mut index = 0;
+    while index < LEAP_SECONDS_MARKS.len() {
+        let minute = LEAP_SECONDS_MARKS[index];
+        let leap = minute_to_second(minute) - 1;
+        assert!(leap - minute_to_second(minute - 1) == 60);
+        assert!(second_to_minute(leap) == minute - 1);
+        assert!(second_to_minute_up(leap) == minute);
+        check_bracket!(
+            second_to_minute,
+            second_to_minute_up,
+            minute_to_second,
+            leap
+        );
+        check_roundtrip!(
+            second_to_minute,
+            second_to_minute_up,
+            minute_to_second,
+            minute
+        );
+        check_bracket!(second_to_day, second_to_day_up, day_to_second, leap);
+        check_bracket!(second_to_year, second_to_year_up, year_to_second, leap);
+        index += 1;
+    }
+};
+
+// Four centuries of days and months, around the epoch and around the leap days of 1900 and 2100.
+const _: () = {
+    let mut month = -2412;
+    while month <= 2412 {
+        check_roundtrip!(day_to_month, day_to_month_up, month_to_day, month);
+        check_roundtrip!(
+            month_to_year,
+            month_to_year_up,
+            year_to_month,
+            month / 12 + 2000
+        );
+        check_bracket!(month_to_year, month_to_year_up, year_to_month, month);
+        let day = month_to_day(month);
+        check_bracket!(day_to_month, day_to_month_up, month_to_day, day - 1);
+        check_bracket!(day_to_month, day_to_month_up, month_to_day, day + 15);
+        check_bracket!(
+            hour_to_day,
+            hour_to_day_up,
+            day_to_hour,
+            day_to_hour(day) + 7
+        );
+        month += 1;
+    }
+};
+
+// Minutes and seconds around the 2016 leap second, and around the epoch.
+const _: () = {
+    let mut second = 536_544_004 - 200;
+    while second <= 536_544_004 + 200 {
+        check_bracket!(
+            second_to_minute,
+            second_to_minute_up,
+            minute_to_second,
+            second
+        );
+        check_bracket!(
+            second_to_minute,
+            second_to_minute_up,
+            minute_to_second,
+            second - 536_544_004
+        );
+        check_bracket!(
+            minute_to_hour,
+            minute_to_hour_up,
+            hour_to_minute,
+            second / 60
+        );
+        second += 1;
+    }
+};
+
+// The sub-second lanes only span about 2 seconds around the epoch with 32 bits.
+const _: () = {
+    let mut second = -2;
+    while second <= 1 {
+        check_roundtrip!(
+            nanosecond_to_second,
+            nanosecond_to_second_up,
+            second_to_nanosecond,
+            second
+        );
+        check_bracket!(
+            nanosecond_to_second,
+            nanosecond_to_second_up,
+            second_to_nanosecond,
+            second_to_nanosecond(second) + 1,
+        );
+        check_bracket!(
+            microsecond_to_second,
+            microsecond_to_second_up,
+            second_to_microsecond,
+            second_to_microsecond(second) + 1,
+        );
+        check_bracket!(
+            millisecond_to_second,
+            millisecond_to_second_up,
+            second_to_millisecond,
+            second_to_millisecond(second) + 1,
+        );
+        second += 1;
+    }
+};
+
+// The extremes of the lanes, where the width of isize matters.
+const _: () = {
+    check_extremes!(month_to_year, month_to_year_up, year_to_month);
+    check_extremes!(day_to_month, day_to_month_up, month_to_day);
+    check_extremes!(hour_to_day, hour_to_day_up, day_to_hour);
+    check_extremes!(minute_to_hour, minute_to_hour_up, hour_to_minute);
+    check_extremes!(second_to_minute, second_to_minute_up, minute_to_second);
+    check_extremes!(
+        millisecond_to_second,
+        millisecond_to_second_up,
+        second_to_millisecond
+    );
+    check_extremes!(
+        microsecond_to_second,
+        microsecond_to_second_up,
+        second_to_microsecond
+    );
+    check_extremes!(
+        nanosecond_to_second,
+        nanosecond_to_second_up,
+        second_to_nanosecond
+    );
+    check_extremes!(second_to_day, second_to_day_up, day_to_second);
+    check_extremes!(second_to_year, second_to_year_up, year_to_second);
+    check_extremes!(nanosecond_to_day, nanosecond_to_day_up, day_to_nanosecond);
+    check_extremes!(
+        julian::day_to_month,
+        julian::day_to_month_up,
+        julian::month_to_day
+    );
+    check_extremes!(
+        islamic::month_to_year,
+        islamic::month_to_year_up,
+        islamic::year_to_month
+    );
+    check_extremes!(
+        islamic::day_to_month,
+        islamic::day_to_month_up,
+        islamic::month_to_day
+    );
+    check_extremes!(
+        hebrew::month_to_year,
+        hebrew::month_to_year_up,
+        hebrew::year_to_month
+    );
+    check_extremes!(
+        hebrew::day_to_month,
+        hebrew::day_to_month_up,
+        hebrew::month_to_day
+    );
+    assert!(leap_days_before_year(Mark::MIN) < leap_days_before_year(Mark::MAX));
+    assert!(leap_seconds_before_minute(Mark::MIN) == -22);
+    check_lane::<Year>();
+    check_lane::<Month>();
+    check_lane::<JulianYear>();
+    check_lane::<JulianMonth>();
+    check_lane::<IslamicYear>();
+    check_lane::<IslamicMonth>();
+    check_lane::<HebrewYear>();
+    check_lane::<HebrewMonth>();
+    check_lane::<Day>();
+    check_lane::<Hour>();
+    check_lane::<Minute>();
+    check_lane::<Second>();
+    check_lane::<Millisecond>();
+    check_lane::<Microsecond>();
+    check_lane::<Nanosecond>();
+};
@@ -0,0 +1,26 @@
+//! Drives the cron expressions with arbitrary texts and minutes, and checks that the next and
+//! previous fire times match the expression and are on the right side of the minute.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use timelane::cron::Cron;
+use timelane::zone::{FixedOffset, Zone};
+use timelane::Mark;
+
+fuzz_target!(|input: (&str, Mark, i16)| {
+    let (text, minute, offset) = input;
+    let Ok(cron) = Cron::parse(text) else {
+        return;
+    };
+    // Minutes around the years 0 to 4000, the search covers a 400 years cycle after them
+    let minute = minute.rem_euclid(4_000 * 525_960) - 2_000 * 525_960;
+    let zone = FixedOffset(Mark::from(offset) % 1_440);
+    if let Some(next) = cron.next_after(minute, zone) {
+        assert!(next > minute);
+        assert!(cron.matches(zone.local_minute(next)));
+    }
+    if let Some(previous) = cron.previous_before(minute, zone) {
+        assert!(previous < minute);
+        assert!(cron.matches(zone.local_minute(previous)));
+    }
+});
@@ -0,0 +1,51 @@
+//! Drives every parser with arbitrary text, and checks that what they accept is formatted back
+//! into a text parsed as the same value.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use timelane::iso8601::{
+    format_rfc3339, parse_iso8601, parse_iso8601_second, parse_rfc3339, parse_rfc3339_second,
+};
+use timelane::lane::{Nanosecond, Second};
+use timelane::period::Period;
+use timelane::rfc2822::{format_http_date, format_rfc2822, parse_http_date, parse_rfc2822};
+
+fuzz_target!(|input: &str| {
+    // Years outside 0000 to 9999 are formatted with more digits, which RFC 3339 rejects
+    if let Ok(nanosecond) = parse_rfc3339(input) {
+        let formatted = format_rfc3339::<Nanosecond>(nanosecond).to_string();
+        assert_eq!(parse_rfc3339(&formatted), Ok(nanosecond), "{}", formatted);
+    }
+    if let Ok(second) = parse_rfc3339_second(input) {
+        let formatted = format_rfc3339::<Second>(second).to_string();
+        if let Ok(parsed) = parse_rfc3339_second(&formatted) {
+            assert_eq!(parsed, second, "{}", formatted);
+        }
+    }
+    if let Ok(nanosecond) = parse_iso8601(input) {
+        let formatted = format_rfc3339::<Nanosecond>(nanosecond).to_string();
+        assert_eq!(parse_iso8601(&formatted), Ok(nanosecond), "{}", formatted);
+    }
+    if let Ok(second) = parse_iso8601_second(input) {
+        let formatted = format_rfc3339::<Second>(second).to_string();
+        if let Ok(parsed) = parse_iso8601_second(&formatted) {
+            assert_eq!(parsed, second, "{}", formatted);
+        }
+    }
+    if let Ok(second) = parse_rfc2822(input) {
+        let formatted = format_rfc2822(second, 0).to_string();
+        if let Ok(parsed) = parse_rfc2822(&formatted) {
+            assert_eq!(parsed, second, "{}", formatted);
+        }
+    }
+    if let Ok(second) = parse_http_date(input) {
+        let formatted = format_http_date(second).to_string();
+        if let Ok(parsed) = parse_http_date(&formatted) {
+            assert_eq!(parsed, second, "{}", formatted);
+        }
+    }
+    if let Ok(period) = Period::parse(input) {
+        let formatted = period.to_string();
+        assert_eq!(Period::parse(&formatted), Ok(period), "{}", formatted);
+    }
+});
@@ -0,0 +1,23 @@
+//! Drives the recurrence rules with arbitrary texts, starts and zones, and checks that their
+//! first occurrences are strictly increasing and not before the start.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use timelane::rrule::RecurrenceRule;
+use timelane::zone::FixedOffset;
+use timelane::Mark;
+
+fuzz_target!(|input: (&str, Mark, i16)| {
+    let (text, start, offset) = input;
+    let Ok(rule) = RecurrenceRule::parse(text) else {
+        return;
+    };
+    // Seconds around the years 0 to 4000, rules that never match search a 400 years cycle
+    let start = start.rem_euclid(4_000 * 31_557_600) - 2_000 * 31_557_600;
+    let zone = FixedOffset(Mark::from(offset) % 1_440);
+    let mut last = start - 1;
+    for second in rule.occurrences(start, zone).take(16) {
+        assert!(second > last, "{} after {}", second, last);
+        last = second;
+    }
+});
@@ -0,0 +1,111 @@
+//! Drives every scaler with arbitrary marks, and checks that the rounded down and rounded up
+//! scalers bracket the mark, round trip, never go backwards, and agree with the batch ones.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use timelane::subsecond::{
+    day_to_nanosecond, microsecond_to_second, microsecond_to_second_up, millisecond_to_second,
+    millisecond_to_second_up, nanosecond_to_day, nanosecond_to_day_up, nanosecond_to_second,
+    nanosecond_to_second_up, second_to_microsecond, second_to_millisecond, second_to_nanosecond,
+};
+use timelane::{
+    batch, day_to_hour, day_to_month, day_to_month_up, day_to_second, hour_to_day, hour_to_day_up,
+    hour_to_minute, leap_days_before_year, leap_seconds_before_minute, minute_to_hour,
+    minute_to_hour_up, minute_to_second, month_to_day, month_to_year, month_to_year_up,
+    second_to_day, second_to_day_up, second_to_minute, second_to_minute_up, second_to_year,
+    second_to_year_up, year_to_month, year_to_second, Mark, Scaler,
+};
+use timelane::{hebrew, islamic, julian};
+
+/// A finer lane scaler, and the rounded down and rounded up scalers back to the coarser lane.
+const SCALERS: [(Scaler, Scaler, Scaler); 16] = [
+    (year_to_month, month_to_year, month_to_year_up),
+    (month_to_day, day_to_month, day_to_month_up),
+    (day_to_hour, hour_to_day, hour_to_day_up),
+    (hour_to_minute, minute_to_hour, minute_to_hour_up),
+    (minute_to_second, second_to_minute, second_to_minute_up),
+    (
+        second_to_millisecond,
+        millisecond_to_second,
+        millisecond_to_second_up,
+    ),
+    (
+        second_to_microsecond,
+        microsecond_to_second,
+        microsecond_to_second_up,
+    ),
+    (
+        second_to_nanosecond,
+        nanosecond_to_second,
+        nanosecond_to_second_up,
+    ),
+    (day_to_second, second_to_day, second_to_day_up),
+    (year_to_second, second_to_year, second_to_year_up),
+    (day_to_nanosecond, nanosecond_to_day, nanosecond_to_day_up),
+    (
+        julian::month_to_day,
+        julian::day_to_month,
+        julian::day_to_month_up,
+    ),
+    (
+        islamic::year_to_month,
+        islamic::month_to_year,
+        islamic::month_to_year_up,
+    ),
+    (
+        islamic::month_to_day,
+        islamic::day_to_month,
+        islamic::day_to_month_up,
+    ),
+    (
+        hebrew::year_to_month,
+        hebrew::month_to_year,
+        hebrew::month_to_year_up,
+    ),
+    (
+        hebrew::month_to_day,
+        hebrew::day_to_month,
+        hebrew::day_to_month_up,
+    ),
+];
+
+fuzz_target!(|input: (Mark, Mark, Vec<Mark>)| {
+    let (a, b, marks) = input;
+    let (a, b) = (a.min(b), a.max(b));
+    leap_days_before_year(a);
+    leap_seconds_before_minute(a);
+    for (to_fine, down, up) in SCALERS {
+        // The coarse marks converted to the finer lane without overflowing
+        let (first, last) = (up(Mark::MIN), down(Mark::MAX));
+        let (low, high) = (down(a), up(a));
+        assert!(high == low || high == low + 1);
+        if (first..=last).contains(&low) {
+            assert!(to_fine(low) <= a);
+            assert_eq!(high == low, to_fine(low) == a);
+        }
+        if (first..=last).contains(&high) {
+            assert!(to_fine(high) >= a);
+        }
+        assert!(down(a) <= down(b));
+        assert!(up(a) <= up(b));
+        let coarse = first + a.rem_euclid(last - first + 1);
+        assert_eq!(down(to_fine(coarse)), coarse);
+        assert_eq!(up(to_fine(coarse)), coarse);
+    }
+    let mut output = vec![0; marks.len()];
+    batch::second_to_minute(&marks, &mut output);
+    assert!(marks
+        .iter()
+        .zip(&output)
+        .all(|(&mark, &minute)| second_to_minute(mark) == minute));
+    batch::second_to_day(&marks, &mut output);
+    assert!(marks
+        .iter()
+        .zip(&output)
+        .all(|(&mark, &day)| second_to_day(mark) == day));
+    batch::nanosecond_to_day(&marks, &mut output);
+    assert!(marks
+        .iter()
+        .zip(&output)
+        .all(|(&mark, &day)| nanosecond_to_day(mark) == day));
+});
@@ -0,0 +1,19 @@
+//! Drives the `strftime` patterns with arbitrary patterns, texts and marks, and checks that they
+//! never panic. Formatted marks are parsed back, but patterns can leave out fields or repeat
+//! them, so the parsed mark can differ.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use timelane::lane::Second;
+use timelane::strftime::Pattern;
+use timelane::Mark;
+
+fuzz_target!(|input: (&str, &str, Mark)| {
+    let (pattern, text, second) = input;
+    let Ok(pattern) = Pattern::new(pattern) else {
+        return;
+    };
+    let _ = pattern.parse::<Second>(text);
+    let formatted = pattern.format::<Second>(second).to_string();
+    let _ = pattern.parse::<Second>(&formatted);
+});
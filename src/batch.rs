@@ -0,0 +1,313 @@
+//! [`crate::Scaler`] functions converting whole slices of marks, for columnar data.
+//!
+//! Each function converts `input[i]` into `output[i]` and gives the same results as chaining the
+//! scalar functions on each mark. Marks are processed in fixed-size chunks: the leap seconds are
+//! looked up once for a chunk whose marks are all between the same two leap seconds, then the
+//! chunk is converted with plain arithmetic, in a loop without branches that the compiler can
+//! unroll and vectorize. The rare chunks holding a leap second fall back to the scalar functions.
+//!
+//! The fast path pays off for marks after the last leap second, before the first one, or sorted
+//! like the rows of a time series. Shuffled marks spread between 1972 and 2017 are converted a
+//! bit slower than with the scalar functions.
+//!
+//! # Examples
+//! ```
+//! use timelane::batch;
+//! use timelane::iso8601::parse_rfc3339;
+//! let nanoseconds = [
+//!     parse_rfc3339("2024-02-29T23:59:59.999999999Z").unwrap(),
+//!     parse_rfc3339("2024-03-01T00:00:00Z").unwrap(),
+//! ];
+//! let mut days = [0; 2];
+//! batch::nanosecond_to_day(&nanoseconds, &mut days);
+//! assert_eq!(days[1] - days[0], 1);
+//! ```
+use crate::lane::Lane;
+use crate::subsecond;
+use crate::{
+    leap_seconds_before_minute, leap_seconds_until_minute, minute_to_second, Mark,
+    LEAP_SECONDS_MARKS,
+};
+
+/// The number of marks converted together, with the fast path or the scalar functions.
+const CHUNK: usize = 8;
+
+/// Returns the seconds from `start` to `end`, excluded, between two leap seconds, with the
+/// number of leap seconds inserted in that range since the start of [`crate::EPOCH_YEAR`].
+///
+/// A second in this range is converted to a minute as if no leap second had ever been inserted
+/// once the leap seconds are subtracted. Returns `None` for a leap second.
+const fn leap_seconds_range(second: Mark) -> Option<(Mark, Mark, Mark)> {
+    let index = leap_seconds_until_minute(crate::second_to_minute(second)) as usize;
+    let (start, leap_seconds) = if index == 0 {
+        (
+            Mark::MIN,
+            leap_seconds_before_minute(LEAP_SECONDS_MARKS[0] - 1),
+        )
+    } else {
+        let minute = LEAP_SECONDS_MARKS[index - 1];
+        (minute_to_second(minute), leap_seconds_before_minute(minute))
+    };
+    let end = if index == LEAP_SECONDS_MARKS.len() {
+        Mark::MAX
+    } else {
+        minute_to_second(LEAP_SECONDS_MARKS[index]) - 1
+    };
+    if second < end {
+        Some((start, end, leap_seconds))
+    } else {
+        None
+    }
+}
+
+/// Converts the marks of a slice with `convert`, written to be vectorized.
+fn convert_all(input: &[Mark], output: &mut [Mark], convert: impl Fn(Mark) -> Mark) {
+    assert_eq!(
+        input.len(),
+        output.len(),
+        "the input and output slices must have the same length"
+    );
+    for (mark, converted) in input.iter().zip(output) {
+        *converted = convert(*mark);
+    }
+}
+
+/// Converts the marks of a slice through their seconds, by chunks. When all the seconds of a
+/// chunk are between the same two leap seconds, `fast` converts them with the number of leap
+/// seconds, otherwise `scalar` converts the marks one by one.
+fn convert_chunks(
+    input: &[Mark],
+    output: &mut [Mark],
+    to_second: impl Fn(Mark) -> Mark,
+    fast: impl Fn(Mark, Mark) -> Mark,
+    scalar: impl Fn(Mark) -> Mark,
+) {
+    assert_eq!(
+        input.len(),
+        output.len(),
+        "the input and output slices must have the same length"
+    );
+    let mut inputs = input.chunks_exact(CHUNK);
+    let mut outputs = output.chunks_exact_mut(CHUNK);
+    // Consecutive chunks are usually between the same leap seconds, the range is only looked up
+    // again when a chunk leaves it
+    let mut range = (0, 0, 0);
+    let is_in_range = |input: &[Mark], (start, end, _): (Mark, Mark, Mark)| {
+        input.iter().fold(true, |all, &mark| {
+            let second = to_second(mark);
+            all & (second >= start) & (second < end)
+        })
+    };
+    for (input, output) in (&mut inputs).zip(&mut outputs) {
+        if !is_in_range(input, range) {
+            if let Some(found) = leap_seconds_range(to_second(input[0])) {
+                range = found;
+            }
+        }
+        if is_in_range(input, range) {
+            for (mark, converted) in input.iter().zip(output) {
+                *converted = fast(to_second(*mark), range.2);
+            }
+        } else {
+            for (mark, converted) in input.iter().zip(output) {
+                *converted = scalar(*mark);
+            }
+        }
+    }
+    for (mark, converted) in inputs.remainder().iter().zip(outputs.into_remainder()) {
+        *converted = scalar(*mark);
+    }
+}
+
+/// Converts nanosecond marks to rounded down second marks, like
+/// [`subsecond::nanosecond_to_second`].
+///
+/// # Panics
+/// Panics if the slices have different lengths.
+///
+/// # Examples
+/// ```
+/// use timelane::batch;
+/// let mut seconds = [0; 3];
+/// batch::nanosecond_to_second(&[-1, 0, 1_999_999_999], &mut seconds);
+/// assert_eq!(seconds, [-1, 0, 1]);
+/// ```
+pub fn nanosecond_to_second(input: &[Mark], output: &mut [Mark]) {
+    convert_all(input, output, subsecond::nanosecond_to_second);
+}
+
+/// Converts second marks to rounded down minute marks, like [`crate::second_to_minute`].
+///
+/// # Panics
+/// Panics if the slices have different lengths.
+///
+/// # Examples
+/// ```
+/// use timelane::{batch, minute_to_second, LEAP_SECONDS_MARKS};
+/// let leap_second = minute_to_second(LEAP_SECONDS_MARKS[26]) - 1;
+/// let mut minutes = [0; 3];
+/// batch::second_to_minute(&[-1, 60, leap_second], &mut minutes);
+/// assert_eq!(minutes, [-1, 1, LEAP_SECONDS_MARKS[26] - 1]);
+/// ```
+pub fn second_to_minute(input: &[Mark], output: &mut [Mark]) {
+    convert_chunks(
+        input,
+        output,
+        |second| second,
+        |second, leap_seconds| (second - leap_seconds).div_euclid(60),
+        crate::second_to_minute,
+    );
+}
+
+/// Converts second marks to rounded down day marks, like [`crate::second_to_day`].
+///
+/// # Panics
+/// Panics if the slices have different lengths.
+///
+/// # Examples
+/// ```
+/// use timelane::batch;
+/// let mut days = [0; 3];
+/// batch::second_to_day(&[-1, 0, 86_399], &mut days);
+/// assert_eq!(days, [0, 1, 1]);
+/// ```
+pub fn second_to_day(input: &[Mark], output: &mut [Mark]) {
+    convert_chunks(
+        input,
+        output,
+        |second| second,
+        |second, leap_seconds| (second - leap_seconds).div_euclid(86_400) + 1,
+        crate::second_to_day,
+    );
+}
+
+/// Converts nanosecond marks to rounded down day marks, like [`subsecond::nanosecond_to_day`].
+///
+/// # Panics
+/// Panics if the slices have different lengths.
+///
+/// # Examples
+/// ```
+/// use timelane::batch;
+/// let mut days = [0; 2];
+/// batch::nanosecond_to_day(&[-1, 86_400_000_000_000], &mut days);
+/// assert_eq!(days, [0, 2]);
+/// ```
+pub fn nanosecond_to_day(input: &[Mark], output: &mut [Mark]) {
+    convert_chunks(
+        input,
+        output,
+        subsecond::nanosecond_to_second,
+        |second, leap_seconds| (second - leap_seconds).div_euclid(86_400) + 1,
+        subsecond::nanosecond_to_day,
+    );
+}
+
+/// Converts day marks to rounded down month marks, like [`crate::day_to_month`].
+///
+/// # Panics
+/// Panics if the slices have different lengths.
+///
+/// # Examples
+/// ```
+/// use timelane::batch;
+/// let mut months = [0; 3];
+/// batch::day_to_month(&[0, 31, 32], &mut months);
+/// assert_eq!(months, [0, 1, 2]);
+/// ```
+pub fn day_to_month(input: &[Mark], output: &mut [Mark]) {
+    convert_all(input, output, crate::day_to_month);
+}
+
+/// Converts marks of lane `F` to rounded down marks of lane `T`, through
+/// [`Lane::to_second_and_nanosecond`] and [`Lane::from_second_and_nanosecond`].
+///
+/// This works for any pair of lanes, but only as fast as the scalar functions.
+///
+/// # Panics
+/// Panics if the slices have different lengths.
+///
+/// # Examples
+/// ```
+/// use timelane::batch;
+/// use timelane::lane::{Month, Year};
+/// let mut years = [0; 3];
+/// batch::convert::<Month, Year>(&[0, 1, 13], &mut years);
+/// assert_eq!(years, [1999, 2000, 2001]);
+/// ```
+pub fn convert<F: Lane, T: Lane>(input: &[Mark], output: &mut [Mark]) {
+    let scalar = |mark| {
+        let (second, nanosecond) = F::to_second_and_nanosecond(mark);
+        T::from_second_and_nanosecond(second, nanosecond)
+    };
+    convert_all(input, output, scalar);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lane::{Day, Minute, Nanosecond, Second};
+    use crate::test_util::xorshift;
+
+    /// Seconds around each leap second and at the ends of the lane, and pseudo-random ones.
+    fn seconds() -> Vec<Mark> {
+        let around_leap_seconds = LEAP_SECONDS_MARKS.iter().flat_map(|&leap_minute| {
+            let leap_second = minute_to_second(leap_minute) - 1;
+            leap_second - 100..leap_second + 100
+        });
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        let random = (0..10_000).map(move |_| {
+            let random = xorshift(&mut state);
+            (random as Mark) >> (random % 40)
+        });
+        let extremes = [Mark::MIN, Mark::MIN + 1, -1, 0, 1, Mark::MAX - 1, Mark::MAX];
+        around_leap_seconds.chain(random).chain(extremes).collect()
+    }
+
+    /// Checks that a batch conversion matches a scalar one, for the whole slice and for its
+    /// shorter prefixes, which end with incomplete chunks.
+    fn check(input: &[Mark], batch: fn(&[Mark], &mut [Mark]), scalar: impl Fn(Mark) -> Mark) {
+        let expected: Vec<_> = input.iter().map(|&mark| scalar(mark)).collect();
+        for length in [input.len(), input.len() - 3, 5] {
+            let mut output = vec![0; length];
+            batch(&input[..length], &mut output);
+            assert_eq!(output, expected[..length]);
+        }
+    }
+
+    #[test]
+    fn batches_match_the_scalar_functions() {
+        let seconds = seconds();
+        check(&seconds, second_to_minute, crate::second_to_minute);
+        check(&seconds, second_to_day, crate::second_to_day);
+        check(&seconds, convert::<Second, Minute>, crate::second_to_minute);
+        let days: Vec<_> = seconds.iter().map(|second| second / 86_400).collect();
+        check(&days, day_to_month, crate::day_to_month);
+        let nanoseconds: Vec<_> = seconds
+            .iter()
+            .map(|second| second.wrapping_mul(1_000_000_000))
+            .chain(seconds.iter().map(|second| second / 1_000_000_000))
+            .collect();
+        check(
+            &nanoseconds,
+            nanosecond_to_second,
+            subsecond::nanosecond_to_second,
+        );
+        check(
+            &nanoseconds,
+            nanosecond_to_day,
+            subsecond::nanosecond_to_day,
+        );
+        check(
+            &nanoseconds,
+            convert::<Nanosecond, Day>,
+            subsecond::nanosecond_to_day,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the input and output slices must have the same length")]
+    fn slices_of_different_lengths_panic() {
+        second_to_day(&[0; 3], &mut [0; 2]);
+    }
+}
@@ -0,0 +1,193 @@
+//! Conversions between [`LaneMark`] and the date and time types of `chrono`, with the `chrono`
+//! feature.
+//!
+//! A `chrono` [`DateTime`] of any time zone converts to a rounded down [`LaneMark`] of any lane,
+//! and a [`LaneMark`] converts to the UTC [`DateTime`] where it starts. A [`NaiveDate`] converts
+//! to and from a day [`LaneMark`].
+//!
+//! `chrono` represents a leap second as second 59 with more than 10<sup>9</sup> nanoseconds, so
+//! leap seconds convert exactly in both directions. A `chrono` leap second that is not declared
+//! by [`crate::LEAP_SECONDS_MARKS`] fails to convert with [`ConversionError::InvalidLeapSecond`].
+//!
+//! # Examples
+//! ```
+//! use chrono::{DateTime, Utc};
+//! use timelane::lane::{LaneMark, Second};
+//! let leap = DateTime::parse_from_rfc3339("2016-12-31T23:59:60Z").unwrap();
+//! let second = LaneMark::<Second>::try_from(leap).unwrap();
+//! assert_eq!(second.mark(), 536_544_004);
+//! assert_eq!(DateTime::<Utc>::try_from(second).unwrap(), leap);
+//! ```
+use ::chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeZone, Timelike, Utc};
+
+use crate::lane::{ConversionError, Day, Lane, LaneMark};
+use crate::Mark;
+
+impl<L: Lane, Tz: TimeZone> TryFrom<DateTime<Tz>> for LaneMark<L> {
+    type Error = ConversionError;
+
+    fn try_from(date_time: DateTime<Tz>) -> Result<Self, ConversionError> {
+        let date_time = date_time.naive_utc();
+        let (second, nanosecond) = if date_time.nanosecond() >= 1_000_000_000 {
+            (
+                date_time.second() + 1,
+                date_time.nanosecond() - 1_000_000_000,
+            )
+        } else {
+            (date_time.second(), date_time.nanosecond())
+        };
+        LaneMark::from_civil(
+            (
+                date_time.year() as Mark,
+                date_time.month() as Mark,
+                date_time.day() as Mark,
+            ),
+            (
+                date_time.hour() as Mark,
+                date_time.minute() as Mark,
+                second as Mark,
+            ),
+            nanosecond as Mark,
+        )
+    }
+}
+
+impl<L: Lane> TryFrom<LaneMark<L>> for DateTime<Utc> {
+    type Error = ConversionError;
+
+    fn try_from(mark: LaneMark<L>) -> Result<Self, ConversionError> {
+        let ((year, month, day), (hour, minute, second), nanosecond) = mark.to_civil();
+        let date = naive_date(year, month, day)?;
+        // Leap seconds are the second 59 with more than a second of nanoseconds
+        let (second, nanosecond) = if second == 60 {
+            (59, nanosecond + 1_000_000_000)
+        } else {
+            (second, nanosecond)
+        };
+        let time = NaiveTime::from_hms_nano_opt(
+            hour as u32,
+            minute as u32,
+            second as u32,
+            nanosecond as u32,
+        )
+        .ok_or(ConversionError::OutOfRange)?;
+        Ok(Utc.from_utc_datetime(&date.and_time(time)))
+    }
+}
+
+impl TryFrom<NaiveDate> for LaneMark<Day> {
+    type Error = ConversionError;
+
+    fn try_from(date: NaiveDate) -> Result<Self, ConversionError> {
+        LaneMark::from_civil(
+            (
+                date.year() as Mark,
+                date.month() as Mark,
+                date.day() as Mark,
+            ),
+            (0, 0, 0),
+            0,
+        )
+    }
+}
+
+impl TryFrom<LaneMark<Day>> for NaiveDate {
+    type Error = ConversionError;
+
+    fn try_from(mark: LaneMark<Day>) -> Result<Self, ConversionError> {
+        let ((year, month, day), _, _) = mark.to_civil();
+        naive_date(year, month, day)
+    }
+}
+
+/// Returns the [`NaiveDate`] of a year, a one-based month and a one-based day of month.
+fn naive_date(year: Mark, month: Mark, day: Mark) -> Result<NaiveDate, ConversionError> {
+    let year = i32::try_from(year).map_err(|_| ConversionError::OutOfRange)?;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).ok_or(ConversionError::OutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iso8601::parse_rfc3339;
+    use crate::lane::{Month, Nanosecond, Second};
+    use crate::{minute_to_second, LEAP_SECONDS_MARKS};
+
+    fn chrono(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn all_leap_seconds_roundtrip() {
+        for leap_minute in LEAP_SECONDS_MARKS {
+            let leap = minute_to_second(leap_minute) - 1;
+            for second in leap - 1..=leap + 1 {
+                let date_time = DateTime::<Utc>::try_from(LaneMark::<Second>::new(second)).unwrap();
+                assert_eq!(date_time.nanosecond() >= 1_000_000_000, second == leap);
+                assert_eq!(
+                    LaneMark::<Second>::try_from(date_time).unwrap().mark(),
+                    second
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn date_times_convert_to_all_lanes() {
+        let date_time = chrono("2017-01-01T00:59:60.5+01:00");
+        let nanosecond = LaneMark::<Nanosecond>::try_from(date_time).unwrap();
+        assert_eq!(
+            nanosecond.mark(),
+            parse_rfc3339("2016-12-31T23:59:60.5Z").unwrap()
+        );
+        let month = LaneMark::<Month>::try_from(date_time).unwrap();
+        assert_eq!(
+            DateTime::<Utc>::try_from(month).unwrap(),
+            chrono("2016-12-01T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn undeclared_leap_seconds_are_rejected() {
+        let date_time = chrono("2015-12-31T23:59:60Z");
+        assert_eq!(
+            LaneMark::<Second>::try_from(date_time),
+            Err(ConversionError::InvalidLeapSecond)
+        );
+        let date_time = NaiveDate::from_ymd_opt(2016, 12, 31)
+            .unwrap()
+            .and_hms_nano_opt(23, 58, 59, 1_000_000_000)
+            .unwrap()
+            .and_utc();
+        assert_eq!(
+            LaneMark::<Second>::try_from(date_time),
+            Err(ConversionError::InvalidLeapSecond)
+        );
+    }
+
+    #[test]
+    fn dates_roundtrip() {
+        for day in -800_000..800_000 {
+            let date = NaiveDate::try_from(LaneMark::<Day>::new(day)).unwrap();
+            assert_eq!(LaneMark::<Day>::try_from(date).unwrap().mark(), day);
+        }
+        assert_eq!(
+            NaiveDate::try_from(LaneMark::<Day>::new(1)),
+            Ok(NaiveDate::from_ymd_opt(2000, 1, 1).unwrap())
+        );
+    }
+
+    #[test]
+    fn out_of_range_marks_are_rejected() {
+        assert_eq!(
+            DateTime::<Utc>::try_from(LaneMark::<Second>::new(Mark::MAX)),
+            Err(ConversionError::OutOfRange)
+        );
+        assert_eq!(
+            LaneMark::<Nanosecond>::try_from(chrono("9999-12-31T23:59:59Z")),
+            Err(ConversionError::OutOfRange)
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! Helpers to move between civil calendar fields (year, month of year, day of month...) and marks.
+use crate::{
+    day_to_hour, day_to_month, hour_to_day, hour_to_minute, leap_days_before_year,
+    leap_seconds_before_minute, minute_to_hour, minute_to_second, month_to_day, month_to_year,
+    second_to_minute, year_to_month, Mark,
+};
+
+/// The English names of the months of year.
+pub(crate) const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// The English names of the days of week, starting on Monday.
+pub(crate) const WEEKDAY_NAMES: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// The day [`Mark`] of January 1st, 1970.
+pub(crate) const UNIX_EPOCH_DAY: Mark = year_month_day_to_day(1970, 1, 1);
+
+/// Returns the day [`Mark`] of a day of month in a month of year.
+///
+/// The month is one-based and can overflow the year, the day is one-based and can overflow the
+/// month.
+pub(crate) const fn year_month_day_to_day(year: Mark, month: Mark, day: Mark) -> Mark {
+    let zmonth = month - 1;
+    let zday = day - 1;
+    month_to_day(year_to_month(year) + zmonth) + zday
+}
+
+/// Returns the day [`Mark`] of a one-based day of year.
+pub(crate) const fn year_ordinal_to_day(year: Mark, ordinal: Mark) -> Mark {
+    month_to_day(year_to_month(year)) + ordinal - 1
+}
+
+/// Returns whether a year is a leap year according to the proleptic gregorian calendar.
+pub(crate) const fn is_leap_year(year: Mark) -> bool {
+    leap_days_before_year(year + 1) > leap_days_before_year(year)
+}
+
+/// Returns the number of days in a month of year.
+pub(crate) const fn days_in_month(year: Mark, month: Mark) -> Mark {
+    let month = year_to_month(year) + month - 1;
+    month_to_day(month + 1) - month_to_day(month)
+}
+
+/// Returns the number of days in a year.
+pub(crate) const fn days_in_year(year: Mark) -> Mark {
+    if is_leap_year(year) {
+        366
+    } else {
+        365
+    }
+}
+
+/// Returns the ISO 8601 day of week of a day [`Mark`]: 1 for Monday up to 7 for Sunday.
+///
+/// Day one, the first day of [`crate::EPOCH_YEAR`], is a Saturday.
+pub(crate) const fn day_to_weekday(day: Mark) -> Mark {
+    (day % 7 + 7 + 4) % 7 + 1
+}
+
+/// Returns the day [`Mark`] of the Monday starting the first week of an ISO 8601 week-numbering year.
+///
+/// The first week of a year is the one containing January 4th.
+pub(crate) const fn iso_year_to_first_monday(year: Mark) -> Mark {
+    let january_4th = year_month_day_to_day(year, 1, 4);
+    january_4th - day_to_weekday(january_4th) + 1
+}
+
+/// Returns the number of weeks, 52 or 53, in an ISO 8601 week-numbering year.
+pub(crate) const fn weeks_in_iso_year(year: Mark) -> Mark {
+    (iso_year_to_first_monday(year + 1) - iso_year_to_first_monday(year)) / 7
+}
+
+/// Returns the day [`Mark`] of a one-based ISO 8601 week and day of week.
+pub(crate) const fn iso_week_date_to_day(year: Mark, week: Mark, weekday: Mark) -> Mark {
+    iso_year_to_first_monday(year) + (week - 1) * 7 + weekday - 1
+}
+
+/// Returns whether a leap second is inserted at the end of a minute [`Mark`].
+pub(crate) const fn minute_has_leap_second(minute: Mark) -> bool {
+    minute_to_second(minute + 1) - minute_to_second(minute) > 60
+}
+
+/// Splits a day [`Mark`] into a year, a one-based month of year and a one-based day of month.
+pub(crate) const fn day_to_year_month_day(day: Mark) -> (Mark, Mark, Mark) {
+    let month = day_to_month(day);
+    let year = month_to_year(month);
+    (
+        year,
+        month - year_to_month(year) + 1,
+        day - month_to_day(month) + 1,
+    )
+}
+
+/// Splits a minute [`Mark`] into a day [`Mark`], an hour of day and a minute of hour.
+pub(crate) const fn minute_to_day_hour_minute(minute: Mark) -> (Mark, Mark, Mark) {
+    let hour = minute_to_hour(minute);
+    let day = hour_to_day(hour);
+    (day, hour - day_to_hour(day), minute - hour_to_minute(hour))
+}
+
+/// Splits a second [`Mark`] into a minute [`Mark`] and a second of minute, which is 60 during a
+/// leap second.
+pub(crate) const fn second_to_minute_and_second(second: Mark) -> (Mark, Mark) {
+    let minute = second_to_minute(second);
+    // The minute of the first seconds of the lane starts before the lane
+    (
+        minute,
+        second - leap_seconds_before_minute(minute) - minute * 60,
+    )
+}
+
+/// Returns the POSIX time of a second [`Mark`], where every day lasts 86 400 seconds.
+///
+/// A leap second has the same POSIX time as the midnight following it. The POSIX time of the
+/// seconds close to the ends of the lane does not fit in a [`Mark`].
+pub(crate) const fn second_to_posix(second: Mark) -> i128 {
+    let (minute, second_of_minute) = second_to_minute_and_second(second);
+    let (day, hour, minute_of_hour) = minute_to_day_hour_minute(minute);
+    let second_of_day = hour * 3_600 + minute_of_hour * 60 + second_of_minute;
+    (day - UNIX_EPOCH_DAY) as i128 * 86_400 + second_of_day as i128
+}
+
+/// Returns the second [`Mark`] of a POSIX time, which is never a leap second.
+pub(crate) const fn posix_to_second(posix: Mark) -> Mark {
+    let day = UNIX_EPOCH_DAY + posix.div_euclid(86_400);
+    let second_of_day = posix.rem_euclid(86_400);
+    minute_to_second(hour_to_minute(day_to_hour(day)) + second_of_day / 60) + second_of_day % 60
+}
@@ -0,0 +1,262 @@
+//! Clocks telling the current nanosecond [`Mark`], which can be replaced by fake clocks in tests.
+//!
+//! Code reading the time through a [`Clock`] can use a [`SystemClock`] in production, with the
+//! `std` feature, and a [`ManualClock`] in tests to freeze the time or move it across a leap
+//! second or a month boundary. [`OffsetClock`] and [`ScaledClock`] shift or speed up another
+//! clock.
+//!
+//! # Examples
+//! ```
+//! use timelane::clock::{Clock, ManualClock};
+//! use timelane::lane::Day;
+//! use timelane::period::Period;
+//! let clock = ManualClock::new(0);
+//! assert_eq!(clock.now_in::<Day>(), 1);
+//! clock.advance_by(&Period { months: 1, ..Period::ZERO });
+//! assert_eq!(clock.now_in::<Day>(), 32);
+//! ```
+use core::cell::Cell;
+
+use crate::lane::{Lane, Nanosecond};
+use crate::period::Period;
+use crate::Mark;
+
+/// A source of the current time.
+pub trait Clock {
+    /// Returns the current nanosecond [`Mark`].
+    fn now(&self) -> Mark;
+
+    /// Returns the rounded down [`Mark`] of a lane containing the current time.
+    fn now_in<L: Lane>(&self) -> Mark
+    where
+        Self: Sized,
+    {
+        let (second, nanosecond) = Nanosecond::to_second_and_nanosecond(self.now());
+        L::from_second_and_nanosecond(second, nanosecond)
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> Mark {
+        (**self).now()
+    }
+}
+
+/// The clock of the system, see [`crate::system`].
+///
+/// # Examples
+/// ```
+/// use timelane::clock::{Clock, SystemClock};
+/// use timelane::system::LeapSeconds;
+/// let clock = SystemClock::new(LeapSeconds::Posix);
+/// assert!(clock.now() > 0);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SystemClock {
+    leap_seconds: crate::system::LeapSeconds,
+}
+
+#[cfg(feature = "std")]
+impl SystemClock {
+    /// Creates a clock reading the system time, mapping leap seconds as chosen.
+    pub const fn new(leap_seconds: crate::system::LeapSeconds) -> Self {
+        Self { leap_seconds }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    /// Returns the current nanosecond [`Mark`].
+    ///
+    /// # Panics
+    /// Panics if the system time does not fit in the nanosecond lane.
+    fn now(&self) -> Mark {
+        crate::system::now::<Nanosecond>(self.leap_seconds)
+    }
+}
+
+/// A clock that only moves when told to.
+///
+/// # Examples
+/// ```
+/// use timelane::clock::{Clock, ManualClock};
+/// use timelane::iso8601::parse_rfc3339;
+/// use timelane::lane::Minute;
+/// let clock = ManualClock::new(parse_rfc3339("2016-12-31T23:59:59Z").unwrap());
+/// let minute = clock.now_in::<Minute>();
+/// clock.advance(1_000_000_000);
+/// assert_eq!(clock.now_in::<Minute>(), minute);
+/// clock.advance(1_000_000_000);
+/// assert_eq!(clock.now_in::<Minute>(), minute + 1);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ManualClock {
+    now: Cell<Mark>,
+}
+
+impl ManualClock {
+    /// Creates a clock stopped at a nanosecond [`Mark`].
+    pub const fn new(now: Mark) -> Self {
+        Self {
+            now: Cell::new(now),
+        }
+    }
+
+    /// Moves the clock to a nanosecond [`Mark`], which can be in the past.
+    pub fn set(&self, now: Mark) {
+        self.now.set(now);
+    }
+
+    /// Moves the clock forward by a number of nanoseconds, or backward if negative.
+    pub fn advance(&self, nanoseconds: Mark) {
+        self.now.set(self.now.get() + nanoseconds);
+    }
+
+    /// Moves the clock by a calendar [`Period`].
+    pub fn advance_by(&self, period: &Period) {
+        self.now.set(period.add_to_nanosecond(self.now.get()));
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Mark {
+        self.now.get()
+    }
+}
+
+/// A clock shifted from another clock by a number of nanoseconds.
+///
+/// # Examples
+/// ```
+/// use timelane::clock::{Clock, ManualClock, OffsetClock};
+/// let clock = ManualClock::new(0);
+/// let offset = OffsetClock::new(&clock, -1_000);
+/// clock.advance(10);
+/// assert_eq!(offset.now(), -990);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct OffsetClock<C> {
+    clock: C,
+    offset: Mark,
+}
+
+impl<C: Clock> OffsetClock<C> {
+    /// Creates a clock ahead of another clock by a number of nanoseconds, or behind if negative.
+    pub const fn new(clock: C, offset: Mark) -> Self {
+        Self { clock, offset }
+    }
+}
+
+impl<C: Clock> Clock for OffsetClock<C> {
+    fn now(&self) -> Mark {
+        self.clock.now() + self.offset
+    }
+}
+
+/// A clock running faster or slower than another clock, from a starting [`Mark`].
+///
+/// # Examples
+/// ```
+/// use timelane::clock::{Clock, ManualClock, ScaledClock};
+/// let clock = ManualClock::new(500);
+/// let fast = ScaledClock::new(&clock, 0, 60, 1);
+/// clock.advance(10);
+/// assert_eq!(fast.now(), 600);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ScaledClock<C> {
+    clock: C,
+    start: Mark,
+    clock_start: Mark,
+    numerator: Mark,
+    denominator: Mark,
+}
+
+impl<C: Clock> ScaledClock<C> {
+    /// Creates a clock starting now at a nanosecond [`Mark`], and then moving by `numerator`
+    /// nanoseconds every `denominator` nanoseconds of another clock.
+    ///
+    /// # Panics
+    /// Panics if `denominator` is not positive.
+    pub fn new(clock: C, start: Mark, numerator: Mark, denominator: Mark) -> Self {
+        assert!(denominator > 0, "the denominator must be positive");
+        let clock_start = clock.now();
+        Self {
+            clock,
+            start,
+            clock_start,
+            numerator,
+            denominator,
+        }
+    }
+}
+
+impl<C: Clock> Clock for ScaledClock<C> {
+    /// Returns the current nanosecond [`Mark`].
+    ///
+    /// # Panics
+    /// Panics if the scaled time does not fit in the nanosecond lane.
+    fn now(&self) -> Mark {
+        let elapsed =
+            (self.clock.now() as i128 - self.clock_start as i128) * self.numerator as i128;
+        Mark::try_from(self.start as i128 + elapsed.div_euclid(self.denominator as i128))
+            .expect("the scaled time does not fit in the nanosecond lane")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iso8601::parse_rfc3339;
+    use crate::lane::{Day, Month, Second};
+    use crate::subsecond::second_to_nanosecond;
+
+    #[test]
+    fn manual_clocks_cross_leap_seconds() {
+        let clock = ManualClock::new(parse_rfc3339("2016-12-31T23:59:59Z").unwrap());
+        let second = clock.now_in::<Second>();
+        let day = clock.now_in::<Day>();
+        clock.advance(second_to_nanosecond(1));
+        assert_eq!(clock.now_in::<Second>(), second + 1);
+        assert_eq!(clock.now_in::<Day>(), day);
+        clock.advance(second_to_nanosecond(1));
+        assert_eq!(clock.now_in::<Day>(), day + 1);
+    }
+
+    #[test]
+    fn manual_clocks_cross_month_boundaries() {
+        let clock = ManualClock::new(parse_rfc3339("2000-01-31T12:00:00Z").unwrap());
+        let month = clock.now_in::<Month>();
+        clock.advance_by(&Period::parse("P1M").unwrap());
+        assert_eq!(clock.now(), parse_rfc3339("2000-02-29T12:00:00Z").unwrap());
+        assert_eq!(clock.now_in::<Month>(), month + 1);
+        clock.set(0);
+        assert_eq!(clock.now_in::<Month>(), 1);
+    }
+
+    #[test]
+    fn clocks_can_be_combined() {
+        let clock = ManualClock::new(1_000);
+        let scaled = ScaledClock::new(OffsetClock::new(&clock, 1_000), 0, -1, 2);
+        assert_eq!(scaled.now(), 0);
+        clock.advance(3);
+        assert_eq!(scaled.now(), -2);
+        clock.advance(-6);
+        assert_eq!(scaled.now(), 1);
+        let clocks: [&dyn Clock; 2] = [&clock, &scaled];
+        assert_eq!(clocks.map(|clock| clock.now()), [997, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the scaled time does not fit in the nanosecond lane")]
+    fn scaled_clocks_panic_out_of_the_lane() {
+        let clock = ManualClock::new(0);
+        let scaled = ScaledClock::new(&clock, Mark::MAX - 10, 2, 1);
+        assert_eq!(scaled.now(), Mark::MAX - 10);
+        clock.advance(5);
+        assert_eq!(scaled.now(), Mark::MAX);
+        clock.advance(1);
+        scaled.now();
+    }
+}
@@ -0,0 +1,827 @@
+//! Cron expressions, to find the minute marks when a scheduled job fires.
+//!
+//! A [`Cron`] expression has five fields separated by whitespace: the minute, the hour, the day of
+//! month, the month and the day of week. Each field is a list separated by commas of values,
+//! ranges like `1-5`, or `*` for all the values, optionally stepped like `*/15` or `8-18/2`.
+//! Months and days of week also accept their English names or three letters abbreviations, and
+//! days of week are numbered from 0 for Sunday to 6 for Saturday, with 7 being Sunday again.
+//!
+//! These extensions are supported:
+//!  - `L` in the day of month is the last day of the month, `L-3` three days before it.
+//!  - `15W` is the weekday nearest to the 15th, in the same month, and `LW` the last weekday of
+//!    the month.
+//!  - `5L` in the day of week is the last Friday of the month, and `5#2` the second one.
+//!  - `?` is the same as `*` in the day of month and the day of week.
+//!  - `@yearly`, `@annually`, `@monthly`, `@weekly`, `@daily`, `@midnight` and `@hourly` replace
+//!    the five fields.
+//!
+//! As in most cron implementations, when both the day of month and the day of week are
+//! restricted, a day matches if it matches either of them.
+//!
+//! Expressions are matched on the wall clock of a [`Zone`]. When the clock is set back, a job
+//! fires once, at the first occurrence of a repeated local time. When the clock is set forward,
+//! a job scheduled during the skipped local times fires at the end of the gap.
+//!
+//! # Examples
+//! ```
+//! use timelane::cron::Cron;
+//! use timelane::iso8601::parse_rfc3339_in;
+//! use timelane::lane::Minute;
+//! use timelane::zone::Utc;
+//! let cron: Cron = "30 9 * * 5#1".parse().unwrap();
+//! let after = parse_rfc3339_in::<Minute>("2024-06-01T00:00:00Z").unwrap();
+//! let next = cron.next_after(after, Utc);
+//! assert_eq!(next, Some(parse_rfc3339_in::<Minute>("2024-06-07T09:30:00Z").unwrap()));
+//! ```
+use core::str::FromStr;
+
+use crate::civil::{
+    day_to_weekday, day_to_year_month_day, days_in_month, minute_to_day_hour_minute, MONTH_NAMES,
+    WEEKDAY_NAMES,
+};
+use crate::parse::{
+    check_range, count_digits, digits, expect_end, parse_name, peek, skip_whitespace, ParseError,
+    ParseErrorKind,
+};
+use crate::zone::{LocalMinute, Zone};
+use crate::{day_to_hour, hour_to_minute, Mark};
+
+/// The number of minutes in 400 years, after which the Gregorian calendar and the days of week
+/// repeat: a search gives up after that.
+const CYCLE_MINUTES: Mark = 146_097 * 1440;
+
+/// A cron expression.
+///
+/// # Examples
+/// ```
+/// use timelane::cron::Cron;
+/// use timelane::iso8601::parse_rfc3339_in;
+/// use timelane::lane::Minute;
+/// use timelane::zone::FixedOffset;
+/// let cron = Cron::parse("0 18 LW * *").unwrap();
+/// let after = parse_rfc3339_in::<Minute>("2024-08-15T12:00:00Z").unwrap();
+/// let next = cron.next_after(after, FixedOffset(120)).unwrap();
+/// assert_eq!(next, parse_rfc3339_in::<Minute>("2024-08-30T16:00:00Z").unwrap());
+/// assert_eq!(cron.previous_before(next, FixedOffset(120)), Some(next - 30 * 24 * 60));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cron {
+    /// One bit for each minute of hour.
+    minutes: u64,
+    /// One bit for each hour of day.
+    hours: u64,
+    /// One bit for each day of month, from bit 1.
+    days: u64,
+    /// One bit for each number of days before the last day of month, `L-n`.
+    last_days: u64,
+    /// One bit for each day of month whose nearest weekday matches, `nW`.
+    nearest_weekdays: u64,
+    /// Whether the last weekday of the month matches, `LW`.
+    last_weekday: bool,
+    /// One bit for each month of year, from bit 1.
+    months: u64,
+    /// One bit for each day of week, from bit 1 for Monday to bit 7 for Sunday.
+    weekdays: u64,
+    /// One bit for each day of week whose last occurrence in the month matches, `nL`.
+    last_weekdays: u64,
+    /// One bit for each occurrence of a day of week in the month, bit `8 * n + w` for the `n`th
+    /// day of week `w`, `w#n`.
+    nth_weekdays: u64,
+    /// Whether a day matches when either the day of month or the day of week matches, rather
+    /// than both.
+    either_day: bool,
+}
+
+/// The range and names of the values of a field.
+struct Field {
+    min: Mark,
+    max: Mark,
+    kind: ParseErrorKind,
+    names: &'static [&'static str],
+    /// Whether `?` is accepted for all values, in the day fields.
+    optional: bool,
+}
+
+const MINUTE: Field = Field {
+    min: 0,
+    max: 59,
+    kind: ParseErrorKind::InvalidMinute,
+    names: &[],
+    optional: false,
+};
+const HOUR: Field = Field {
+    min: 0,
+    max: 23,
+    kind: ParseErrorKind::InvalidHour,
+    names: &[],
+    optional: false,
+};
+const DAY: Field = Field {
+    min: 1,
+    max: 31,
+    kind: ParseErrorKind::InvalidDay,
+    names: &[],
+    optional: true,
+};
+const MONTH: Field = Field {
+    min: 1,
+    max: 12,
+    kind: ParseErrorKind::InvalidMonth,
+    names: &MONTH_NAMES,
+    optional: false,
+};
+const WEEKDAY: Field = Field {
+    min: 0,
+    max: 7,
+    kind: ParseErrorKind::InvalidWeekday,
+    names: &WEEKDAY_NAMES,
+    optional: true,
+};
+
+/// The expressions replacing the five fields.
+const MACROS: [(&str, &str); 7] = [
+    ("@yearly", "0 0 1 1 *"),
+    ("@annually", "0 0 1 1 *"),
+    ("@monthly", "0 0 1 * *"),
+    ("@weekly", "0 0 * * 0"),
+    ("@daily", "0 0 * * *"),
+    ("@midnight", "0 0 * * *"),
+    ("@hourly", "0 * * * *"),
+];
+
+impl Field {
+    /// Parses a value, or a name, returns it and the next position.
+    fn value(&self, bytes: &[u8], position: usize) -> Result<(Mark, usize), ParseError> {
+        let count = count_digits(bytes, position);
+        if count == 0 && !self.names.is_empty() {
+            let (value, next) = parse_name(bytes, position, self.names)?;
+            if self.max == WEEKDAY.max {
+                // Sunday is the last name but the first day of week of cron
+                return Ok((value % 7, next));
+            }
+            return Ok((value, next));
+        }
+        let (value, next) = digits(bytes, position, count.max(1))?;
+        Ok((
+            check_range(value, self.min, self.max, position, self.kind)?,
+            next,
+        ))
+    }
+
+    /// Parses a value, a range or `*`, optionally stepped, returns its bits and the next
+    /// position.
+    fn range(&self, bytes: &[u8], position: usize) -> Result<(u64, usize), ParseError> {
+        let (start, end, mut next) = match peek(bytes, position)? {
+            b'*' => (self.min, self.max, position + 1),
+            b'?' if self.optional => (self.min, self.max, position + 1),
+            _ => {
+                let (start, next) = self.value(bytes, position)?;
+                if bytes.get(next) == Some(&b'-') {
+                    let (mut end, after) = self.value(bytes, next + 1)?;
+                    if end == 0 && self.max == WEEKDAY.max {
+                        end = 7;
+                    }
+                    if end < start {
+                        return Err(ParseError::new(next + 1, self.kind));
+                    }
+                    (start, end, after)
+                } else if bytes.get(next) == Some(&b'/') {
+                    (start, self.max, next)
+                } else {
+                    (start, start, next)
+                }
+            }
+        };
+        let mut step = 1;
+        if bytes.get(next) == Some(&b'/') {
+            let count = count_digits(bytes, next + 1);
+            let (value, after) = digits(bytes, next + 1, count.max(1))?;
+            step = check_range(value, 1, self.max, next + 1, self.kind)?;
+            next = after;
+        }
+        let mut bits = 0;
+        let mut value = start;
+        while value <= end {
+            bits |= 1 << value;
+            value += step;
+        }
+        Ok((bits, next))
+    }
+}
+
+/// Returns whether a field ends at a position, or is followed by another item.
+fn item_end(bytes: &[u8], position: usize) -> Result<bool, ParseError> {
+    match bytes.get(position) {
+        None => Ok(true),
+        Some(byte) if byte.is_ascii_whitespace() => Ok(true),
+        Some(b',') => Ok(false),
+        Some(_) => Err(ParseError::new(
+            position,
+            ParseErrorKind::UnexpectedCharacter,
+        )),
+    }
+}
+
+/// Returns the first set bit at or after `from`.
+const fn next_bit(bits: u64, from: Mark) -> Option<Mark> {
+    let bits = bits & (!0 << from);
+    if bits == 0 {
+        None
+    } else {
+        Some(bits.trailing_zeros() as Mark)
+    }
+}
+
+/// Returns the last set bit at or before `to`, which is smaller than 63.
+const fn previous_bit(bits: u64, to: Mark) -> Option<Mark> {
+    let bits = bits & ((2 << to) - 1);
+    if bits == 0 {
+        None
+    } else {
+        Some(63 - bits.leading_zeros() as Mark)
+    }
+}
+
+/// Returns the first local minute [`Mark`] of a day [`Mark`].
+const fn day_start(day: Mark) -> Mark {
+    hour_to_minute(day_to_hour(day))
+}
+
+impl Cron {
+    /// Parses a cron expression.
+    ///
+    /// Out of range values are rejected with the error of their field, like
+    /// [`ParseErrorKind::InvalidHour`], and the occurrences of `#` out of 1 to 5 with
+    /// [`ParseErrorKind::InvalidWeek`].
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::cron::Cron;
+    /// use timelane::parse::ParseErrorKind;
+    /// assert!(Cron::parse("*/15 8-18 L-2,15W JAN-JUN MON-FRI").is_ok());
+    /// assert_eq!(Cron::parse("@daily"), Cron::parse("0 0 * * *"));
+    /// let error = Cron::parse("0 24 * * *").unwrap_err();
+    /// assert_eq!((error.position(), error.kind()), (2, ParseErrorKind::InvalidHour));
+    /// let error = Cron::parse("0 0 * *").unwrap_err();
+    /// assert_eq!((error.position(), error.kind()), (7, ParseErrorKind::UnexpectedEnd));
+    /// ```
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let bytes = text.as_bytes();
+        let position = skip_whitespace(bytes, 0);
+        if bytes.get(position) == Some(&b'@') {
+            let end = bytes[position..]
+                .iter()
+                .position(u8::is_ascii_whitespace)
+                .map_or(bytes.len(), |length| position + length);
+            let (_, fields) = MACROS
+                .iter()
+                .find(|(name, _)| bytes[position..end].eq_ignore_ascii_case(name.as_bytes()))
+                .ok_or(ParseError::new(
+                    position,
+                    ParseErrorKind::UnexpectedCharacter,
+                ))?;
+            expect_end(bytes, skip_whitespace(bytes, end))?;
+            return Self::parse(fields);
+        }
+        let mut cron = Self {
+            minutes: 0,
+            hours: 0,
+            days: 0,
+            last_days: 0,
+            nearest_weekdays: 0,
+            last_weekday: false,
+            months: 0,
+            weekdays: 0,
+            last_weekdays: 0,
+            nth_weekdays: 0,
+            either_day: false,
+        };
+        let (minutes, position) = cron.parse_field(bytes, position, &MINUTE)?;
+        let (hours, position) = cron.parse_field(bytes, position, &HOUR)?;
+        let all_days = matches!(bytes.get(position), Some(b'*' | b'?'));
+        let (days, position) = cron.parse_field(bytes, position, &DAY)?;
+        let (months, position) = cron.parse_field(bytes, position, &MONTH)?;
+        let all_weekdays = matches!(bytes.get(position), Some(b'*' | b'?'));
+        let (weekdays, position) = cron.parse_field(bytes, position, &WEEKDAY)?;
+        expect_end(bytes, position)?;
+        cron.minutes = minutes;
+        cron.hours = hours;
+        cron.days = days;
+        cron.months = months;
+        // Sunday is both 0 and 7, and the bits of days of week start on Monday
+        cron.weekdays = (weekdays & !1) | (weekdays & 1) << 7;
+        cron.either_day = !all_days && !all_weekdays;
+        Ok(cron)
+    }
+
+    /// Parses the items of a field, returns the bits of its plain values and ranges, and the
+    /// position of the next field. The extensions are stored directly.
+    fn parse_field(
+        &mut self,
+        bytes: &[u8],
+        mut position: usize,
+        field: &Field,
+    ) -> Result<(u64, usize), ParseError> {
+        let mut bits = 0;
+        loop {
+            let next = if field.max == DAY.max {
+                self.parse_day_extension(bytes, position)?
+            } else if field.max == WEEKDAY.max {
+                self.parse_weekday_extension(bytes, position)?
+            } else {
+                None
+            };
+            let next = match next {
+                Some(next) => next,
+                None => {
+                    let (range, next) = field.range(bytes, position)?;
+                    bits |= range;
+                    next
+                }
+            };
+            if item_end(bytes, next)? {
+                return Ok((bits, skip_whitespace(bytes, next)));
+            }
+            position = next + 1;
+        }
+    }
+
+    /// Parses `L`, `L-n`, `LW` or `nW` in the day of month, returns the next position, or
+    /// `None` for other items.
+    fn parse_day_extension(
+        &mut self,
+        bytes: &[u8],
+        position: usize,
+    ) -> Result<Option<usize>, ParseError> {
+        if matches!(bytes.get(position), Some(b'L' | b'l')) {
+            return Ok(Some(match bytes.get(position + 1) {
+                Some(b'W' | b'w') => {
+                    self.last_weekday = true;
+                    position + 2
+                }
+                Some(b'-') => {
+                    let count = count_digits(bytes, position + 2);
+                    let (value, next) = digits(bytes, position + 2, count.max(1))?;
+                    let value =
+                        check_range(value, 0, 30, position + 2, ParseErrorKind::InvalidDay)?;
+                    self.last_days |= 1 << value;
+                    next
+                }
+                _ => {
+                    self.last_days |= 1;
+                    position + 1
+                }
+            }));
+        }
+        let count = count_digits(bytes, position);
+        if count > 0 && matches!(bytes.get(position + count), Some(b'W' | b'w')) {
+            let (value, next) = DAY.value(bytes, position)?;
+            self.nearest_weekdays |= 1 << value;
+            return Ok(Some(next + 1));
+        }
+        Ok(None)
+    }
+
+    /// Parses `wL` or `w#n` in the day of week, returns the next position, or `None` for other
+    /// items.
+    fn parse_weekday_extension(
+        &mut self,
+        bytes: &[u8],
+        position: usize,
+    ) -> Result<Option<usize>, ParseError> {
+        if matches!(bytes.get(position), Some(b'*' | b'?')) {
+            return Ok(None);
+        }
+        let (value, next) = WEEKDAY.value(bytes, position)?;
+        // The bits of days of week are from 1 for Monday to 7 for Sunday
+        let weekday = if value == 0 { 7 } else { value };
+        match bytes.get(next) {
+            Some(b'L' | b'l') => {
+                self.last_weekdays |= 1 << weekday;
+                Ok(Some(next + 1))
+            }
+            Some(b'#') => {
+                let count = count_digits(bytes, next + 1);
+                let (nth, after) = digits(bytes, next + 1, count.max(1))?;
+                let nth = check_range(nth, 1, 5, next + 1, ParseErrorKind::InvalidWeek)?;
+                self.nth_weekdays |= 1 << (8 * nth + weekday);
+                Ok(Some(after))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns whether a day [`Mark`] matches the day of month and the day of week fields.
+    fn matches_day(&self, day: Mark) -> bool {
+        let (year, month, month_day) = day_to_year_month_day(day);
+        let last = days_in_month(year, month);
+        let weekday = day_to_weekday(day);
+        let is_weekday = weekday <= 5;
+        let mut nearest = false;
+        let mut nearest_days = self.nearest_weekdays;
+        while nearest_days != 0 {
+            let target = nearest_days.trailing_zeros() as Mark;
+            nearest_days &= nearest_days - 1;
+            if target <= last {
+                nearest |= nearest_weekday(target, day_to_weekday(day - month_day + target), last)
+                    == month_day;
+            }
+        }
+        let last_weekday = match day_to_weekday(day - month_day + last) {
+            6 => last - 1,
+            7 => last - 2,
+            _ => last,
+        };
+        let day_matches = self.days & 1 << month_day != 0
+            || (last - month_day <= 30 && self.last_days & 1 << (last - month_day) != 0)
+            || (is_weekday && nearest)
+            || (self.last_weekday && month_day == last_weekday);
+        let nth = (month_day - 1) / 7 + 1;
+        let weekday_matches = self.weekdays & 1 << weekday != 0
+            || (self.last_weekdays & 1 << weekday != 0 && month_day + 7 > last)
+            || self.nth_weekdays & 1 << (8 * nth + weekday) != 0;
+        if self.either_day {
+            day_matches || weekday_matches
+        } else {
+            day_matches && weekday_matches
+        }
+    }
+
+    /// Returns whether a local minute [`Mark`] matches the expression.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::cron::Cron;
+    /// use timelane::{day_to_hour, hour_to_minute};
+    /// let cron = Cron::parse("0 12 13 * FRI").unwrap();
+    /// // Day 1 is Saturday, January 1st, 2000, and day 13 a Thursday
+    /// assert!(cron.matches(hour_to_minute(day_to_hour(13) + 12)));
+    /// assert!(cron.matches(hour_to_minute(day_to_hour(7) + 12)));
+    /// assert!(!cron.matches(hour_to_minute(day_to_hour(8) + 12)));
+    /// ```
+    pub fn matches(&self, local_minute: Mark) -> bool {
+        let (day, hour, minute) = minute_to_day_hour_minute(local_minute);
+        let (_, month, _) = day_to_year_month_day(day);
+        self.minutes & 1 << minute != 0
+            && self.hours & 1 << hour != 0
+            && self.months & 1 << month != 0
+            && self.matches_day(day)
+    }
+
+    /// Returns the first matching local minute [`Mark`] from `minute` included, up to `limit`.
+    fn next_local(&self, mut minute: Mark, limit: Mark) -> Option<Mark> {
+        while minute <= limit {
+            let (day, hour, minute_of_hour) = minute_to_day_hour_minute(minute);
+            let (year, month, month_day) = day_to_year_month_day(day);
+            if self.months & 1 << month == 0 {
+                minute = day_start(day - month_day + 1 + days_in_month(year, month));
+            } else if !self.matches_day(day) {
+                minute = day_start(day + 1);
+            } else {
+                match next_bit(self.hours, hour) {
+                    None => minute = day_start(day + 1),
+                    Some(next_hour) if next_hour != hour => {
+                        minute = day_start(day) + next_hour * 60;
+                    }
+                    _ => match next_bit(self.minutes, minute_of_hour) {
+                        None => minute += 60 - minute_of_hour,
+                        Some(next_minute) => return Some(minute + next_minute - minute_of_hour),
+                    },
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the last matching local minute [`Mark`] up to `minute` included, down to `limit`.
+    fn previous_local(&self, mut minute: Mark, limit: Mark) -> Option<Mark> {
+        while minute >= limit {
+            let (day, hour, minute_of_hour) = minute_to_day_hour_minute(minute);
+            let (_, month, month_day) = day_to_year_month_day(day);
+            if self.months & 1 << month == 0 {
+                minute = day_start(day - month_day + 1) - 1;
+            } else if !self.matches_day(day) {
+                minute = day_start(day) - 1;
+            } else {
+                match previous_bit(self.hours, hour) {
+                    None => minute = day_start(day) - 1,
+                    Some(previous_hour) if previous_hour != hour => {
+                        minute = day_start(day) + previous_hour * 60 + 59;
+                    }
+                    _ => match previous_bit(self.minutes, minute_of_hour) {
+                        None => minute -= minute_of_hour + 1,
+                        Some(previous_minute) => {
+                            return Some(minute - (minute_of_hour - previous_minute))
+                        }
+                    },
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the first UTC minute [`Mark`] after `utc_minute` excluded when the expression
+    /// fires on the wall clock of a [`Zone`], or `None` if it never fires in the next 400
+    /// years.
+    ///
+    /// The search jumps over the months, days and hours that do not match.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::cron::Cron;
+    /// use timelane::zone::Utc;
+    /// let cron = Cron::parse("*/20 * * * *").unwrap();
+    /// assert_eq!(cron.next_after(0, Utc), Some(20));
+    /// assert_eq!(cron.next_after(-1, Utc), Some(0));
+    /// assert_eq!(Cron::parse("0 0 30 2 *").unwrap().next_after(0, Utc), None);
+    /// ```
+    pub fn next_after<Z: Zone>(&self, utc_minute: Mark, zone: Z) -> Option<Mark> {
+        // No later UTC minute shows an earlier local minute than this one
+        let offset = zone
+            .offset(utc_minute)
+            .min(zone.offset(utc_minute.saturating_add(1440)));
+        let mut local_minute = utc_minute.checked_add(1)?.checked_add(offset)?;
+        let limit = local_minute.saturating_add(CYCLE_MINUTES);
+        loop {
+            local_minute = self.next_local(local_minute, limit)?;
+            let fired = fire_minute(&zone, local_minute);
+            if fired > utc_minute {
+                return Some(fired);
+            }
+            local_minute += 1;
+        }
+    }
+
+    /// Returns the last UTC minute [`Mark`] before `utc_minute` excluded when the expression
+    /// fires on the wall clock of a [`Zone`], or `None` if it never fired in the previous 400
+    /// years.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::cron::Cron;
+    /// use timelane::zone::Utc;
+    /// let cron = Cron::parse("0 0 1 * *").unwrap();
+    /// // Minute 0 is the start of January 1st, 2000
+    /// assert_eq!(cron.previous_before(1, Utc), Some(0));
+    /// assert_eq!(cron.previous_before(0, Utc), Some(-31 * 24 * 60));
+    /// ```
+    pub fn previous_before<Z: Zone>(&self, utc_minute: Mark, zone: Z) -> Option<Mark> {
+        // No earlier UTC minute shows a later local minute than this one
+        let offset = zone
+            .offset(utc_minute)
+            .max(zone.offset(utc_minute.saturating_sub(1440)));
+        let mut local_minute = utc_minute.checked_add(offset)?;
+        let limit = local_minute.saturating_sub(CYCLE_MINUTES);
+        loop {
+            local_minute = self.previous_local(local_minute, limit)?;
+            let fired = fire_minute(&zone, local_minute);
+            if fired < utc_minute {
+                return Some(fired);
+            }
+            local_minute -= 1;
+        }
+    }
+}
+
+impl FromStr for Cron {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        Self::parse(text)
+    }
+}
+
+/// Returns the day of month of the weekday nearest to a day of month, in the same month, given
+/// its day of week and the last day of the month.
+const fn nearest_weekday(month_day: Mark, weekday: Mark, last: Mark) -> Mark {
+    match weekday {
+        6 if month_day == 1 => 3,
+        6 => month_day - 1,
+        7 if month_day == last => month_day - 2,
+        7 => month_day + 1,
+        _ => month_day,
+    }
+}
+
+/// Returns the UTC minute [`Mark`] when a job scheduled at a local minute [`Mark`] fires: the
+/// first time the wall clock shows it, or the end of the gap when the clock skips it.
+fn fire_minute<Z: Zone>(zone: &Z, local_minute: Mark) -> Mark {
+    match zone.utc_minutes(local_minute) {
+        LocalMinute::Unique(minute) | LocalMinute::Repeated(minute, _) => minute,
+        LocalMinute::Skipped(_) => {
+            let before = zone.offset(local_minute - 1440);
+            // The offset is `before` at `low` and the one after the change at `high`
+            let mut low = local_minute - zone.offset(local_minute + 1440);
+            let mut high = local_minute - before;
+            while high - low > 1 {
+                let middle = low + (high - low) / 2;
+                if zone.offset(middle) == before {
+                    low = middle;
+                } else {
+                    high = middle;
+                }
+            }
+            high
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iso8601::{format_rfc3339, parse_rfc3339_in};
+    use crate::lane::Minute;
+    use crate::zone::Utc;
+
+    fn minute(text: &str) -> Mark {
+        parse_rfc3339_in::<Minute>(text).unwrap()
+    }
+
+    fn format(minute: Option<Mark>) -> String {
+        format_rfc3339::<Minute>(minute.unwrap()).to_string()
+    }
+
+    /// Returns the dates matching an expression in a month, at midnight.
+    fn days_in(cron: &str, month: &str) -> Vec<Mark> {
+        let cron = Cron::parse(cron).unwrap();
+        let start = minute(&format!("{}-01T00:00:00Z", month));
+        (0..31)
+            .map(|day| start + day * 1440)
+            .filter(|&minute| {
+                minute_to_day_hour_minute(minute).0 - minute_to_day_hour_minute(start).0
+                    < days_in_month_of(start)
+                    && cron.matches(minute)
+            })
+            .map(|minute| day_to_year_month_day(minute_to_day_hour_minute(minute).0).2)
+            .collect()
+    }
+
+    fn days_in_month_of(minute: Mark) -> Mark {
+        let (year, month, _) = day_to_year_month_day(minute_to_day_hour_minute(minute).0);
+        days_in_month(year, month)
+    }
+
+    #[test]
+    fn search_matches_a_scan_minute_by_minute() {
+        for expression in [
+            "* * * * *",
+            "*/7 */5 * * *",
+            "59 23 L * *",
+            "0 0 29 2 *",
+            "15,45 8-18/3 1-7 * 1",
+            "0 12 15W * *",
+            "30 6 * 1,6,12 5L",
+            "0 0 13 * FRI",
+            "0 9 LW JUN-AUG ?",
+            "1 1 L-3 * 2#2",
+        ] {
+            let cron = Cron::parse(expression).unwrap();
+            for after in [
+                -1,
+                0,
+                7919,
+                minute("2023-12-31T23:59:00Z"),
+                minute("2100-02-28T12:00:00Z"),
+            ] {
+                let next = (after + 1..).find(|&minute| cron.matches(minute));
+                assert_eq!(
+                    cron.next_after(after, Utc),
+                    next,
+                    "{} after {}",
+                    expression,
+                    after
+                );
+                let previous = (Mark::MIN..after)
+                    .rev()
+                    .find(|&minute| cron.matches(minute));
+                assert_eq!(
+                    cron.previous_before(after, Utc),
+                    previous,
+                    "{} before {}",
+                    expression,
+                    after
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn extensions_match_their_days() {
+        // September 2024 starts on a Sunday and ends on a Monday
+        assert_eq!(days_in("0 0 L * *", "2024-09"), [30]);
+        assert_eq!(days_in("0 0 L-2,L-29 * *", "2024-09"), [1, 28]);
+        assert_eq!(days_in("0 0 1W * *", "2024-09"), [2]);
+        assert_eq!(days_in("0 0 7W,14W * *", "2024-09"), [6, 13]);
+        assert_eq!(days_in("0 0 LW * *", "2024-09"), [30]);
+        assert_eq!(days_in("0 0 * * 0L", "2024-09"), [29]);
+        assert_eq!(days_in("0 0 * * SUN#1,1#5", "2024-09"), [1, 30]);
+        // June 2024 starts on a Saturday and ends on a Sunday
+        assert_eq!(days_in("0 0 1W,30W * *", "2024-06"), [3, 28]);
+        assert_eq!(days_in("0 0 LW * *", "2024-06"), [28]);
+        assert!(days_in("0 0 31W * *", "2024-06").is_empty());
+        assert_eq!(days_in("0 0 * * 7#5", "2024-06"), [30]);
+        assert_eq!(days_in("0 0 1,15 * 6", "2024-06"), [1, 8, 15, 22, 29]);
+        assert_eq!(days_in("0 0 1,15 * *", "2024-06"), [1, 15]);
+        assert_eq!(days_in("0 0 ? * SAT-SUN/6", "2024-06"), [1, 8, 15, 22, 29]);
+        assert_eq!(days_in("0 0 * * FRI-SUN", "2024-06").len(), 14);
+    }
+
+    #[test]
+    fn invalid_expressions_are_rejected() {
+        for (expression, position, kind) in [
+            ("60 * * * *", 0, ParseErrorKind::InvalidMinute),
+            ("* 1-24 * * *", 4, ParseErrorKind::InvalidHour),
+            ("* * 0 * *", 4, ParseErrorKind::InvalidDay),
+            ("* * L-31 * *", 6, ParseErrorKind::InvalidDay),
+            ("* * * 13 *", 6, ParseErrorKind::InvalidMonth),
+            ("* * * DEC-JAN *", 10, ParseErrorKind::InvalidMonth),
+            ("* * * * 8", 8, ParseErrorKind::InvalidWeekday),
+            ("* * * * 1#6", 10, ParseErrorKind::InvalidWeek),
+            ("*/0 * * * *", 2, ParseErrorKind::InvalidMinute),
+            ("? * * * *", 0, ParseErrorKind::UnexpectedCharacter),
+            ("* * * * MOO", 8, ParseErrorKind::UnexpectedCharacter),
+            ("*, * * * *", 2, ParseErrorKind::UnexpectedCharacter),
+            ("* * * * * *", 10, ParseErrorKind::TrailingCharacters),
+            ("* * *", 5, ParseErrorKind::UnexpectedEnd),
+            ("@reboot", 0, ParseErrorKind::UnexpectedCharacter),
+            ("@daily *", 7, ParseErrorKind::TrailingCharacters),
+        ] {
+            let error = Cron::parse(expression).unwrap_err();
+            assert_eq!(
+                (error.position(), error.kind()),
+                (position, kind),
+                "{}",
+                expression
+            );
+        }
+        assert_eq!(Cron::parse(" @Weekly "), Cron::parse("0 0 * * SUN"));
+        assert_eq!(Cron::parse("0 0 * * 0"), Cron::parse("0 0 * * 7"));
+        assert_eq!(Cron::parse("0\t0  1 jan,Feb *"), Cron::parse("0 0 1 1-2 *"));
+    }
+
+    #[test]
+    fn jobs_fire_once_across_daylight_saving_time_changes() {
+        /// Central European Time in 2024, on summer time from March 31st to October 27th.
+        struct Europe2024;
+
+        impl Zone for Europe2024 {
+            fn offset(&self, utc_minute: Mark) -> Mark {
+                if (minute("2024-03-31T01:00:00Z")..minute("2024-10-27T01:00:00Z"))
+                    .contains(&utc_minute)
+                {
+                    120
+                } else {
+                    60
+                }
+            }
+        }
+
+        let nightly = Cron::parse("30 2 * * *").unwrap();
+        let spring = nightly.next_after(minute("2024-03-30T12:00:00Z"), Europe2024);
+        assert_eq!(format(spring), "2024-03-31T01:00:00Z");
+        let autumn = nightly.next_after(minute("2024-10-26T12:00:00Z"), Europe2024);
+        assert_eq!(format(autumn), "2024-10-27T00:30:00Z");
+        let after_autumn = nightly.next_after(autumn.unwrap(), Europe2024);
+        assert_eq!(format(after_autumn), "2024-10-28T01:30:00Z");
+        assert_eq!(
+            nightly.previous_before(after_autumn.unwrap(), Europe2024),
+            autumn
+        );
+        assert_eq!(
+            format(nightly.previous_before(spring.unwrap(), Europe2024)),
+            "2024-03-30T01:30:00Z"
+        );
+
+        let half_hourly = Cron::parse("*/30 * * * *").unwrap();
+        let fire_times = |mut utc_minute: Mark| {
+            (0..4)
+                .map(|_| {
+                    utc_minute = half_hourly.next_after(utc_minute, Europe2024).unwrap();
+                    format_rfc3339::<Minute>(utc_minute).to_string()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            fire_times(minute("2024-03-31T00:15:00Z")),
+            [
+                "2024-03-31T00:30:00Z",
+                "2024-03-31T01:00:00Z",
+                "2024-03-31T01:30:00Z",
+                "2024-03-31T02:00:00Z"
+            ]
+        );
+        assert_eq!(
+            fire_times(minute("2024-10-27T00:15:00Z")),
+            [
+                "2024-10-27T00:30:00Z",
+                "2024-10-27T02:00:00Z",
+                "2024-10-27T02:30:00Z",
+                "2024-10-27T03:00:00Z"
+            ]
+        );
+        assert_eq!(
+            half_hourly.previous_before(minute("2024-10-27T01:45:00Z"), Europe2024),
+            Some(minute("2024-10-27T00:30:00Z"))
+        );
+    }
+}
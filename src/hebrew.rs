@@ -0,0 +1,492 @@
+//! [`crate::Scaler`] functions for the hebrew calendar.
+//!
+//! Hebrew years have 12 months, or 13 months in the 7 leap years of each cycle of 19 years, where
+//! Adar I is inserted before Adar. The year starts with Tishrei, on the day of the molad, the
+//! computed new moon, unless one of the postponement rules pushes it by one or two days: when
+//! the molad is too late in the day, or when the year would start on a Sunday, a Wednesday or a
+//! Friday, or would be too long or too short. Heshvan and Kislev have 29 or 30 days so that the
+//! year ends right before the next one starts.
+//!
+//! Year marks are the years of the hebrew calendar, anno mundi, with a year zero before year one.
+//! Month one is Tishrei of year one, and the month marks follow each other in the order of the
+//! calendar, across leap years.
+//!
+//! The start of a year is computed with the molad of the years around it, in 128 bits, so these
+//! functions are slower than the other scalers.
+//!
+//! # Examples
+//! ```
+//! use timelane::{hebrew, month_to_day, year_to_month};
+//! // The year 5785 started on October 3rd, 2024
+//! let tishrei_5785 = hebrew::year_to_month(5785);
+//! assert_eq!(hebrew::month_to_day(tishrei_5785), month_to_day(year_to_month(2024) + 9) + 2);
+//! ```
+use crate::{julian, Mark};
+
+/// The number of months in 19 years, after which leap years repeat.
+const MONTHS_IN_19_YEARS: Mark = 19 * 12 + 7;
+
+/// The number of parts, a 1080th of an hour, in a day.
+const PARTS_IN_DAY: i128 = 24 * 1080;
+
+/// The day [`Mark`] of Tishrei 1st of year one, October 7th, 3761BC in the julian calendar.
+const EPOCH: i128 = (julian::month_to_day(crate::year_to_month(-3760) + 9) + 6) as i128;
+
+/// Converts a hebrew year [`Mark`] to a hebrew month [`Mark`].
+///
+/// Year one is month one.
+///
+/// # Examples
+/// ```
+/// use timelane::hebrew::year_to_month;
+/// assert_eq!(year_to_month(0), -12);
+/// assert_eq!(year_to_month(1), 1);
+/// assert_eq!(year_to_month(2), 13);
+/// // The year 3 has 13 months
+/// assert_eq!(year_to_month(4), 38);
+/// use timelane::Mark;
+/// assert_eq!(year_to_month(-745719441277620170), Mark::MIN + 9);
+/// assert_eq!(year_to_month(745719441277620172), Mark::MAX - 7);
+/// ```
+pub const fn year_to_month(year: Mark) -> Mark {
+    let zyear = year - 1;
+    let cycle = zyear.div_euclid(19);
+    let month_in_cycle = months_before_zyear_in_cycle(zyear.rem_euclid(19)) + 1;
+    // Negative cycles lend a cycle of months to the month in the cycle to keep the product in range
+    let is_negative = (cycle < 0) as Mark;
+    (cycle + is_negative) * MONTHS_IN_19_YEARS + (month_in_cycle - is_negative * MONTHS_IN_19_YEARS)
+}
+
+/// Converts a hebrew month [`Mark`] to a rounded down hebrew year [`Mark`].
+///
+/// Month one is year one.
+///
+/// # Examples
+/// ```
+/// use timelane::hebrew::month_to_year;
+/// assert_eq!(month_to_year(0), 0);
+/// assert_eq!(month_to_year(1), 1);
+/// assert_eq!(month_to_year(12), 1);
+/// assert_eq!(month_to_year(13), 2);
+/// use timelane::Mark;
+/// assert_eq!(month_to_year(Mark::MIN), -745719441277620171);
+/// assert_eq!(month_to_year(Mark::MAX), 745719441277620172);
+/// ```
+pub const fn month_to_year(month: Mark) -> Mark {
+    let (zyear, _) = month_to_zyear_and_zmonth(month);
+    zyear + 1
+}
+
+/// Converts a hebrew month [`Mark`] to a rounded up hebrew year [`Mark`].
+///
+/// Month one is year one.
+///
+/// # Examples
+/// ```
+/// use timelane::hebrew::month_to_year_up;
+/// assert_eq!(month_to_year_up(0), 1);
+/// assert_eq!(month_to_year_up(1), 1);
+/// assert_eq!(month_to_year_up(2), 2);
+/// assert_eq!(month_to_year_up(13), 2);
+/// use timelane::Mark;
+/// assert_eq!(month_to_year_up(Mark::MIN), -745719441277620170);
+/// assert_eq!(month_to_year_up(Mark::MAX), 745719441277620173);
+/// ```
+pub const fn month_to_year_up(month: Mark) -> Mark {
+    let (zyear, zmonth_in_year) = month_to_zyear_and_zmonth(month);
+    zyear + 1 + (zmonth_in_year != 0) as Mark
+}
+
+/// Converts a hebrew month [`Mark`] to a day [`Mark`].
+///
+/// Month one, Tishrei of year one, is day -2103546, October 7th, 3761BC in the julian calendar.
+///
+/// # Examples
+/// ```
+/// use timelane::hebrew::month_to_day;
+/// assert_eq!(month_to_day(1), -2_103_546);
+/// assert_eq!(month_to_day(2), -2_103_516);
+/// use timelane::Mark;
+/// assert_eq!(month_to_day(-312332762234214182), Mark::MIN + 12);
+/// assert_eq!(month_to_day(312332762234356649), Mark::MAX - 25);
+/// ```
+pub const fn month_to_day(month: Mark) -> Mark {
+    let (zyear, zmonth_in_year) = month_to_zyear_and_zmonth(month);
+    let year = zyear as i128 + 1;
+    let start = new_year(year);
+    let days_in_year = (new_year(year + 1) - start) as Mark;
+    let mut zday_in_year = 0;
+    let mut zmonth = 0;
+    while zmonth < zmonth_in_year {
+        zday_in_year += days_in_zmonth(days_in_year, zmonth);
+        zmonth += 1;
+    }
+    // The year can start before the first day of the lane while the month does not
+    let day = start + zday_in_year as i128;
+    assert!(
+        day >= Mark::MIN as i128 && day <= Mark::MAX as i128,
+        "attempt to convert a hebrew month to a day with overflow"
+    );
+    day as Mark
+}
+
+/// Converts a day [`Mark`] to a rounded down hebrew month [`Mark`].
+///
+/// # Examples
+/// ```
+/// use timelane::hebrew::day_to_month;
+/// assert_eq!(day_to_month(-2_103_516), 2);
+/// assert_eq!(day_to_month(-2_103_517), 1);
+/// assert_eq!(day_to_month(-2_103_546), 1);
+/// assert_eq!(day_to_month(-2_103_547), 0);
+/// use timelane::Mark;
+/// assert_eq!(day_to_month(Mark::MIN), -312332762234214183);
+/// assert_eq!(day_to_month(Mark::MAX), 312332762234356649);
+/// ```
+pub const fn day_to_month(day: Mark) -> Mark {
+    let (month, _) = day_to_month_and_zday(day);
+    month
+}
+
+/// Converts a day [`Mark`] to a rounded up hebrew month [`Mark`].
+///
+/// # Examples
+/// ```
+/// use timelane::hebrew::day_to_month_up;
+/// assert_eq!(day_to_month_up(-2_103_516), 2);
+/// assert_eq!(day_to_month_up(-2_103_517), 2);
+/// assert_eq!(day_to_month_up(-2_103_545), 2);
+/// assert_eq!(day_to_month_up(-2_103_546), 1);
+/// use timelane::Mark;
+/// assert_eq!(day_to_month_up(Mark::MIN), -312332762234214182);
+/// assert_eq!(day_to_month_up(Mark::MAX), 312332762234356650);
+/// ```
+pub const fn day_to_month_up(day: Mark) -> Mark {
+    let (month, zday_in_month) = day_to_month_and_zday(day);
+    month + (zday_in_month != 0) as Mark
+}
+
+/// Returns whether a hebrew year [`Mark`] is a leap year of 13 months.
+///
+/// # Examples
+/// ```
+/// use timelane::hebrew::is_leap_year;
+/// assert!(is_leap_year(5784));
+/// assert!(!is_leap_year(5785));
+/// ```
+pub const fn is_leap_year(year: Mark) -> bool {
+    (7 * year.rem_euclid(19) + 1) % 19 < 7
+}
+
+/// Returns the number of days in a hebrew year [`Mark`]: 353, 354 or 355 days, or 383, 384 or 385
+/// days in leap years.
+///
+/// # Examples
+/// ```
+/// use timelane::hebrew::days_in_year;
+/// assert_eq!(days_in_year(5784), 383);
+/// assert_eq!(days_in_year(5785), 355);
+/// use timelane::Mark;
+/// assert_eq!(days_in_year(Mark::MIN), 355);
+/// assert_eq!(days_in_year(Mark::MAX), 385);
+/// ```
+pub const fn days_in_year(year: Mark) -> Mark {
+    let year = year as i128;
+    // The years of the whole lane start within the range of an i128, and last at most 385 days
+    (new_year(year + 1) - new_year(year)) as Mark
+}
+
+/// Returns the day [`Mark`] of a date with a one-based day of month, or `None` if the date does
+/// not exist.
+///
+/// Months of year are numbered from Nisan, like in the Torah: Nisan is 1, Elul 6, Tishrei 7,
+/// Shevat 11, Adar, or Adar I in leap years, 12 and Adar II 13. The year still starts with
+/// Tishrei, the 7th month.
+///
+/// # Examples
+/// ```
+/// use timelane::{hebrew, month_to_day, year_to_month};
+/// // Passover 5784 started on April 23rd, 2024
+/// let day = month_to_day(year_to_month(2024) + 3) + 22;
+/// assert_eq!(hebrew::date_to_day(5784, 1, 15), Some(day));
+/// assert_eq!(hebrew::date_to_day(5784, 13, 29), Some(day - 15));
+/// assert_eq!(hebrew::date_to_day(5785, 13, 1), None);
+/// ```
+pub const fn date_to_day(year: Mark, month: Mark, day: Mark) -> Option<Mark> {
+    let months_in_year = 12 + is_leap_year(year) as Mark;
+    if month < 1 || month > months_in_year || day < 1 {
+        return None;
+    }
+    let zmonth_in_year = if month >= 7 {
+        month - 7
+    } else {
+        month - 1 + months_in_year - 6
+    };
+    if day > days_in_zmonth(days_in_year(year), zmonth_in_year) {
+        return None;
+    }
+    Some(month_to_day(year_to_month(year) + zmonth_in_year) + day - 1)
+}
+
+/// Splits a day [`Mark`] into a hebrew year, a one-based month of year numbered from Nisan, like
+/// [`date_to_day`], and a one-based day of month.
+///
+/// # Examples
+/// ```
+/// use timelane::{hebrew, month_to_day, year_to_month};
+/// let day = month_to_day(year_to_month(2024) + 9) + 2;
+/// assert_eq!(hebrew::day_to_date(day), (5785, 7, 1));
+/// assert_eq!(hebrew::day_to_date(day - 1), (5784, 6, 29));
+/// ```
+pub const fn day_to_date(day: Mark) -> (Mark, Mark, Mark) {
+    let (month, zday_in_month) = day_to_month_and_zday(day);
+    let (zyear, zmonth_in_year) = month_to_zyear_and_zmonth(month);
+    let year = zyear + 1;
+    let months_in_year = 12 + is_leap_year(year) as Mark;
+    let month_of_year = if zmonth_in_year < months_in_year - 6 {
+        zmonth_in_year + 7
+    } else {
+        zmonth_in_year - (months_in_year - 6) + 1
+    };
+    (year, month_of_year, zday_in_month + 1)
+}
+
+/// Returns the number of months before a zero-based year of the 19 years cycle.
+const fn months_before_zyear_in_cycle(zyear_in_cycle: Mark) -> Mark {
+    (235 * zyear_in_cycle + 1) / 19
+}
+
+/// Splits a hebrew month [`Mark`] into a zero-based year and the zero-based month in that year.
+const fn month_to_zyear_and_zmonth(month: Mark) -> (Mark, Mark) {
+    // Month one starts the cycles, it is taken from the month in the cycle, not from the month
+    let mut cycle = month.div_euclid(MONTHS_IN_19_YEARS);
+    let mut zmonth_in_cycle = month.rem_euclid(MONTHS_IN_19_YEARS) - 1;
+    let is_before_cycle = (zmonth_in_cycle < 0) as Mark;
+    cycle -= is_before_cycle;
+    zmonth_in_cycle += is_before_cycle * MONTHS_IN_19_YEARS;
+    let zyear_in_cycle = (19 * zmonth_in_cycle + 17) / MONTHS_IN_19_YEARS;
+    (
+        cycle * 19 + zyear_in_cycle,
+        zmonth_in_cycle - months_before_zyear_in_cycle(zyear_in_cycle),
+    )
+}
+
+/// Returns the days from the epoch to the day of the molad of Tishrei of a year, postponed when
+/// the molad is after noon or when the day is a Sunday, a Wednesday or a Friday.
+///
+/// This is the formula of Reingold and Dershowitz, "Calendrical Calculations": the molad is
+/// counted from 6 hours after the molad of year one, so that a molad after noon falls on the
+/// next day.
+const fn elapsed_days(year: i128) -> i128 {
+    let months_elapsed = (235 * year - 234).div_euclid(19);
+    // The molad of year one was 5 hours and 204 parts into a Monday, a month lasts 29 days, 12
+    // hours and 793 parts
+    let parts_elapsed = 12_084 + 13_753 * months_elapsed;
+    let days = 29 * months_elapsed + parts_elapsed.div_euclid(PARTS_IN_DAY);
+    if (3 * (days + 1)).rem_euclid(7) < 3 {
+        days + 1
+    } else {
+        days
+    }
+}
+
+/// Returns the day of Tishrei 1st of a year, with the postponements keeping the years from being
+/// 356 or 382 days long.
+const fn new_year(year: i128) -> i128 {
+    let days = elapsed_days(year);
+    let delay = if elapsed_days(year + 1) - days == 356 {
+        2
+    } else if days - elapsed_days(year - 1) == 382 {
+        1
+    } else {
+        0
+    };
+    EPOCH + days + delay
+}
+
+/// Returns the number of days in a zero-based month of a year of `days_in_year` days.
+const fn days_in_zmonth(days_in_year: Mark, zmonth_in_year: Mark) -> Mark {
+    let is_leap_year = days_in_year > 355;
+    match zmonth_in_year {
+        // Heshvan is long in complete years of 355 or 385 days
+        1 if days_in_year % 10 == 5 => 30,
+        // Kislev is short in deficient years of 353 or 383 days
+        2 if days_in_year % 10 == 3 => 29,
+        1 => 29,
+        2 => 30,
+        // Adar I has 30 days
+        5 if is_leap_year => 30,
+        // The months alternate 30 and 29 days after Adar I
+        zmonth if is_leap_year && zmonth > 5 => 29 + zmonth % 2,
+        zmonth => 30 - zmonth % 2,
+    }
+}
+
+/// Splits a day [`Mark`] into a hebrew month [`Mark`] and the zero-based day in that month.
+const fn day_to_month_and_zday(day: Mark) -> (Mark, Mark) {
+    // The average year lasts 35975351 / 98496 days, which gives the year or the next one
+    let mut year = ((day as i128 - EPOCH) * 98_496).div_euclid(35_975_351);
+    while new_year(year + 1) <= day as i128 {
+        year += 1;
+    }
+    let start = new_year(year);
+    let days_in_year = (new_year(year + 1) - start) as Mark;
+    let mut zday_in_month = (day as i128 - start) as Mark;
+    let mut zmonth_in_year = 0;
+    while zday_in_month >= days_in_zmonth(days_in_year, zmonth_in_year) {
+        zday_in_month -= days_in_zmonth(days_in_year, zmonth_in_year);
+        zmonth_in_year += 1;
+    }
+    (year_to_month(year as Mark) + zmonth_in_year, zday_in_month)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::civil::day_to_weekday;
+    use crate::reference_calendar::rata_die_to_day;
+
+    /// Dates as a Rata Die and a hebrew year, month and day, with months numbered from Nisan.
+    const RATA_DIE_DATES: [(Mark, Mark, Mark, Mark); 33] = [
+        (-214_193, 3174, 5, 10),
+        (-61_387, 3593, 9, 25),
+        (25_469, 3831, 7, 3),
+        (49_217, 3896, 7, 9),
+        (171_307, 4230, 10, 18),
+        (210_155, 4336, 3, 4),
+        (253_427, 4455, 8, 13),
+        (369_740, 4773, 2, 6),
+        (400_085, 4856, 2, 23),
+        (434_355, 4950, 1, 7),
+        (452_605, 5000, 13, 8),
+        (470_160, 5048, 1, 21),
+        (473_837, 5058, 2, 7),
+        (507_850, 5151, 4, 1),
+        (524_156, 5196, 11, 7),
+        (544_676, 5252, 1, 3),
+        (567_118, 5314, 7, 1),
+        (569_477, 5320, 12, 27),
+        (601_716, 5408, 3, 20),
+        (613_424, 5440, 4, 3),
+        (626_596, 5476, 5, 5),
+        (645_554, 5528, 4, 4),
+        (664_224, 5579, 5, 11),
+        (671_401, 5599, 1, 12),
+        (694_799, 5663, 1, 22),
+        (704_424, 5689, 5, 19),
+        (708_842, 5702, 7, 8),
+        (709_409, 5703, 1, 14),
+        (709_580, 5704, 7, 8),
+        (727_274, 5752, 13, 12),
+        (728_714, 5756, 12, 5),
+        (744_313, 5799, 8, 12),
+        (764_652, 5854, 5, 5),
+    ];
+
+    /// Returns the day of Tishrei 1st of a year, with each postponement rule spelled out.
+    fn naive_new_year(year: i128) -> i128 {
+        let is_leap_year = |year: i128| (7 * year + 1).rem_euclid(19) < 7;
+        let months_elapsed = (1..year)
+            .map(|year| 12 + is_leap_year(year) as i128)
+            .sum::<i128>()
+            - (year..1)
+                .map(|year| 12 + is_leap_year(year) as i128)
+                .sum::<i128>();
+        // The molad of year one, in parts from the start of the Sunday before, at 6PM
+        let molad = 25_920 + 5 * 1080 + 204 + months_elapsed * (29 * 25_920 + 12 * 1080 + 793);
+        let (mut day, parts) = (molad.div_euclid(25_920), molad.rem_euclid(25_920));
+        let weekday = day.rem_euclid(7);
+        if parts >= 18 * 1080 {
+            // Molad zaken: the molad is after noon
+            day += 1;
+        } else if !is_leap_year(year) && weekday == 2 && parts >= 9 * 1080 + 204 {
+            // Gatarad: a common year would last 356 days
+            day += 1;
+        } else if is_leap_year(year - 1) && weekday == 1 && parts >= 15 * 1080 + 589 {
+            // Betutakpat: the previous leap year would last 382 days
+            day += 1;
+        }
+        if matches!(day.rem_euclid(7), 0 | 3 | 5) {
+            // Lo ADU Rosh: Rosh Hashanah is never a Sunday, a Wednesday or a Friday
+            day += 1;
+        }
+        EPOCH + day - 1
+    }
+
+    #[test]
+    fn scalers_match_published_dates() {
+        for (rata_die, year, month, day) in RATA_DIE_DATES {
+            let mark = rata_die_to_day(rata_die);
+            assert_eq!(date_to_day(year, month, day), Some(mark), "{}", rata_die);
+            assert_eq!(day_to_date(mark), (year, month, day), "{}", rata_die);
+        }
+        // The years 5761 and 5785 started on September 30th, 2000 and October 3rd, 2024
+        assert_eq!(month_to_day(year_to_month(5761)), 274);
+        assert_eq!(month_to_day(year_to_month(5785)), 9043);
+        assert_eq!(EPOCH, rata_die_to_day(-1_373_427) as i128);
+    }
+
+    #[test]
+    fn scalers_match_the_postponement_rules_for_every_year() {
+        let mut naive_start = naive_new_year(-2_000);
+        for year in -2_000..12_000 {
+            let naive_next_start = naive_new_year(year as i128 + 1);
+            let start = month_to_day(year_to_month(year));
+            assert_eq!(start as i128, naive_start, "{}", year);
+            assert!(matches!(day_to_weekday(start), 1 | 2 | 4 | 6), "{}", year);
+            let days = days_in_year(year);
+            assert_eq!(days as i128, naive_next_start - naive_start, "{}", year);
+            let lengths: &[Mark] = if is_leap_year(year) {
+                &[383, 384, 385]
+            } else {
+                &[353, 354, 355]
+            };
+            assert!(lengths.contains(&days), "{}", year);
+            let months = year_to_month(year)..year_to_month(year + 1);
+            assert_eq!(months.len(), 12 + is_leap_year(year) as usize, "{}", year);
+            let mut first_day_of_month = start;
+            for month in months {
+                assert_eq!(month_to_day(month), first_day_of_month, "{}", month);
+                assert_eq!(day_to_month(first_day_of_month), month, "{}", month);
+                assert_eq!(day_to_month_up(first_day_of_month), month, "{}", month);
+                assert_eq!(month_to_year(month), year);
+                let next = month_to_day(month + 1);
+                assert!(matches!(next - first_day_of_month, 29 | 30), "{}", month);
+                assert_eq!(day_to_month(next - 1), month, "{}", month);
+                assert_eq!(day_to_month_up(next - 1), month + 1, "{}", month);
+                let (date_year, date_month, date_day) = day_to_date(next - 1);
+                assert_eq!(date_year, year);
+                assert_eq!(date_day, next - first_day_of_month);
+                assert_eq!(date_to_day(year, date_month, date_day), Some(next - 1));
+                first_day_of_month = next;
+            }
+            assert_eq!(first_day_of_month as i128, naive_next_start, "{}", year);
+            naive_start = naive_next_start;
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to convert a hebrew month to a day with overflow")]
+    fn months_after_the_end_of_the_lane_overflow() {
+        month_to_day(Mark::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to convert a hebrew month to a day with overflow")]
+    fn months_before_the_start_of_the_lane_overflow() {
+        month_to_day(day_to_month(Mark::MIN));
+    }
+
+    #[test]
+    fn invalid_dates_have_no_day() {
+        assert_eq!(date_to_day(5785, 13, 1), None);
+        assert!(date_to_day(5784, 13, 1).is_some());
+        // 5785 is a complete year, where Heshvan has 30 days, 5781 is a deficient one
+        assert!(date_to_day(5785, 8, 30).is_some());
+        assert_eq!(date_to_day(5781, 9, 30), None);
+        assert_eq!(date_to_day(5785, 2, 30), None);
+        assert_eq!(date_to_day(5785, 0, 1), None);
+        assert_eq!(date_to_day(5785, 14, 1), None);
+        assert_eq!(date_to_day(5785, 1, 0), None);
+    }
+}
@@ -0,0 +1,539 @@
+//! [`crate::Scaler`] functions for the tabular islamic calendar, also known as the arithmetic
+//! hijri calendar.
+//!
+//! The tabular calendar replaces the observation of the crescent moon by a cycle of 30 lunar
+//! years: 19 common years of 354 days and 11 leap years of 355 days. Months alternate between 30
+//! and 29 days, starting with Muharram, and the last month, Dhu al-Hijjah, gets one more day in
+//! leap years.
+//!
+//! Year marks are the years of the hijra, with a year zero before year one. Month one is Muharram
+//! of year one. The free functions of this module use the [`Variant::CIVIL`] calendar, the most
+//! common one, while the other variants in use are available from [`Variant`].
+//!
+//! # Examples
+//! ```
+//! use timelane::{islamic, month_to_day, year_to_month};
+//! // The first day of year 1421 is April 6th, 2000
+//! let muharram_1421 = islamic::year_to_month(1421);
+//! assert_eq!(islamic::month_to_day(muharram_1421), month_to_day(year_to_month(2000) + 3) + 5);
+//! ```
+use crate::{julian, Mark, EPOCH_YEAR};
+
+/// The number of days in 30 tabular years, after which leap years repeat.
+const DAYS_IN_30_YEARS: Mark = 30 * 354 + 11;
+
+/// Converts a year [`Mark`] of the hijra to an islamic month [`Mark`].
+///
+/// Year one is month one.
+///
+/// # Examples
+/// ```
+/// use timelane::islamic::year_to_month;
+/// assert_eq!(year_to_month(0), -11);
+/// assert_eq!(year_to_month(1), 1);
+/// assert_eq!(year_to_month(2), 13);
+/// use timelane::Mark;
+/// assert_eq!(year_to_month(-768614336404564649), Mark::MIN + 9);
+/// assert_eq!(year_to_month(768614336404564651), Mark::MAX - 6);
+/// ```
+pub const fn year_to_month(year: Mark) -> Mark {
+    let zyear = year - 1;
+    zyear * 12 + 1
+}
+
+/// Converts an islamic month [`Mark`] to a rounded down year [`Mark`] of the hijra.
+///
+/// Month one is year one.
+///
+/// # Examples
+/// ```
+/// use timelane::islamic::month_to_year;
+/// assert_eq!(month_to_year(0), 0);
+/// assert_eq!(month_to_year(1), 1);
+/// assert_eq!(month_to_year(12), 1);
+/// assert_eq!(month_to_year(13), 2);
+/// use timelane::Mark;
+/// assert_eq!(month_to_year(Mark::MIN), -768614336404564650);
+/// assert_eq!(month_to_year(Mark::MAX), 768614336404564651);
+/// ```
+pub const fn month_to_year(month: Mark) -> Mark {
+    crate::month_to_year(month) - EPOCH_YEAR + 1
+}
+
+/// Converts an islamic month [`Mark`] to a rounded up year [`Mark`] of the hijra.
+///
+/// Month one is year one.
+///
+/// # Examples
+/// ```
+/// use timelane::islamic::month_to_year_up;
+/// assert_eq!(month_to_year_up(0), 1);
+/// assert_eq!(month_to_year_up(1), 1);
+/// assert_eq!(month_to_year_up(2), 2);
+/// assert_eq!(month_to_year_up(13), 2);
+/// use timelane::Mark;
+/// assert_eq!(month_to_year_up(Mark::MIN), -768614336404564649);
+/// assert_eq!(month_to_year_up(Mark::MAX), 768614336404564652);
+/// ```
+pub const fn month_to_year_up(month: Mark) -> Mark {
+    crate::month_to_year_up(month) - EPOCH_YEAR + 1
+}
+
+/// Converts an islamic month [`Mark`] to a day [`Mark`], in the [`Variant::CIVIL`] calendar.
+///
+/// Month one, the first month of the hijra, is day -503104, Friday July 16th, 622 in the julian
+/// calendar.
+///
+/// # Examples
+/// ```
+/// use timelane::islamic::month_to_day;
+/// assert_eq!(month_to_day(1), -503_104);
+/// assert_eq!(month_to_day(2), -503_074);
+/// assert_eq!(month_to_day(3), -503_045);
+/// use timelane::Mark;
+/// assert_eq!(month_to_day(-312333170281962013), Mark::MIN + 7);
+/// assert_eq!(month_to_day(312333170281996088), Mark::MAX - 19);
+/// ```
+pub const fn month_to_day(month: Mark) -> Mark {
+    Variant::CIVIL.month_to_day(month)
+}
+
+/// Converts a day [`Mark`] to a rounded down islamic month [`Mark`], in the [`Variant::CIVIL`]
+/// calendar.
+///
+/// # Examples
+/// ```
+/// use timelane::islamic::day_to_month;
+/// assert_eq!(day_to_month(-503_074), 2);
+/// assert_eq!(day_to_month(-503_075), 1);
+/// assert_eq!(day_to_month(-503_104), 1);
+/// assert_eq!(day_to_month(-503_105), 0);
+/// use timelane::Mark;
+/// assert_eq!(day_to_month(Mark::MIN), -312333170281962014);
+/// assert_eq!(day_to_month(Mark::MAX), 312333170281996088);
+/// ```
+pub const fn day_to_month(day: Mark) -> Mark {
+    Variant::CIVIL.day_to_month(day)
+}
+
+/// Converts a day [`Mark`] to a rounded up islamic month [`Mark`], in the [`Variant::CIVIL`]
+/// calendar.
+///
+/// # Examples
+/// ```
+/// use timelane::islamic::day_to_month_up;
+/// assert_eq!(day_to_month_up(-503_074), 2);
+/// assert_eq!(day_to_month_up(-503_075), 2);
+/// assert_eq!(day_to_month_up(-503_103), 2);
+/// assert_eq!(day_to_month_up(-503_104), 1);
+/// use timelane::Mark;
+/// assert_eq!(day_to_month_up(Mark::MIN), -312333170281962013);
+/// assert_eq!(day_to_month_up(Mark::MAX), 312333170281996089);
+/// ```
+pub const fn day_to_month_up(day: Mark) -> Mark {
+    Variant::CIVIL.day_to_month_up(day)
+}
+
+/// The years of the 30 years cycle that are leap years, in the variants of the tabular calendar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Intercalation {
+    /// The years 2, 5, 7, 10, 13, 15, 18, 21, 24, 26 and 29.
+    Fifteenth,
+    /// The years 2, 5, 7, 10, 13, 16, 18, 21, 24, 26 and 29, the most widespread variant.
+    Sixteenth,
+    /// The years 2, 5, 8, 10, 13, 16, 19, 21, 24, 27 and 29, of the Fatimid calendar still used
+    /// by the Bohras.
+    Fatimid,
+    /// The years 2, 5, 8, 11, 13, 16, 19, 21, 24, 27 and 30, of the astronomer Habash al-Hasib.
+    HabashAlHasib,
+}
+
+impl Intercalation {
+    /// Returns the leap years of the cycle as bits, from the lowest bit for the first year.
+    const fn leap_years(self) -> u32 {
+        let years: [u32; 11] = match self {
+            Intercalation::Fifteenth => [2, 5, 7, 10, 13, 15, 18, 21, 24, 26, 29],
+            Intercalation::Sixteenth => [2, 5, 7, 10, 13, 16, 18, 21, 24, 26, 29],
+            Intercalation::Fatimid => [2, 5, 8, 10, 13, 16, 19, 21, 24, 27, 29],
+            Intercalation::HabashAlHasib => [2, 5, 8, 11, 13, 16, 19, 21, 24, 27, 30],
+        };
+        let mut bits = 0;
+        let mut index = 0;
+        while index < years.len() {
+            bits |= 1 << (years[index] - 1);
+            index += 1;
+        }
+        bits
+    }
+}
+
+/// The day starting the first year of the hijra, in the variants of the tabular calendar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Epoch {
+    /// Thursday July 15th, 622 in the julian calendar, used by astronomers.
+    Thursday,
+    /// Friday July 16th, 622 in the julian calendar, used for civil dates.
+    Friday,
+}
+
+impl Epoch {
+    /// Returns the day [`Mark`] of the epoch.
+    pub const fn day(self) -> Mark {
+        let july_622 = julian::month_to_day(crate::year_to_month(622) + 6);
+        match self {
+            Epoch::Thursday => july_622 + 14,
+            Epoch::Friday => july_622 + 15,
+        }
+    }
+}
+
+/// A variant of the tabular islamic calendar, with its leap years and its epoch.
+///
+/// # Examples
+/// ```
+/// use timelane::islamic::{year_to_month, Epoch, Intercalation, Variant};
+/// let fatimid = Variant::new(Intercalation::Fatimid, Epoch::Thursday);
+/// assert!(fatimid.is_leap_year(8));
+/// assert!(!Variant::CIVIL.is_leap_year(8));
+/// // The leap years 7 and 8 compensate, but the epoch is one day earlier
+/// let day = Variant::CIVIL.month_to_day(year_to_month(9));
+/// assert_eq!(fatimid.month_to_day(year_to_month(9)), day - 1);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Variant {
+    leap_years: u32,
+    epoch: Mark,
+}
+
+impl Variant {
+    /// The [`Intercalation::Sixteenth`] leap years with the [`Epoch::Friday`], the calendar of
+    /// the `islamic-civil` locales and of most software.
+    pub const CIVIL: Variant = Variant::new(Intercalation::Sixteenth, Epoch::Friday);
+
+    /// The [`Intercalation::Sixteenth`] leap years with the [`Epoch::Thursday`], the calendar of
+    /// the `islamic-tbla` locales.
+    pub const ASTRONOMICAL: Variant = Variant::new(Intercalation::Sixteenth, Epoch::Thursday);
+
+    /// Returns the variant with these leap years and this epoch.
+    pub const fn new(intercalation: Intercalation, epoch: Epoch) -> Variant {
+        Variant {
+            leap_years: intercalation.leap_years(),
+            epoch: epoch.day(),
+        }
+    }
+
+    /// Returns whether a year [`Mark`] of the hijra is a leap year of 355 days.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::islamic::Variant;
+    /// assert!(Variant::CIVIL.is_leap_year(1445));
+    /// assert!(!Variant::CIVIL.is_leap_year(1446));
+    /// ```
+    pub const fn is_leap_year(self, year: Mark) -> bool {
+        self.is_leap_zyear_in_cycle((year.rem_euclid(30) + 29) % 30)
+    }
+
+    /// Converts an islamic month [`Mark`] to a day [`Mark`].
+    pub const fn month_to_day(self, month: Mark) -> Mark {
+        let zmonth = month - 1;
+        let zmonth_in_cycle = zmonth.rem_euclid(360);
+        let zyear_in_cycle = zmonth_in_cycle / 12;
+        let zmonth_in_year = zmonth_in_cycle % 12;
+        // The cycles start on multiples of the cycle length, the epoch is folded into the day in
+        // the cycle, which can exceed one cycle
+        let cycle = zmonth.div_euclid(360) + self.epoch.div_euclid(DAYS_IN_30_YEARS);
+        let day_in_cycle = self.epoch.rem_euclid(DAYS_IN_30_YEARS)
+            + zyear_in_cycle * 354
+            + self.leap_days_before_zyear_in_cycle(zyear_in_cycle)
+            + (59 * zmonth_in_year + 1) / 2;
+        // Negative cycles lend two cycles of days to the day in the cycle, which holds the epoch
+        let is_negative = (cycle < 0) as Mark;
+        (cycle + 2 * is_negative) * DAYS_IN_30_YEARS
+            + (day_in_cycle - 2 * is_negative * DAYS_IN_30_YEARS)
+    }
+
+    /// Converts a day [`Mark`] to a rounded down islamic month [`Mark`].
+    pub const fn day_to_month(self, day: Mark) -> Mark {
+        let (month, _) = self.day_to_month_and_zday(day);
+        month
+    }
+
+    /// Converts a day [`Mark`] to a rounded up islamic month [`Mark`].
+    pub const fn day_to_month_up(self, day: Mark) -> Mark {
+        let (month, zday_in_month) = self.day_to_month_and_zday(day);
+        month + (zday_in_month != 0) as Mark
+    }
+
+    /// Returns the day [`Mark`] of a date with a one-based month of year and day of month, or
+    /// `None` if the date does not exist or its month does not end in the lane.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::islamic::Variant;
+    /// use timelane::{month_to_day, year_to_month};
+    /// // Ramadan 1st, 1445 is March 11th, 2024
+    /// let day = month_to_day(year_to_month(2024) + 2) + 10;
+    /// assert_eq!(Variant::CIVIL.date_to_day(1445, 9, 1), Some(day));
+    /// assert_eq!(Variant::CIVIL.date_to_day(1445, 9, 30), Some(day + 29));
+    /// assert_eq!(Variant::CIVIL.date_to_day(1445, 10, 30), None);
+    /// ```
+    pub const fn date_to_day(self, year: Mark, month: Mark, day: Mark) -> Option<Mark> {
+        if month < 1 || month > 12 || day < 1 {
+            return None;
+        }
+        let zyear = match year.checked_sub(1) {
+            Some(zyear) => zyear.checked_mul(12),
+            None => None,
+        };
+        let month = match zyear {
+            Some(zyear) => zyear.checked_add(month),
+            None => None,
+        };
+        // The month and the next one must start in the lane
+        let month = match month {
+            Some(month)
+                if month >= self.day_to_month_up(Mark::MIN)
+                    && month < self.day_to_month(Mark::MAX) =>
+            {
+                month
+            }
+            _ => return None,
+        };
+        let start = self.month_to_day(month);
+        if day > self.month_to_day(month + 1) - start {
+            return None;
+        }
+        Some(start + day - 1)
+    }
+
+    /// Splits a day [`Mark`] into a year of the hijra, a one-based month of year and a one-based
+    /// day of month.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::islamic::Variant;
+    /// use timelane::{month_to_day, year_to_month};
+    /// let day = month_to_day(year_to_month(2024) + 2) + 10;
+    /// assert_eq!(Variant::CIVIL.day_to_date(day), (1445, 9, 1));
+    /// assert_eq!(Variant::ASTRONOMICAL.day_to_date(day), (1445, 9, 2));
+    /// ```
+    pub const fn day_to_date(self, day: Mark) -> (Mark, Mark, Mark) {
+        let (month, zday_in_month) = self.day_to_month_and_zday(day);
+        let year = month_to_year(month);
+        (year, month - year_to_month(year) + 1, zday_in_month + 1)
+    }
+
+    /// Returns the number of leap years before a zero-based year of the 30 years cycle.
+    const fn leap_days_before_zyear_in_cycle(self, zyear_in_cycle: Mark) -> Mark {
+        (self.leap_years & ((1 << zyear_in_cycle) - 1)).count_ones() as Mark
+    }
+
+    /// Splits a day [`Mark`] into an islamic month [`Mark`] and the zero-based day in that month.
+    const fn day_to_month_and_zday(self, day: Mark) -> (Mark, Mark) {
+        // The offset of the epoch in its cycle is taken from the day in the cycle, not the day
+        let mut cycle = day.div_euclid(DAYS_IN_30_YEARS);
+        let mut zday_in_cycle =
+            day.rem_euclid(DAYS_IN_30_YEARS) - self.epoch.rem_euclid(DAYS_IN_30_YEARS);
+        let is_before_epoch = (zday_in_cycle < 0) as Mark;
+        cycle -= is_before_epoch + self.epoch.div_euclid(DAYS_IN_30_YEARS);
+        zday_in_cycle += is_before_epoch * DAYS_IN_30_YEARS;
+        // Rounding the years of 354 days gives the year or the next one, which starts later
+        let mut zyear_in_cycle = zday_in_cycle / 354;
+        let mut zday_in_year = zday_in_cycle
+            - zyear_in_cycle * 354
+            - self.leap_days_before_zyear_in_cycle(zyear_in_cycle);
+        if zday_in_year < 0 {
+            zyear_in_cycle -= 1;
+            zday_in_year += 354 + self.is_leap_zyear_in_cycle(zyear_in_cycle) as Mark;
+        }
+        // Months alternate 30 and 29 days, the leap day extends the last one
+        let zmonth_in_year = if zday_in_year < 354 {
+            2 * zday_in_year / 59
+        } else {
+            11
+        };
+        let zday_in_month = zday_in_year - (59 * zmonth_in_year + 1) / 2;
+        let month = cycle * 360 + zyear_in_cycle * 12 + zmonth_in_year + 1;
+        (month, zday_in_month)
+    }
+
+    /// Returns whether a zero-based year of the 30 years cycle is a leap year.
+    const fn is_leap_zyear_in_cycle(self, zyear_in_cycle: Mark) -> bool {
+        self.leap_years & (1 << zyear_in_cycle) != 0
+    }
+}
+
+impl Default for Variant {
+    /// Returns [`Variant::CIVIL`].
+    fn default() -> Self {
+        Variant::CIVIL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reference_calendar::rata_die_to_day;
+    use crate::test_util::assert_month_walk;
+
+    /// Dates as a Rata Die and an islamic year, month and day of the [`Variant::CIVIL`] calendar.
+    const RATA_DIE_DATES: [(Mark, Mark, Mark, Mark); 33] = [
+        (-214_193, -1245, 12, 9),
+        (-61_387, -813, 2, 23),
+        (25_469, -568, 4, 1),
+        (49_217, -501, 4, 6),
+        (171_307, -157, 10, 17),
+        (210_155, -47, 6, 3),
+        (253_427, 75, 7, 13),
+        (369_740, 403, 10, 5),
+        (400_085, 489, 5, 22),
+        (434_355, 586, 2, 7),
+        (452_605, 637, 8, 7),
+        (470_160, 687, 2, 20),
+        (473_837, 697, 7, 7),
+        (507_850, 793, 7, 1),
+        (524_156, 839, 7, 6),
+        (544_676, 897, 6, 1),
+        (567_118, 960, 9, 30),
+        (569_477, 967, 5, 27),
+        (601_716, 1058, 5, 18),
+        (613_424, 1091, 6, 2),
+        (626_596, 1128, 8, 4),
+        (645_554, 1182, 2, 3),
+        (664_224, 1234, 10, 10),
+        (671_401, 1255, 1, 11),
+        (694_799, 1321, 1, 21),
+        (704_424, 1348, 3, 19),
+        (708_842, 1360, 9, 8),
+        (709_409, 1362, 4, 13),
+        (709_580, 1362, 10, 7),
+        (727_274, 1412, 9, 13),
+        (728_714, 1416, 10, 5),
+        (744_313, 1460, 10, 12),
+        (764_652, 1518, 3, 5),
+    ];
+
+    const VARIANTS: [Variant; 8] = [
+        Variant::new(Intercalation::Fifteenth, Epoch::Thursday),
+        Variant::new(Intercalation::Fifteenth, Epoch::Friday),
+        Variant::new(Intercalation::Sixteenth, Epoch::Thursday),
+        Variant::new(Intercalation::Sixteenth, Epoch::Friday),
+        Variant::new(Intercalation::Fatimid, Epoch::Thursday),
+        Variant::new(Intercalation::Fatimid, Epoch::Friday),
+        Variant::new(Intercalation::HabashAlHasib, Epoch::Thursday),
+        Variant::new(Intercalation::HabashAlHasib, Epoch::Friday),
+    ];
+
+    /// Returns the number of days in a month of year, with the leap years of the cycle spelled
+    /// out as one-based years.
+    fn days_in_month(leap_years: &[Mark], year: Mark, month: Mark) -> Mark {
+        let is_leap_year = leap_years.contains(&((year - 1).rem_euclid(30) + 1));
+        if month == 12 && is_leap_year {
+            30
+        } else {
+            30 - (month + 1) % 2
+        }
+    }
+
+    fn leap_years(intercalation: Intercalation) -> Vec<Mark> {
+        (1..=30)
+            .filter(|year| intercalation.leap_years() & (1 << (year - 1)) != 0)
+            .collect()
+    }
+
+    #[test]
+    fn scalers_match_published_dates() {
+        for (rata_die, year, month, day) in RATA_DIE_DATES {
+            let mark = rata_die_to_day(rata_die);
+            let month_mark = year_to_month(year) + month - 1;
+            assert_eq!(month_to_day(month_mark) + day - 1, mark, "{}", rata_die);
+            assert_eq!(day_to_month(mark), month_mark, "{}", rata_die);
+            assert_eq!(Variant::CIVIL.day_to_date(mark), (year, month, day));
+            assert_eq!(Variant::CIVIL.date_to_day(year, month, day), Some(mark));
+        }
+        // The hijra started on julian July 16th, 622, and year 1421 on April 6th, 2000
+        assert_eq!(Epoch::Friday.day(), rata_die_to_day(227_015));
+        assert_eq!(month_to_day(year_to_month(1421)), 97);
+        assert_eq!(Variant::ASTRONOMICAL.day_to_date(96), (1421, 1, 1));
+    }
+
+    #[test]
+    fn intercalations_have_11_leap_years() {
+        for intercalation in [
+            Intercalation::Fifteenth,
+            Intercalation::Sixteenth,
+            Intercalation::Fatimid,
+            Intercalation::HabashAlHasib,
+        ] {
+            assert_eq!(intercalation.leap_years().count_ones(), 11);
+            assert_eq!(intercalation.leap_years() >> 30, 0);
+        }
+    }
+
+    #[test]
+    fn scalers_match_a_walk_over_thousands_of_years() {
+        let years = 10_000;
+        for variant in VARIANTS {
+            let intercalation = [
+                Intercalation::Fifteenth,
+                Intercalation::Sixteenth,
+                Intercalation::Fatimid,
+                Intercalation::HabashAlHasib,
+            ]
+            .into_iter()
+            .find(|intercalation| intercalation.leap_years() == variant.leap_years)
+            .unwrap();
+            let leap_years = leap_years(intercalation);
+            let days_in_month = |month: Mark| {
+                days_in_month(
+                    &leap_years,
+                    month_to_year(month),
+                    (month - 1).rem_euclid(12) + 1,
+                )
+            };
+            assert_month_walk(
+                years * 12,
+                variant.epoch,
+                days_in_month,
+                |month| variant.month_to_day(month),
+                |day| variant.day_to_month(day),
+                |day| variant.day_to_month_up(day),
+            );
+            for month in 1..=years * 12 {
+                let year = month_to_year(month);
+                let days = days_in_month(month);
+                let last_day_of_month = variant.month_to_day(month) + days - 1;
+                assert_eq!(
+                    variant.day_to_date(last_day_of_month),
+                    (year, (month - 1) % 12 + 1, days)
+                );
+                assert_eq!(variant.is_leap_year(year), days_in_month(year * 12) == 30);
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_dates_have_no_day() {
+        assert_eq!(Variant::CIVIL.date_to_day(1446, 12, 30), None);
+        assert!(Variant::CIVIL.date_to_day(1445, 12, 30).is_some());
+        assert_eq!(Variant::CIVIL.date_to_day(1445, 2, 30), None);
+        assert_eq!(Variant::CIVIL.date_to_day(1445, 0, 1), None);
+        assert_eq!(Variant::CIVIL.date_to_day(1445, 13, 1), None);
+        assert_eq!(Variant::CIVIL.date_to_day(1445, 1, 0), None);
+    }
+
+    #[test]
+    fn dates_outside_of_the_lane_have_no_day() {
+        for variant in VARIANTS {
+            for year in [Mark::MIN, Mark::MIN / 12, Mark::MAX / 12, Mark::MAX] {
+                assert_eq!(variant.date_to_day(year, 1, 1), None, "{}", year);
+                assert_eq!(variant.date_to_day(year, 12, 1), None, "{}", year);
+            }
+            for day in [Mark::MIN + 60, Mark::MAX - 60] {
+                let (year, month, day_of_month) = variant.day_to_date(day);
+                assert_eq!(variant.date_to_day(year, month, day_of_month), Some(day));
+            }
+        }
+    }
+}
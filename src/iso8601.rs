@@ -0,0 +1,810 @@
+//! Parse RFC 3339 and ISO 8601 date and time representations into [`crate::Mark`], and format
+//! [`crate::Mark`] as RFC 3339.
+//!
+//! The parsers are `const` functions and do not allocate. On failure they return a
+//! [`ParseError`] holding the byte offset where the input stopped making sense.
+//!
+//! A second of `60` is only accepted when [`crate::LEAP_SECONDS_MARKS`] declares a leap second
+//! at the end of that minute, after taking the UTC offset in account.
+//!
+//! Fractions of a second are rounded down to the nanosecond.
+use crate::civil::{
+    days_in_month, days_in_year, iso_week_date_to_day, weeks_in_iso_year, year_month_day_to_day,
+    year_ordinal_to_day,
+};
+use crate::lane::Lane;
+use crate::parse::{
+    check_range, check_year, count_digits, digits, expect, expect_end, expect_ignore_case,
+    fraction, peek, DateTimeFields, ParseError, ParseErrorKind,
+};
+use crate::strftime::{Formatted, Pattern};
+use crate::Mark;
+
+const RFC3339: Pattern = crate::strftime!("%Y-%m-%dT%H:%M:%SZ");
+const RFC3339_MILLISECOND: Pattern = crate::strftime!("%Y-%m-%dT%H:%M:%S.%3NZ");
+const RFC3339_MICROSECOND: Pattern = crate::strftime!("%Y-%m-%dT%H:%M:%S.%6NZ");
+const RFC3339_NANOSECOND: Pattern = crate::strftime!("%Y-%m-%dT%H:%M:%S.%NZ");
+
+/// Formats a [`Mark`] of a lane as an RFC 3339 date and time in UTC.
+///
+/// The fraction of a second has three, six or nine digits, or is left out, whichever is the
+/// shortest one holding all the nanoseconds of the mark. Leap seconds are formatted as second
+/// `60`, so that [`parse_rfc3339_in`] gives back the same mark.
+///
+/// RFC 3339 only has the years 0000 to 9999: other years are formatted with more digits or a
+/// sign, which cannot be parsed back.
+///
+/// # Examples
+/// ```
+/// use timelane::iso8601::format_rfc3339;
+/// use timelane::lane::{Day, Millisecond, Second};
+/// assert_eq!(format_rfc3339::<Day>(1).to_string(), "2000-01-01T00:00:00Z");
+/// assert_eq!(format_rfc3339::<Millisecond>(-1).to_string(), "1999-12-31T23:59:59.999Z");
+/// assert_eq!(
+///     format_rfc3339::<Second>(536_544_004).to_string(),
+///     "2016-12-31T23:59:60Z"
+/// );
+/// ```
+pub fn format_rfc3339<L: Lane>(mark: Mark) -> Formatted<'static, L> {
+    let (_, nanosecond) = L::to_second_and_nanosecond(mark);
+    let pattern = if nanosecond == 0 {
+        RFC3339
+    } else if nanosecond % 1_000_000 == 0 {
+        RFC3339_MILLISECOND
+    } else if nanosecond % 1_000 == 0 {
+        RFC3339_MICROSECOND
+    } else {
+        RFC3339_NANOSECOND
+    };
+    pattern.format::<L>(mark)
+}
+
+/// Parses an RFC 3339 date and time into a rounded down [`Mark`] of a lane.
+///
+/// This accepts the same syntax as [`parse_rfc3339`], the mark must fit in the lane.
+///
+/// # Examples
+/// ```
+/// use timelane::iso8601::parse_rfc3339_in;
+/// use timelane::lane::{Day, Millisecond};
+/// assert_eq!(parse_rfc3339_in::<Day>("2000-01-01T23:59:59Z"), Ok(1));
+/// assert_eq!(parse_rfc3339_in::<Millisecond>("1999-12-31T23:59:59.9995Z"), Ok(-1));
+/// ```
+pub fn parse_rfc3339_in<L: Lane>(input: &str) -> Result<Mark, ParseError> {
+    let fields = rfc3339_fields(input.as_bytes())?;
+    let second = fields.to_second()?;
+    if second < L::MIN_SECOND || second > L::MAX_SECOND {
+        return Err(ParseError::new(0, ParseErrorKind::OutOfRange));
+    }
+    Ok(L::from_second_and_nanosecond(second, fields.nanosecond))
+}
+
+/// Parses an RFC 3339 date and time into a nanosecond [`Mark`].
+///
+/// The accepted syntax is `YYYY-MM-DDTHH:MM:SS[.fraction](Z|+HH:MM|-HH:MM)`, where `T` can also
+/// be a lowercase `t` or a space, and `Z` can also be a lowercase `z`.
+///
+/// # Examples
+/// ```
+/// use timelane::iso8601::parse_rfc3339;
+/// assert_eq!(parse_rfc3339("2000-01-01T00:00:00Z"), Ok(0));
+/// assert_eq!(parse_rfc3339("2000-01-01T00:00:01.5Z"), Ok(1_500_000_000));
+/// assert_eq!(parse_rfc3339("2000-01-01T01:00:00+01:00"), Ok(0));
+/// assert_eq!(parse_rfc3339("2016-12-31T23:59:60Z"), Ok(536_544_004_000_000_000));
+/// use timelane::parse::ParseErrorKind;
+/// let error = parse_rfc3339("2015-12-31T23:59:60Z").unwrap_err();
+/// assert_eq!(error.kind(), ParseErrorKind::InvalidLeapSecond);
+/// assert_eq!(error.position(), 17);
+/// ```
+pub const fn parse_rfc3339(input: &str) -> Result<Mark, ParseError> {
+    match rfc3339_fields(input.as_bytes()) {
+        Ok(fields) => fields.to_nanosecond(),
+        Err(error) => Err(error),
+    }
+}
+
+/// Parses an RFC 3339 date and time into a rounded down second [`Mark`].
+///
+/// This accepts the same syntax as [`parse_rfc3339`] but covers a much larger range of years.
+///
+/// # Examples
+/// ```
+/// use timelane::iso8601::parse_rfc3339_second;
+/// assert_eq!(parse_rfc3339_second("2000-01-01T00:00:00Z"), Ok(0));
+/// assert_eq!(parse_rfc3339_second("2000-01-01T00:00:01.5Z"), Ok(1));
+/// assert_eq!(parse_rfc3339_second("1999-12-31T23:59:59.999Z"), Ok(-1));
+/// assert_eq!(parse_rfc3339_second("9999-12-31T23:59:59Z"), Ok(252_455_616_004));
+/// ```
+pub const fn parse_rfc3339_second(input: &str) -> Result<Mark, ParseError> {
+    match rfc3339_fields(input.as_bytes()) {
+        Ok(fields) => fields.to_second(),
+        Err(error) => Err(error),
+    }
+}
+
+/// Parses an ISO 8601 date, optionally followed by a time, into a nanosecond [`Mark`].
+///
+/// Both the basic (`20000101T000000Z`) and extended (`2000-01-01T00:00:00Z`) formats are
+/// accepted, with calendar (`2000-01-01`), ordinal (`2000-001`) or week (`1999-W52-6`) dates.
+///
+/// Years have four digits, or four and more digits following a mandatory sign when using the
+/// extended format: `+12000-01-01`, `-0001-01-01`.
+///
+/// The time can have only hours and minutes, and hour `24` is accepted for the end of the day.
+/// When the UTC offset is missing, the time is considered to be in UTC.
+///
+/// # Examples
+/// ```
+/// use timelane::iso8601::parse_iso8601;
+/// assert_eq!(parse_iso8601("2000-01-01"), Ok(0));
+/// assert_eq!(parse_iso8601("2000-001T00:00Z"), Ok(0));
+/// assert_eq!(parse_iso8601("1999-W52-6T00:00:00Z"), Ok(0));
+/// assert_eq!(parse_iso8601("20000101T000001,5+0000"), Ok(1_500_000_000));
+/// assert_eq!(parse_iso8601("1999-12-31T24:00"), Ok(0));
+/// ```
+pub const fn parse_iso8601(input: &str) -> Result<Mark, ParseError> {
+    match iso8601_fields(input.as_bytes()) {
+        Ok(fields) => fields.to_nanosecond(),
+        Err(error) => Err(error),
+    }
+}
+
+/// Parses an ISO 8601 date, optionally followed by a time, into a rounded down second [`Mark`].
+///
+/// This accepts the same syntax as [`parse_iso8601`] but covers a much larger range of years.
+///
+/// # Examples
+/// ```
+/// use timelane::iso8601::parse_iso8601_second;
+/// assert_eq!(parse_iso8601_second("2000-01-01"), Ok(0));
+/// assert_eq!(parse_iso8601_second("+10000-01-01"), Ok(252_455_616_005));
+/// assert_eq!(parse_iso8601_second("-0001-12-31T23:59:59Z"), Ok(-63_113_904_023));
+/// ```
+pub const fn parse_iso8601_second(input: &str) -> Result<Mark, ParseError> {
+    match iso8601_fields(input.as_bytes()) {
+        Ok(fields) => fields.to_second(),
+        Err(error) => Err(error),
+    }
+}
+
+const fn rfc3339_fields(bytes: &[u8]) -> Result<DateTimeFields, ParseError> {
+    let (year, position) = tri!(digits(bytes, 0, 4));
+    let position = tri!(expect(bytes, position, b'-'));
+    let (day, position) = tri!(month_and_day(bytes, year, position, true));
+    let separator = tri!(peek(bytes, position));
+    if separator != b'T' && separator != b't' && separator != b' ' {
+        return Err(ParseError::new(
+            position,
+            ParseErrorKind::UnexpectedCharacter,
+        ));
+    }
+    let (hour, position) = tri!(hour(bytes, position + 1, 23));
+    let position = tri!(expect(bytes, position, b':'));
+    let (minute, position) = tri!(minute(bytes, position));
+    let position = tri!(expect(bytes, position, b':'));
+    let second_position = position;
+    let (second, position) = tri!(second(bytes, position));
+    let (nanosecond, position) = tri!(optional_fraction(bytes, position));
+    let (offset, position) = if tri!(peek(bytes, position)).eq_ignore_ascii_case(&b'Z') {
+        (0, position + 1)
+    } else {
+        tri!(offset(bytes, position, true, false))
+    };
+    tri!(expect_end(bytes, position));
+    Ok(DateTimeFields {
+        day,
+        hour,
+        minute,
+        second,
+        nanosecond,
+        offset,
+        start_position: 0,
+        second_position,
+    })
+}
+
+const fn iso8601_fields(bytes: &[u8]) -> Result<DateTimeFields, ParseError> {
+    let (year, position, extended) = tri!(year(bytes));
+    let (day, position) = if extended {
+        let position = tri!(expect(bytes, position, b'-'));
+        if tri!(peek(bytes, position)) == b'W' {
+            tri!(week_date(bytes, year, position + 1, true))
+        } else if count_digits(bytes, position) == 3 {
+            tri!(ordinal_date(bytes, year, position))
+        } else {
+            tri!(month_and_day(bytes, year, position, true))
+        }
+    } else if tri!(peek(bytes, position)) == b'W' {
+        tri!(week_date(bytes, year, position + 1, false))
+    } else if count_digits(bytes, position) == 3 {
+        tri!(ordinal_date(bytes, year, position))
+    } else {
+        tri!(month_and_day(bytes, year, position, false))
+    };
+    let mut fields = DateTimeFields {
+        day,
+        hour: 0,
+        minute: 0,
+        second: 0,
+        nanosecond: 0,
+        offset: 0,
+        start_position: 0,
+        second_position: position,
+    };
+    if position == bytes.len() {
+        return Ok(fields);
+    }
+    let position = tri!(expect_ignore_case(bytes, position, b'T'));
+    let hour_position = position;
+    let (hour, position) = tri!(hour(bytes, position, 24));
+    let position = if extended {
+        tri!(expect(bytes, position, b':'))
+    } else {
+        position
+    };
+    let (minute, mut position) = tri!(minute(bytes, position));
+    fields.second_position = position;
+    if position < bytes.len()
+        && ((extended && bytes[position] == b':')
+            || (!extended && bytes[position].is_ascii_digit()))
+    {
+        if extended {
+            position += 1;
+        }
+        fields.second_position = position;
+        let (second, next) = tri!(second(bytes, position));
+        let (nanosecond, next) = tri!(optional_fraction(bytes, next));
+        fields.second = second;
+        fields.nanosecond = nanosecond;
+        position = next;
+    }
+    if position < bytes.len() {
+        if bytes[position].eq_ignore_ascii_case(&b'Z') {
+            position += 1;
+        } else {
+            let (offset, next) = tri!(offset(bytes, position, extended, true));
+            fields.offset = offset;
+            position = next;
+        }
+    }
+    tri!(expect_end(bytes, position));
+    let (day, hour) = tri!(end_of_day(
+        day,
+        hour,
+        minute,
+        fields.second,
+        fields.nanosecond,
+        hour_position
+    ));
+    fields.day = day;
+    fields.hour = hour;
+    fields.minute = minute;
+    Ok(fields)
+}
+
+/// Parses a four digits year, or a signed expanded year, returns the year, the next position and
+/// whether the date uses the extended format.
+const fn year(bytes: &[u8]) -> Result<(Mark, usize, bool), ParseError> {
+    let first = tri!(peek(bytes, 0));
+    if first == b'+' || first == b'-' {
+        let count = count_digits(bytes, 1);
+        if count < 4 {
+            return Err(ParseError::new(
+                1 + count,
+                if 1 + count < bytes.len() {
+                    ParseErrorKind::UnexpectedCharacter
+                } else {
+                    ParseErrorKind::UnexpectedEnd
+                },
+            ));
+        }
+        let (year, position) = tri!(digits(bytes, 1, count));
+        let year = tri!(check_year(if first == b'-' { -year } else { year }, 1));
+        // Expanded years need the separators to know where the year ends
+        if tri!(peek(bytes, position)) != b'-' {
+            return Err(ParseError::new(
+                position,
+                ParseErrorKind::UnexpectedCharacter,
+            ));
+        }
+        Ok((year, position, true))
+    } else {
+        let (year, position) = tri!(digits(bytes, 0, 4));
+        let extended = position < bytes.len() && bytes[position] == b'-';
+        Ok((year, position, extended))
+    }
+}
+
+/// Parses `MM-DD` or `MMDD`, returns the day [`Mark`] and the next position.
+const fn month_and_day(
+    bytes: &[u8],
+    year: Mark,
+    position: usize,
+    extended: bool,
+) -> Result<(Mark, usize), ParseError> {
+    let month_position = position;
+    let (month, position) = tri!(digits(bytes, position, 2));
+    let month = tri!(check_range(
+        month,
+        1,
+        12,
+        month_position,
+        ParseErrorKind::InvalidMonth
+    ));
+    let position = if extended {
+        tri!(expect(bytes, position, b'-'))
+    } else {
+        position
+    };
+    let day_position = position;
+    let (day, position) = tri!(digits(bytes, position, 2));
+    let day = tri!(check_range(
+        day,
+        1,
+        days_in_month(year, month),
+        day_position,
+        ParseErrorKind::InvalidDay
+    ));
+    Ok((year_month_day_to_day(year, month, day), position))
+}
+
+/// Parses `DDD`, returns the day [`Mark`] and the next position.
+const fn ordinal_date(
+    bytes: &[u8],
+    year: Mark,
+    position: usize,
+) -> Result<(Mark, usize), ParseError> {
+    let (ordinal, next) = tri!(digits(bytes, position, 3));
+    let ordinal = tri!(check_range(
+        ordinal,
+        1,
+        days_in_year(year),
+        position,
+        ParseErrorKind::InvalidDay
+    ));
+    Ok((year_ordinal_to_day(year, ordinal), next))
+}
+
+/// Parses `ww-D` or `wwD` after the `W`, returns the day [`Mark`] and the next position.
+const fn week_date(
+    bytes: &[u8],
+    year: Mark,
+    position: usize,
+    extended: bool,
+) -> Result<(Mark, usize), ParseError> {
+    let week_position = position;
+    let (week, position) = tri!(digits(bytes, position, 2));
+    let week = tri!(check_range(
+        week,
+        1,
+        weeks_in_iso_year(year),
+        week_position,
+        ParseErrorKind::InvalidWeek
+    ));
+    let position = if extended {
+        tri!(expect(bytes, position, b'-'))
+    } else {
+        position
+    };
+    let weekday_position = position;
+    let (weekday, position) = tri!(digits(bytes, position, 1));
+    let weekday = tri!(check_range(
+        weekday,
+        1,
+        7,
+        weekday_position,
+        ParseErrorKind::InvalidWeekday
+    ));
+    Ok((iso_week_date_to_day(year, week, weekday), position))
+}
+
+const fn hour(bytes: &[u8], position: usize, max: Mark) -> Result<(Mark, usize), ParseError> {
+    let (hour, next) = tri!(digits(bytes, position, 2));
+    let hour = tri!(check_range(
+        hour,
+        0,
+        max,
+        position,
+        ParseErrorKind::InvalidHour
+    ));
+    Ok((hour, next))
+}
+
+const fn minute(bytes: &[u8], position: usize) -> Result<(Mark, usize), ParseError> {
+    let (minute, next) = tri!(digits(bytes, position, 2));
+    let minute = tri!(check_range(
+        minute,
+        0,
+        59,
+        position,
+        ParseErrorKind::InvalidMinute
+    ));
+    Ok((minute, next))
+}
+
+const fn second(bytes: &[u8], position: usize) -> Result<(Mark, usize), ParseError> {
+    let (second, next) = tri!(digits(bytes, position, 2));
+    let second = tri!(check_range(
+        second,
+        0,
+        60,
+        position,
+        ParseErrorKind::InvalidSecond
+    ));
+    Ok((second, next))
+}
+
+/// Parses an optional fraction of second starting with `.` or `,`.
+const fn optional_fraction(bytes: &[u8], position: usize) -> Result<(Mark, usize), ParseError> {
+    if position < bytes.len() && (bytes[position] == b'.' || bytes[position] == b',') {
+        fraction(bytes, position + 1)
+    } else {
+        Ok((0, position))
+    }
+}
+
+/// Parses a `+HH:MM` or `-HH:MM` UTC offset, returns the offset in minutes and the next position.
+///
+/// The separator is absent when not using the extended format, the minutes can be omitted when
+/// `optional_minutes` is set.
+const fn offset(
+    bytes: &[u8],
+    position: usize,
+    extended: bool,
+    optional_minutes: bool,
+) -> Result<(Mark, usize), ParseError> {
+    let sign = tri!(peek(bytes, position));
+    if sign != b'+' && sign != b'-' {
+        return Err(ParseError::new(
+            position,
+            ParseErrorKind::UnexpectedCharacter,
+        ));
+    }
+    let hours_position = position + 1;
+    let (hours, mut position) = tri!(digits(bytes, hours_position, 2));
+    let hours = tri!(check_range(
+        hours,
+        0,
+        23,
+        hours_position,
+        ParseErrorKind::InvalidOffset
+    ));
+    let mut minutes = 0;
+    if !optional_minutes || position < bytes.len() {
+        if extended {
+            position = tri!(expect(bytes, position, b':'));
+        }
+        let minutes_position = position;
+        let (value, next) = tri!(digits(bytes, position, 2));
+        minutes = tri!(check_range(
+            value,
+            0,
+            59,
+            minutes_position,
+            ParseErrorKind::InvalidOffset
+        ));
+        position = next;
+    }
+    let offset = hours * 60 + minutes;
+    Ok((if sign == b'-' { -offset } else { offset }, position))
+}
+
+/// Turns hour `24` into hour `0` of the next day, after checking the rest of the time is zero.
+const fn end_of_day(
+    day: Mark,
+    hour: Mark,
+    minute: Mark,
+    second: Mark,
+    nanosecond: Mark,
+    hour_position: usize,
+) -> Result<(Mark, Mark), ParseError> {
+    if hour < 24 {
+        Ok((day, hour))
+    } else if minute == 0 && second == 0 && nanosecond == 0 {
+        Ok((day + 1, 0))
+    } else {
+        Err(ParseError::new(hour_position, ParseErrorKind::InvalidHour))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subsecond::second_to_nanosecond;
+    use crate::LEAP_SECONDS_MARKS;
+
+    fn error(result: Result<Mark, ParseError>) -> (usize, ParseErrorKind) {
+        let error = result.unwrap_err();
+        (error.position(), error.kind())
+    }
+
+    #[test]
+    fn rfc3339_accepts_all_separators() {
+        assert_eq!(
+            parse_rfc3339("2017-01-01T00:00:00.123Z"),
+            parse_rfc3339("2017-01-01t00:00:00.123z")
+        );
+        assert_eq!(
+            parse_rfc3339("2017-01-01T00:00:00.123Z"),
+            parse_rfc3339("2017-01-01 00:00:00.123Z")
+        );
+    }
+
+    #[test]
+    fn rfc3339_negative_zero_offset_is_utc() {
+        assert_eq!(
+            parse_rfc3339("2017-01-01T00:00:00-00:00"),
+            parse_rfc3339("2017-01-01T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn rfc3339_offsets_move_towards_utc() {
+        assert_eq!(
+            parse_rfc3339_second("2000-01-01T00:00:00-01:30"),
+            Ok(90 * 60)
+        );
+        assert_eq!(
+            parse_rfc3339_second("2000-01-01T00:00:00+01:30"),
+            Ok(-90 * 60)
+        );
+    }
+
+    #[test]
+    fn rfc3339_fraction_is_rounded_down_to_nanoseconds() {
+        assert_eq!(
+            parse_rfc3339("2000-01-01T00:00:00.123456789999Z"),
+            Ok(123_456_789)
+        );
+        assert_eq!(parse_rfc3339("2000-01-01T00:00:00.000000001Z"), Ok(1));
+    }
+
+    #[test]
+    fn rfc3339_accepts_all_leap_seconds() {
+        for leap_minute in LEAP_SECONDS_MARKS {
+            let second = crate::minute_to_second(leap_minute) - 1;
+            let minute = leap_minute - 1;
+            let day = crate::hour_to_day(crate::minute_to_hour(minute));
+            let (year, month, day_of_month) = naive_civil(day);
+            let text = format!("{:04}-{:02}-{:02}T23:59:60Z", year, month, day_of_month);
+            assert_eq!(parse_rfc3339_second(&text), Ok(second), "{}", text);
+        }
+    }
+
+    #[test]
+    fn rfc3339_accepts_leap_seconds_with_offsets() {
+        assert_eq!(
+            parse_rfc3339("2017-01-01T00:59:60+01:00"),
+            parse_rfc3339("2016-12-31T23:59:60Z")
+        );
+        assert_eq!(
+            error(parse_rfc3339("2016-12-31T23:59:60+01:00")),
+            (17, ParseErrorKind::InvalidLeapSecond)
+        );
+    }
+
+    #[test]
+    fn rfc3339_leap_second_is_before_next_minute() {
+        let leap = parse_rfc3339("2016-12-31T23:59:60Z").unwrap();
+        let next = parse_rfc3339("2017-01-01T00:00:00Z").unwrap();
+        assert_eq!(next - leap, second_to_nanosecond(1));
+    }
+
+    #[test]
+    fn rfc3339_reports_error_positions() {
+        assert_eq!(error(parse_rfc3339("")), (0, ParseErrorKind::UnexpectedEnd));
+        assert_eq!(
+            error(parse_rfc3339("2017")),
+            (4, ParseErrorKind::UnexpectedEnd)
+        );
+        assert_eq!(
+            error(parse_rfc3339("2017/01/01T00:00:00Z")),
+            (4, ParseErrorKind::UnexpectedCharacter)
+        );
+        assert_eq!(
+            error(parse_rfc3339("2017-13-01T00:00:00Z")),
+            (5, ParseErrorKind::InvalidMonth)
+        );
+        assert_eq!(
+            error(parse_rfc3339("2017-02-29T00:00:00Z")),
+            (8, ParseErrorKind::InvalidDay)
+        );
+        assert_eq!(
+            error(parse_rfc3339("2017-01-01X00:00:00Z")),
+            (10, ParseErrorKind::UnexpectedCharacter)
+        );
+        assert_eq!(
+            error(parse_rfc3339("2017-01-01T24:00:00Z")),
+            (11, ParseErrorKind::InvalidHour)
+        );
+        assert_eq!(
+            error(parse_rfc3339("2017-01-01T00:60:00Z")),
+            (14, ParseErrorKind::InvalidMinute)
+        );
+        assert_eq!(
+            error(parse_rfc3339("2017-01-01T00:00:61Z")),
+            (17, ParseErrorKind::InvalidSecond)
+        );
+        assert_eq!(
+            error(parse_rfc3339("2017-01-01T00:00:00.Z")),
+            (20, ParseErrorKind::UnexpectedCharacter)
+        );
+        assert_eq!(
+            error(parse_rfc3339("2017-01-01T00:00:00")),
+            (19, ParseErrorKind::UnexpectedEnd)
+        );
+        assert_eq!(
+            error(parse_rfc3339("2017-01-01T00:00:00+24:00")),
+            (20, ParseErrorKind::InvalidOffset)
+        );
+        assert_eq!(
+            error(parse_rfc3339("2017-01-01T00:00:00+0100")),
+            (22, ParseErrorKind::UnexpectedCharacter)
+        );
+        assert_eq!(
+            error(parse_rfc3339("2017-01-01T00:00:00Zt")),
+            (20, ParseErrorKind::TrailingCharacters)
+        );
+    }
+
+    #[test]
+    fn rfc3339_reports_years_out_of_nanosecond_range() {
+        assert_eq!(
+            error(parse_rfc3339("2300-01-01T00:00:00Z")),
+            (0, ParseErrorKind::OutOfRange)
+        );
+        assert_eq!(
+            error(parse_rfc3339("1700-01-01T00:00:00Z")),
+            (0, ParseErrorKind::OutOfRange)
+        );
+        assert!(parse_rfc3339_second("2300-01-01T00:00:00Z").is_ok());
+    }
+
+    #[test]
+    fn iso8601_basic_and_extended_formats_match() {
+        let expected = parse_rfc3339("2017-03-04T05:06:07.89+01:30");
+        assert_eq!(parse_iso8601("2017-03-04T05:06:07.89+01:30"), expected);
+        assert_eq!(parse_iso8601("20170304T050607.89+0130"), expected);
+        assert_eq!(parse_iso8601("2017-063T05:06:07,89+01:30"), expected);
+        assert_eq!(parse_iso8601("2017063T050607,89+0130"), expected);
+        assert_eq!(parse_iso8601("2017-W09-6T05:06:07.89+01:30"), expected);
+        assert_eq!(parse_iso8601("2017W096T050607.89+0130"), expected);
+    }
+
+    #[test]
+    fn iso8601_accepts_reduced_times_and_offsets() {
+        let expected = parse_rfc3339("2017-03-04T05:06:00+01:00");
+        assert_eq!(parse_iso8601("2017-03-04T05:06+01"), expected);
+        assert_eq!(parse_iso8601("20170304T0506+01"), expected);
+        assert_eq!(parse_iso8601("2017-03-04T05:06:00+01:00"), expected);
+    }
+
+    #[test]
+    fn iso8601_end_of_day_is_next_day() {
+        assert_eq!(
+            parse_iso8601("2016-12-31T24:00:00Z"),
+            parse_iso8601("2017-01-01")
+        );
+        assert_eq!(
+            error(parse_iso8601("2016-12-31T24:00:01Z")),
+            (11, ParseErrorKind::InvalidHour)
+        );
+    }
+
+    #[test]
+    fn iso8601_week_dates_cross_years() {
+        assert_eq!(parse_iso8601("2009-W01-1"), parse_iso8601("2008-12-29"));
+        assert_eq!(parse_iso8601("2009-W53-7"), parse_iso8601("2010-01-03"));
+        assert_eq!(parse_iso8601("2004-W53-6"), parse_iso8601("2005-01-01"));
+        assert_eq!(
+            error(parse_iso8601("2010-W53-1")),
+            (6, ParseErrorKind::InvalidWeek)
+        );
+        assert_eq!(
+            error(parse_iso8601("2010-W01-8")),
+            (9, ParseErrorKind::InvalidWeekday)
+        );
+    }
+
+    #[test]
+    fn iso8601_ordinal_dates_follow_leap_years() {
+        assert_eq!(parse_iso8601("2016-366"), parse_iso8601("2016-12-31"));
+        assert_eq!(
+            error(parse_iso8601("2017-366")),
+            (5, ParseErrorKind::InvalidDay)
+        );
+    }
+
+    #[test]
+    fn iso8601_expanded_years_need_a_sign_and_separators() {
+        assert_eq!(parse_iso8601_second("+2000-01-01"), Ok(0));
+        assert_eq!(parse_iso8601_second("+002000-01-01"), Ok(0));
+        assert_eq!(
+            parse_iso8601_second("-0001-01-01"),
+            Ok(crate::minute_to_second(crate::hour_to_minute(
+                crate::day_to_hour(year_month_day_to_day(-1, 1, 1))
+            )))
+        );
+        assert_eq!(
+            error(parse_iso8601("+200001")),
+            (7, ParseErrorKind::UnexpectedEnd)
+        );
+        assert_eq!(
+            error(parse_iso8601("+20000101")),
+            (9, ParseErrorKind::UnexpectedEnd)
+        );
+        assert_eq!(
+            error(parse_iso8601("+200-01-01")),
+            (4, ParseErrorKind::UnexpectedCharacter)
+        );
+        assert_eq!(
+            error(parse_iso8601_second("+99999999999999999999-01-01")),
+            (1, ParseErrorKind::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn iso8601_rejects_mixed_formats() {
+        assert_eq!(
+            error(parse_iso8601("2017-03-04T0506")),
+            (13, ParseErrorKind::UnexpectedCharacter)
+        );
+        assert_eq!(
+            error(parse_iso8601("20170304T05:06")),
+            (11, ParseErrorKind::UnexpectedCharacter)
+        );
+        assert_eq!(
+            error(parse_iso8601("2017-0304")),
+            (7, ParseErrorKind::UnexpectedCharacter)
+        );
+    }
+
+    #[test]
+    fn parsers_work_in_const() {
+        const MARK: Mark = match parse_rfc3339("2000-01-01T00:00:01Z") {
+            Ok(mark) => mark,
+            Err(_) => panic!(),
+        };
+        assert_eq!(MARK, 1_000_000_000);
+    }
+
+    fn naive_civil(day: Mark) -> (Mark, Mark, Mark) {
+        let month = crate::day_to_month(day);
+        let year = crate::month_to_year(month);
+        (
+            year,
+            month - crate::year_to_month(year) + 1,
+            day - crate::month_to_day(month) + 1,
+        )
+    }
+
+    fn roundtrip<L: Lane>(marks: impl Iterator<Item = Mark>) {
+        for mark in marks {
+            let text = format_rfc3339::<L>(mark).to_string();
+            assert_eq!(parse_rfc3339_in::<L>(&text), Ok(mark), "{}", text);
+        }
+    }
+
+    #[test]
+    fn rfc3339_formatting_roundtrips() {
+        use crate::lane::{Day, Microsecond, Millisecond, Nanosecond, Second};
+        for leap_minute in LEAP_SECONDS_MARKS {
+            let leap = crate::minute_to_second(leap_minute) - 1;
+            roundtrip::<Second>(leap - 2..leap + 2);
+            let leap = second_to_nanosecond(leap);
+            roundtrip::<Nanosecond>((leap - 2_000_000_000..leap + 2_000_000_000).step_by(999_999));
+        }
+        roundtrip::<Day>((-730_000..2_900_000).step_by(97));
+        roundtrip::<Millisecond>(-2_000..2_000);
+        roundtrip::<Microsecond>(-2_000..2_000);
+    }
+
+    #[test]
+    fn rfc3339_formatting_keeps_needed_digits() {
+        use crate::lane::Nanosecond;
+        let format = |mark| format_rfc3339::<Nanosecond>(mark).to_string();
+        assert_eq!(format(1_000_000), "2000-01-01T00:00:00.001Z");
+        assert_eq!(format(1_001_000), "2000-01-01T00:00:00.001001Z");
+        assert_eq!(format(1), "2000-01-01T00:00:00.000000001Z");
+    }
+}
@@ -0,0 +1,237 @@
+//! Iterators over ranges of [`Mark`], like the days of a month or the seconds of a minute.
+//!
+//! [`children`] iterates over the marks of a finer lane contained in a mark of a coarser lane,
+//! with the 61 seconds of a minute ending with a leap second. [`Marks`] iterates over any range of
+//! marks, optionally stepping over some of them. All iterators are double-ended and know their
+//! exact length.
+//!
+//! # Examples
+//! ```
+//! use timelane::iter::children;
+//! use timelane::lane::{Day, Month, Year};
+//! let february = children::<Year, Month>(2024).nth(1).unwrap();
+//! assert_eq!(children::<Month, Day>(february).len(), 29);
+//! ```
+use core::iter::FusedIterator;
+
+use crate::lane::Lane;
+use crate::Mark;
+
+/// Returns an iterator over the marks of lane `C` starting within a mark of lane `P`.
+///
+/// When `C` is finer than `P`, these are all the marks of `C` contained in the `P` mark.
+///
+/// # Examples
+/// ```
+/// use timelane::iter::children;
+/// use timelane::lane::{Day, Hour, Minute, Month, Second};
+/// use timelane::{day_to_hour, month_to_day};
+/// let days = children::<Month, Day>(2);
+/// assert_eq!(days.len(), 29);
+/// assert_eq!(days.clone().next(), Some(month_to_day(2)));
+/// assert_eq!(days.clone().next_back(), Some(month_to_day(3) - 1));
+/// assert!(children::<Day, Hour>(1).eq(day_to_hour(1)..day_to_hour(2)));
+/// let leap_minute = timelane::LEAP_SECONDS_MARKS[26] - 1;
+/// assert_eq!(children::<Minute, Second>(leap_minute).len(), 61);
+/// ```
+pub fn children<P: Lane, C: Lane>(parent: Mark) -> Marks {
+    let (start_second, start_nanosecond) = P::to_second_and_nanosecond(parent);
+    let (end_second, end_nanosecond) = P::to_second_and_nanosecond(parent + 1);
+    Marks::new(
+        C::from_second_and_nanosecond_up(start_second, start_nanosecond),
+        C::from_second_and_nanosecond_up(end_second, end_nanosecond),
+    )
+}
+
+/// An iterator over the marks from a start [`Mark`] to an end [`Mark`] excluded, taking every
+/// `step` mark.
+///
+/// # Examples
+/// ```
+/// use timelane::iter::Marks;
+/// let quarters = Marks::stepped(0, 60, 15);
+/// assert_eq!(quarters.len(), 4);
+/// assert!(quarters.rev().eq([45, 30, 15, 0]));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Marks {
+    start: Mark,
+    step: Mark,
+    remaining: usize,
+}
+
+impl Marks {
+    /// Creates an iterator over all marks from `start` to `end` excluded.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::iter::Marks;
+    /// assert!(Marks::new(-1, 2).eq([-1, 0, 1]));
+    /// assert_eq!(Marks::new(2, -1).len(), 0);
+    /// ```
+    pub const fn new(start: Mark, end: Mark) -> Self {
+        Self::stepped(start, end, 1)
+    }
+
+    /// Creates an iterator over the marks from `start` to `end` excluded, taking every `step`
+    /// mark from `start`.
+    ///
+    /// # Panics
+    /// Panics if `step` is not positive.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::iter::Marks;
+    /// assert!(Marks::stepped(0, 10, 4).eq([0, 4, 8]));
+    /// assert!(Marks::stepped(Mark::MIN, Mark::MAX, Mark::MAX).eq([Mark::MIN, -1, Mark::MAX - 1]));
+    /// # use timelane::Mark;
+    /// ```
+    pub const fn stepped(start: Mark, end: Mark, step: Mark) -> Self {
+        assert!(step > 0, "the step must be positive");
+        let remaining = if start < end {
+            end.abs_diff(start).div_ceil(step as usize)
+        } else {
+            0
+        };
+        Self {
+            start,
+            step,
+            remaining,
+        }
+    }
+
+    /// Returns the mark at an index from the front, which must be smaller than `remaining`.
+    const fn mark_at(&self, index: usize) -> Mark {
+        // The mark fits between start and end, the wrapping intermediate results cancel out
+        self.start
+            .wrapping_add((index as Mark).wrapping_mul(self.step))
+    }
+}
+
+impl Iterator for Marks {
+    type Item = Mark;
+
+    fn next(&mut self) -> Option<Mark> {
+        self.nth(0)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Mark> {
+        if n >= self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+        let mark = self.mark_at(n);
+        self.start = self.mark_at(n + 1);
+        self.remaining -= n + 1;
+        Some(mark)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+
+    fn count(self) -> usize {
+        self.remaining
+    }
+
+    fn last(mut self) -> Option<Mark> {
+        self.next_back()
+    }
+}
+
+impl DoubleEndedIterator for Marks {
+    fn next_back(&mut self) -> Option<Mark> {
+        self.nth_back(0)
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Mark> {
+        if n >= self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+        self.remaining -= n + 1;
+        Some(self.mark_at(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Marks {}
+
+impl FusedIterator for Marks {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lane::{Day, Hour, Microsecond, Minute, Month, Nanosecond, Second, Year};
+    use crate::LEAP_SECONDS_MARKS;
+
+    #[test]
+    fn children_cover_their_parent() {
+        for month in -2412..2412 {
+            let days = children::<Month, Day>(month);
+            assert_eq!(days.clone().next(), Some(crate::month_to_day(month)));
+            assert_eq!(days.last(), Some(crate::month_to_day(month + 1) - 1));
+        }
+        assert_eq!(children::<Year, Day>(2000).len(), 366);
+        assert_eq!(children::<Year, Hour>(2001).len(), 365 * 24);
+        assert_eq!(children::<Second, Nanosecond>(0).len(), 1_000_000_000);
+        assert_eq!(children::<Day, Month>(1).collect::<Vec<_>>(), [1]);
+        assert_eq!(children::<Day, Month>(2).len(), 0);
+    }
+
+    #[test]
+    fn minutes_with_leap_seconds_have_61_seconds() {
+        for leap_minute in LEAP_SECONDS_MARKS {
+            assert_eq!(children::<Minute, Second>(leap_minute - 1).len(), 61);
+            assert_eq!(children::<Minute, Second>(leap_minute).len(), 60);
+            let leap_day = crate::hour_to_day(crate::minute_to_hour(leap_minute - 1));
+            assert_eq!(children::<Day, Second>(leap_day).len(), 86_401);
+            assert_eq!(
+                children::<Minute, Microsecond>(leap_minute - 1).len(),
+                61_000_000
+            );
+        }
+    }
+
+    #[test]
+    fn iterating_from_both_ends_meets_in_the_middle() {
+        let mut marks = Marks::stepped(-7, 8, 3);
+        assert_eq!(marks.len(), 5);
+        assert_eq!(marks.next(), Some(-7));
+        assert_eq!(marks.next_back(), Some(5));
+        assert_eq!(marks.len(), 3);
+        assert_eq!(marks.nth(1), Some(-1));
+        assert_eq!(marks.next_back(), Some(2));
+        assert_eq!(marks.next(), None);
+        assert_eq!(marks.next_back(), None);
+        let mut marks = Marks::new(0, 10);
+        assert_eq!(marks.nth_back(3), Some(6));
+        assert_eq!(marks.nth(6), None);
+        assert_eq!(marks.len(), 0);
+    }
+
+    #[test]
+    fn stepped_marks_match_step_by() {
+        for start in -10..10 {
+            for end in -10..10 {
+                for step in 1..12 {
+                    let marks = Marks::stepped(start, end, step);
+                    let expected = (start..end).step_by(step as usize);
+                    assert_eq!(marks.len(), expected.len());
+                    assert!(marks.clone().eq(expected.clone()));
+                    assert!(marks.rev().eq(expected.rev()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn extreme_marks_do_not_overflow() {
+        let marks = Marks::new(Mark::MIN, Mark::MAX);
+        assert_eq!(marks.len(), usize::MAX);
+        assert_eq!(marks.clone().last(), Some(Mark::MAX - 1));
+        assert_eq!(marks.clone().nth(usize::MAX - 1), Some(Mark::MAX - 1));
+        let mut marks = Marks::stepped(Mark::MAX - 2, Mark::MAX, 2);
+        assert_eq!(marks.next(), Some(Mark::MAX - 2));
+        assert_eq!(marks.next(), None);
+    }
+}
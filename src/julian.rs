@@ -0,0 +1,483 @@
+//! [`crate::Scaler`] functions for the proleptic julian calendar, and mixed historical calendars
+//! switching from the julian to the gregorian calendar.
+//!
+//! Julian months are numbered like gregorian months: month one is January of
+//! [`crate::EPOCH_YEAR`] in the julian calendar, and the julian months and years convert between
+//! each other with [`crate::year_to_month`], [`crate::month_to_year`] and
+//! [`crate::month_to_year_up`]. Only the conversions to and from the day lane differ, the day
+//! lane is shared by both calendars: January 1st, 2000 in the julian calendar is January 14th,
+//! 2000 in the gregorian calendar, the day 14.
+//!
+//! Every fourth year is a leap year in the julian calendar, including 1BC, the year zero.
+//!
+//! # Examples
+//! ```
+//! use timelane::{julian, month_to_day, year_to_month};
+//! // Julian October 4th, 1582 was followed by gregorian October 15th, 1582
+//! let october_1582 = year_to_month(1582) + 9;
+//! assert_eq!(julian::month_to_day(october_1582) + 3 + 1, month_to_day(october_1582) + 14);
+//! ```
+use crate::civil::year_month_day_to_day;
+use crate::{divide_towards_negative_infinity, month_to_year, year_to_month, Mark};
+
+/// The number of days in 4 julian years, after which leap years repeat.
+const DAYS_IN_4_YEARS: Mark = 365 * 4 + 1;
+
+/// The day [`Mark`] of March 1st, 2000 in the julian calendar.
+const MARCH_1ST_2000: Mark = 74;
+
+/// Converts a julian month [`Mark`] to a day [`Mark`].
+///
+/// Julian month one is day 14.
+///
+/// # Examples
+/// ```
+/// use timelane::julian::month_to_day;
+/// assert_eq!(month_to_day(0), -17);
+/// assert_eq!(month_to_day(1), 14);
+/// assert_eq!(month_to_day(2), 45);
+/// use timelane::Mark;
+/// assert_eq!(month_to_day(-303026596693380724), Mark::MIN + 4);
+/// assert_eq!(month_to_day(303026596693380725), Mark::MAX - 7);
+/// ```
+pub const fn month_to_day(month: Mark) -> Mark {
+    // Months from March 2000, in cycles of 4 years starting on March 1st, so that leap days end
+    // the cycles
+    let zmonth = month - 3;
+    let cycle = zmonth.div_euclid(48);
+    let zmonth_in_cycle = zmonth.rem_euclid(48);
+    let zmonth_in_year = zmonth_in_cycle % 12;
+    let zday_in_year = (153 * zmonth_in_year + 2) / 5;
+    let day_in_cycle = MARCH_1ST_2000 + zmonth_in_cycle / 12 * 365 + zday_in_year;
+    // Negative cycles lend two cycles of days to the day in the cycle to keep the product in range
+    let is_negative = (cycle < 0) as Mark;
+    (cycle + 2 * is_negative) * DAYS_IN_4_YEARS + (day_in_cycle - 2 * is_negative * DAYS_IN_4_YEARS)
+}
+
+/// Converts a day [`Mark`] to a rounded down julian month [`Mark`].
+///
+/// Day 14 is julian month one.
+///
+/// # Examples
+/// ```
+/// use timelane::julian::day_to_month;
+/// assert_eq!(day_to_month(45), 2);
+/// assert_eq!(day_to_month(44), 1);
+/// assert_eq!(day_to_month(14), 1);
+/// assert_eq!(day_to_month(13), 0);
+/// use timelane::Mark;
+/// assert_eq!(day_to_month(Mark::MIN), -303026596693380725);
+/// assert_eq!(day_to_month(Mark::MAX), 303026596693380725);
+/// ```
+pub const fn day_to_month(day: Mark) -> Mark {
+    let (month, _) = day_to_month_and_zday(day);
+    month
+}
+
+/// Converts a day [`Mark`] to a rounded up julian month [`Mark`].
+///
+/// Day 14 is julian month one.
+///
+/// # Examples
+/// ```
+/// use timelane::julian::day_to_month_up;
+/// assert_eq!(day_to_month_up(45), 2);
+/// assert_eq!(day_to_month_up(44), 2);
+/// assert_eq!(day_to_month_up(15), 2);
+/// assert_eq!(day_to_month_up(14), 1);
+/// use timelane::Mark;
+/// assert_eq!(day_to_month_up(Mark::MIN), -303026596693380724);
+/// assert_eq!(day_to_month_up(Mark::MAX), 303026596693380726);
+/// ```
+pub const fn day_to_month_up(day: Mark) -> Mark {
+    let (month, zday_in_month) = day_to_month_and_zday(day);
+    month + (zday_in_month != 0) as Mark
+}
+
+/// Returns the number of leap days between year 1 and a given year according to the proleptic
+/// julian calendar, like [`crate::leap_days_before_year`] does for the gregorian calendar.
+///
+/// # Examples
+/// ```
+/// use timelane::julian::leap_days_before_year;
+/// assert_eq!(leap_days_before_year(5), 1);
+/// assert_eq!(leap_days_before_year(1), 0);
+/// assert_eq!(leap_days_before_year(0), -1);
+/// assert_eq!(leap_days_before_year(-4), -2);
+/// // 1700, 1800 and 1900 are only leap years in the julian calendar
+/// assert_eq!(leap_days_before_year(2000) - timelane::leap_days_before_year(2000), 15);
+/// ```
+pub const fn leap_days_before_year(year: Mark) -> Mark {
+    // Same as rounding down (year - 1) / 4, without underflowing
+    divide_towards_negative_infinity(year, 4) - (year % 4 == 0) as Mark
+}
+
+/// Splits a day [`Mark`] into a julian month [`Mark`] and the zero-based day in that month.
+///
+/// This is the gregorian algorithm of Neri and Schneider with a single level of cycles: the
+/// cycles of 4 years start on March 1st, and the year, the month and the day are found with a
+/// multiplication and a division each.
+const fn day_to_month_and_zday(day: Mark) -> (Mark, Mark) {
+    // The offset to julian March 1st, 2000 is taken from the day in the cycle, not from the day
+    let mut cycle = day.div_euclid(DAYS_IN_4_YEARS);
+    let mut zday_in_cycle = day.rem_euclid(DAYS_IN_4_YEARS) - MARCH_1ST_2000;
+    let is_before_march = (zday_in_cycle < 0) as Mark;
+    cycle -= is_before_march;
+    zday_in_cycle += is_before_march * DAYS_IN_4_YEARS;
+    let n = 4 * zday_in_cycle + 3;
+    let zyear_in_cycle = n / DAYS_IN_4_YEARS;
+    let zday_in_year = n % DAYS_IN_4_YEARS / 4;
+    let zmonth_in_year = (5 * zday_in_year + 2) / 153;
+    let zday_in_month = zday_in_year - (153 * zmonth_in_year + 2) / 5;
+    let month = cycle * 48 + zyear_in_cycle * 12 + zmonth_in_year + 3;
+    (month, zday_in_month)
+}
+
+/// The day the gregorian calendar replaced the julian calendar, for a mixed historical calendar.
+///
+/// Days before the changeover use the julian calendar, the changeover day and the days after it
+/// use the gregorian calendar. The month of the changeover is shorter than usual: in 1582, julian
+/// Thursday October 4th was followed by gregorian Friday October 15th.
+///
+/// Months and years are numbered like in both calendars, see [`crate::year_to_month`].
+///
+/// # Examples
+/// ```
+/// use timelane::julian::Changeover;
+/// let changeover = Changeover::GREGORIAN;
+/// let day = changeover.date_to_day(1582, 10, 4).unwrap();
+/// assert_eq!(changeover.date_to_day(1582, 10, 15), Some(day + 1));
+/// assert_eq!(changeover.date_to_day(1582, 10, 10), None);
+/// assert_eq!(changeover.day_to_date(day + 1), (1582, 10, 15));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Changeover {
+    first_gregorian_day: Mark,
+}
+
+impl Changeover {
+    /// The changeover of the papal bull Inter gravissimas, adopted by most catholic countries:
+    /// gregorian October 15th, 1582.
+    pub const GREGORIAN: Changeover = Changeover {
+        first_gregorian_day: year_month_day_to_day(1582, 10, 15),
+    };
+
+    /// The changeover of Great Britain and its colonies: gregorian September 14th, 1752.
+    pub const BRITISH: Changeover = Changeover {
+        first_gregorian_day: year_month_day_to_day(1752, 9, 14),
+    };
+
+    /// Returns the changeover where `first_gregorian_day` is the first day [`Mark`] of the
+    /// gregorian calendar, or `None` if the julian date of the previous day does not come before
+    /// the gregorian date of that day, which only happens before March 200AD.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::julian::Changeover;
+    /// use timelane::{month_to_day, year_to_month};
+    /// let russian = Changeover::new(month_to_day(year_to_month(1918) + 1) + 13).unwrap();
+    /// assert_eq!(russian.day_to_date(russian.first_gregorian_day() - 1), (1918, 1, 31));
+    /// assert_eq!(russian.day_to_date(russian.first_gregorian_day()), (1918, 2, 14));
+    /// assert_eq!(Changeover::new(month_to_day(year_to_month(100))), None);
+    /// ```
+    pub const fn new(first_gregorian_day: Mark) -> Option<Changeover> {
+        let (julian_month, julian_zday) = day_to_month_and_zday(first_gregorian_day - 1);
+        let gregorian_month = crate::day_to_month(first_gregorian_day);
+        let gregorian_zday = first_gregorian_day - crate::month_to_day(gregorian_month);
+        if julian_month < gregorian_month
+            || (julian_month == gregorian_month && julian_zday < gregorian_zday)
+        {
+            Some(Changeover {
+                first_gregorian_day,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the first day [`Mark`] of the gregorian calendar.
+    pub const fn first_gregorian_day(self) -> Mark {
+        self.first_gregorian_day
+    }
+
+    /// Returns whether a day [`Mark`] uses the gregorian calendar.
+    pub const fn is_gregorian(self, day: Mark) -> bool {
+        day >= self.first_gregorian_day
+    }
+
+    /// Converts a month [`Mark`] to the day [`Mark`] where it starts.
+    ///
+    /// A month entirely skipped by the changeover starts, and ends, on the changeover day.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::julian::{self, Changeover};
+    /// use timelane::year_to_month;
+    /// let changeover = Changeover::GREGORIAN;
+    /// let october_1582 = year_to_month(1582) + 9;
+    /// assert_eq!(changeover.month_to_day(october_1582), julian::month_to_day(october_1582));
+    /// assert_eq!(
+    ///     changeover.month_to_day(october_1582 + 1) - changeover.month_to_day(october_1582),
+    ///     21,
+    /// );
+    /// ```
+    pub const fn month_to_day(self, month: Mark) -> Mark {
+        let julian_day = month_to_day(month);
+        if julian_day < self.first_gregorian_day {
+            julian_day
+        } else {
+            let gregorian_day = crate::month_to_day(month);
+            if gregorian_day > self.first_gregorian_day {
+                gregorian_day
+            } else {
+                self.first_gregorian_day
+            }
+        }
+    }
+
+    /// Converts a day [`Mark`] to a rounded down month [`Mark`].
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::julian::Changeover;
+    /// use timelane::year_to_month;
+    /// let changeover = Changeover::GREGORIAN;
+    /// let october_1582 = year_to_month(1582) + 9;
+    /// let first_gregorian_day = changeover.first_gregorian_day();
+    /// assert_eq!(changeover.day_to_month(first_gregorian_day - 1), october_1582);
+    /// assert_eq!(changeover.day_to_month(first_gregorian_day + 16), october_1582);
+    /// assert_eq!(changeover.day_to_month(first_gregorian_day + 17), october_1582 + 1);
+    /// ```
+    pub const fn day_to_month(self, day: Mark) -> Mark {
+        if day < self.first_gregorian_day {
+            day_to_month(day)
+        } else {
+            crate::day_to_month(day)
+        }
+    }
+
+    /// Converts a day [`Mark`] to a rounded up month [`Mark`].
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::julian::Changeover;
+    /// use timelane::year_to_month;
+    /// let changeover = Changeover::GREGORIAN;
+    /// let october_1582 = year_to_month(1582) + 9;
+    /// let first_gregorian_day = changeover.first_gregorian_day();
+    /// assert_eq!(changeover.day_to_month_up(first_gregorian_day), october_1582 + 1);
+    /// assert_eq!(changeover.day_to_month_up(first_gregorian_day + 17), october_1582 + 1);
+    /// ```
+    pub const fn day_to_month_up(self, day: Mark) -> Mark {
+        let month = self.day_to_month(day);
+        if self.month_to_day(month) == day {
+            month
+        } else {
+            month + 1
+        }
+    }
+
+    /// Returns the day [`Mark`] of a date, in the calendar in use at that date, with a one-based
+    /// month of year and day of month.
+    ///
+    /// Returns `None` for the dates skipped by the changeover, and for invalid dates.
+    pub const fn date_to_day(self, year: Mark, month: Mark, day: Mark) -> Option<Mark> {
+        if month < 1 || month > 12 || day < 1 {
+            return None;
+        }
+        let month = year_to_month(year) + month - 1;
+        let zday = day - 1;
+        let julian_start = month_to_day(month);
+        if zday < month_to_day(month + 1) - julian_start
+            && julian_start + zday < self.first_gregorian_day
+        {
+            return Some(julian_start + zday);
+        }
+        let gregorian_start = crate::month_to_day(month);
+        if zday < crate::month_to_day(month + 1) - gregorian_start
+            && gregorian_start + zday >= self.first_gregorian_day
+        {
+            return Some(gregorian_start + zday);
+        }
+        None
+    }
+
+    /// Splits a day [`Mark`] into a year, a one-based month of year and a one-based day of month,
+    /// in the calendar in use at that day.
+    pub const fn day_to_date(self, day: Mark) -> (Mark, Mark, Mark) {
+        let (month, zday_in_month) = if day < self.first_gregorian_day {
+            day_to_month_and_zday(day)
+        } else {
+            let month = crate::day_to_month(day);
+            (month, day - crate::month_to_day(month))
+        };
+        let year = month_to_year(month);
+        (year, month - year_to_month(year) + 1, zday_in_month + 1)
+    }
+}
+
+impl Default for Changeover {
+    /// Returns [`Changeover::GREGORIAN`].
+    fn default() -> Self {
+        Changeover::GREGORIAN
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reference_calendar::rata_die_to_day;
+    use crate::test_util::assert_month_walk;
+
+    /// Dates as a Rata Die and a julian year, month and day, with astronomical year numbering.
+    const RATA_DIE_DATES: [(Mark, Mark, Mark, Mark); 34] = [
+        (-1, 1, 1, 1),
+        (-214_193, -586, 7, 30),
+        (-61_387, -168, 12, 8),
+        (25_469, 70, 9, 26),
+        (49_217, 135, 10, 3),
+        (171_307, 470, 1, 7),
+        (210_155, 576, 5, 18),
+        (253_427, 694, 11, 7),
+        (369_740, 1013, 4, 19),
+        (400_085, 1096, 5, 18),
+        (434_355, 1190, 3, 16),
+        (452_605, 1240, 3, 3),
+        (470_160, 1288, 3, 26),
+        (473_837, 1298, 4, 20),
+        (507_850, 1391, 6, 4),
+        (524_156, 1436, 1, 25),
+        (544_676, 1492, 3, 31),
+        (567_118, 1553, 9, 9),
+        (569_477, 1560, 2, 24),
+        (601_716, 1648, 5, 31),
+        (613_424, 1680, 6, 20),
+        (626_596, 1716, 7, 13),
+        (645_554, 1768, 6, 8),
+        (664_224, 1819, 7, 21),
+        (671_401, 1839, 3, 15),
+        (694_799, 1903, 4, 6),
+        (704_424, 1929, 8, 12),
+        (708_842, 1941, 9, 16),
+        (709_409, 1943, 4, 6),
+        (709_580, 1943, 9, 24),
+        (727_274, 1992, 3, 4),
+        (728_714, 1996, 2, 12),
+        (744_313, 2038, 10, 28),
+        (764_652, 2094, 7, 5),
+    ];
+
+    fn days_in_month(year: Mark, month: Mark) -> Mark {
+        match month {
+            2 if year.rem_euclid(4) == 0 => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    #[test]
+    fn scalers_match_published_dates() {
+        for (rata_die, year, month, day) in RATA_DIE_DATES {
+            let month = year_to_month(year) + month - 1;
+            let mark = rata_die_to_day(rata_die);
+            assert_eq!(month_to_day(month) + day - 1, mark, "{}", rata_die);
+            assert_eq!(day_to_month(mark), month, "{}", rata_die);
+            assert_eq!(
+                Changeover::default().day_to_date(mark).1,
+                if rata_die < 577_736 {
+                    month - year_to_month(year) + 1
+                } else {
+                    crate::civil::day_to_year_month_day(mark).1
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn scalers_match_a_walk_over_thousands_of_years() {
+        assert_month_walk(
+            20_000 * 12,
+            14,
+            |month| days_in_month(month_to_year(month), (month - 1).rem_euclid(12) + 1),
+            month_to_day,
+            day_to_month,
+            day_to_month_up,
+        );
+    }
+
+    #[test]
+    fn leap_days_match_the_julian_rule() {
+        for year in -2_000..2_000 {
+            let is_leap_year = year % 4 == 0;
+            assert_eq!(
+                leap_days_before_year(year + 1) - leap_days_before_year(year),
+                is_leap_year as Mark,
+                "{}",
+                year
+            );
+            let days = month_to_day(year_to_month(year + 1)) - month_to_day(year_to_month(year));
+            assert_eq!(days, 365 + is_leap_year as Mark, "{}", year);
+        }
+        assert!(leap_days_before_year(Mark::MIN) < leap_days_before_year(Mark::MAX));
+    }
+
+    #[test]
+    fn changeovers_skip_the_days_between_the_calendars() {
+        let gregorian = Changeover::GREGORIAN;
+        assert_eq!(gregorian.first_gregorian_day(), rata_die_to_day(577_736));
+        assert_eq!(gregorian.date_to_day(1582, 10, 4), Some(-152_384));
+        assert_eq!(gregorian.date_to_day(1582, 10, 15), Some(-152_383));
+        for day in 5..15 {
+            assert_eq!(gregorian.date_to_day(1582, 10, day), None);
+        }
+        let british = Changeover::BRITISH;
+        let day = british.date_to_day(1752, 9, 2).unwrap();
+        assert_eq!(british.date_to_day(1752, 9, 14), Some(day + 1));
+        assert_eq!(british.day_to_date(day + 1), (1752, 9, 14));
+        assert_eq!(
+            Changeover::new(british.first_gregorian_day()),
+            Some(british)
+        );
+        assert_eq!(gregorian.date_to_day(1582, 2, 29), None);
+        assert_eq!(
+            gregorian.date_to_day(1500, 2, 29),
+            Some(month_to_day(-5998) + 28)
+        );
+        assert_eq!(gregorian.date_to_day(1700, 2, 29), None);
+        assert_eq!(gregorian.date_to_day(1582, 13, 1), None);
+        assert_eq!(gregorian.date_to_day(1582, 1, 0), None);
+    }
+
+    #[test]
+    fn changeovers_keep_the_months_in_order() {
+        let months = year_to_month(200) + 2..year_to_month(3_000);
+        let changeovers = months
+            .clone()
+            .step_by(7)
+            .map(|month| crate::month_to_day(month) + month % 31)
+            .filter_map(Changeover::new);
+        for changeover in changeovers {
+            let first = changeover.first_gregorian_day();
+            let mut previous = changeover.day_to_date(first - 100);
+            for day in first - 99..first + 100 {
+                let month = changeover.day_to_month(day);
+                let month_up = changeover.day_to_month_up(day);
+                assert!(changeover.month_to_day(month) <= day);
+                assert!(changeover.month_to_day(month_up) >= day);
+                assert!(month_up == month || month_up == month + 1);
+                let date = changeover.day_to_date(day);
+                assert!(date > previous, "{:?} {:?}", date, previous);
+                assert_eq!(changeover.date_to_day(date.0, date.1, date.2), Some(day));
+                assert_eq!(year_to_month(date.0) + date.1 - 1, month);
+                assert_eq!(changeover.is_gregorian(day), day >= first);
+                previous = date;
+            }
+        }
+        for month in months.clone() {
+            assert!(Changeover::new(crate::month_to_day(month)).is_some());
+        }
+        let march_200 = crate::month_to_day(months.start);
+        assert!(Changeover::new(march_200 - 1).is_none());
+    }
+}
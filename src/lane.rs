@@ -0,0 +1,543 @@
+//! Types naming each lane, to write code working with [`Mark`] of any lane.
+//!
+//! Lanes are related to each other through the second lane: every [`Lane`] knows the second, and
+//! the nanosecond within that second, where each of its marks starts. Converting between two
+//! lanes goes through that position, using the [`crate::Scaler`] functions of the crate.
+//!
+//! # Examples
+//! ```
+//! use timelane::lane::{Day, Lane, Month};
+//! use timelane::month_to_day;
+//! let (second, nanosecond) = Month::to_second_and_nanosecond(2);
+//! assert_eq!(Day::from_second_and_nanosecond(second, nanosecond), month_to_day(2));
+//! ```
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::subsecond::{
+    microsecond_to_second, microsecond_to_second_up, millisecond_to_second,
+    millisecond_to_second_up, nanosecond_to_second, nanosecond_to_second_up, second_to_microsecond,
+    second_to_millisecond, second_to_nanosecond,
+};
+use crate::{
+    day_to_hour, day_to_month, day_to_month_up, hour_to_day, hour_to_day_up, hour_to_minute,
+    minute_to_hour, minute_to_hour_up, minute_to_second, month_to_day, month_to_year,
+    month_to_year_up, second_to_minute, second_to_minute_up, year_to_month, Mark,
+};
+use crate::{hebrew, islamic, julian};
+
+/// A lane of time, where each [`Mark`] lasts a year, a month, a day...
+pub trait Lane {
+    /// The smallest second [`Mark`] that can be converted to this lane without overflowing.
+    const MIN_SECOND: Mark;
+
+    /// The largest second [`Mark`] that can be converted to this lane without overflowing, with
+    /// any number of nanoseconds.
+    const MAX_SECOND: Mark;
+
+    /// Returns the second [`Mark`], and the nanoseconds after that second, where a [`Mark`] of
+    /// this lane starts.
+    fn to_second_and_nanosecond(mark: Mark) -> (Mark, Mark);
+
+    /// Returns the rounded down [`Mark`] of this lane containing a second [`Mark`] and a number
+    /// of nanoseconds after that second.
+    fn from_second_and_nanosecond(second: Mark, nanosecond: Mark) -> Mark;
+
+    /// Returns the rounded up [`Mark`] of this lane containing a second [`Mark`] and a number of
+    /// nanoseconds after that second.
+    fn from_second_and_nanosecond_up(second: Mark, nanosecond: Mark) -> Mark;
+}
+
+/// The lane of years, see [`crate::year_to_month`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Year;
+
+/// The lane of months, see [`crate::month_to_day`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Month;
+
+/// The lane of years of the julian calendar, see [`crate::julian`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JulianYear;
+
+/// The lane of months of the julian calendar, see [`crate::julian::month_to_day`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JulianMonth;
+
+/// The lane of years of the tabular islamic calendar, see [`crate::islamic::year_to_month`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IslamicYear;
+
+/// The lane of months of the tabular islamic calendar, see [`crate::islamic::month_to_day`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IslamicMonth;
+
+/// The lane of years of the hebrew calendar, see [`crate::hebrew::year_to_month`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HebrewYear;
+
+/// The lane of months of the hebrew calendar, see [`crate::hebrew::month_to_day`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HebrewMonth;
+
+/// The lane of days, see [`crate::day_to_hour`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Day;
+
+/// The lane of hours, see [`crate::hour_to_minute`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hour;
+
+/// The lane of minutes, see [`crate::minute_to_second`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Minute;
+
+/// The lane of seconds, including leap seconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Second;
+
+/// The lane of milliseconds, see [`crate::subsecond::second_to_millisecond`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Millisecond;
+
+/// The lane of microseconds, see [`crate::subsecond::second_to_microsecond`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Microsecond;
+
+/// The lane of nanoseconds, see [`crate::subsecond::second_to_nanosecond`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Nanosecond;
+
+/// A [`Mark`] of a known lane, to convert from and to the date and time types of other crates.
+///
+/// # Examples
+/// ```
+/// use timelane::lane::{Day, LaneMark};
+/// let day = LaneMark::<Day>::new(1);
+/// assert_eq!(day.mark(), 1);
+/// assert_eq!(timelane::Mark::from(day), 1);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LaneMark<L> {
+    mark: Mark,
+    lane: PhantomData<L>,
+}
+
+impl<L: Lane> LaneMark<L> {
+    /// Tags a [`Mark`] with its lane.
+    pub const fn new(mark: Mark) -> Self {
+        Self {
+            mark,
+            lane: PhantomData,
+        }
+    }
+
+    /// Returns the untagged [`Mark`].
+    pub const fn mark(self) -> Mark {
+        self.mark
+    }
+
+    /// Returns the rounded down [`LaneMark`] containing a UTC date and time, or an error if it
+    /// does not fit in the lane.
+    ///
+    /// The month and the day of month are one-based, the second can be 60 during a leap second.
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    pub(crate) fn from_civil(
+        (year, month, day): (Mark, Mark, Mark),
+        (hour, minute, second): (Mark, Mark, Mark),
+        nanosecond: Mark,
+    ) -> Result<Self, ConversionError> {
+        if (year - crate::EPOCH_YEAR).abs() > crate::parse::MAX_SECOND_ZYEAR {
+            return Err(ConversionError::OutOfRange);
+        }
+        let day = crate::civil::year_month_day_to_day(year, month, day);
+        let minute = hour_to_minute(day_to_hour(day) + hour) + minute;
+        if second == 60 && !crate::civil::minute_has_leap_second(minute) {
+            return Err(ConversionError::InvalidLeapSecond);
+        }
+        let second = minute_to_second(minute) + second;
+        if second < L::MIN_SECOND || second > L::MAX_SECOND {
+            return Err(ConversionError::OutOfRange);
+        }
+        Ok(Self::new(L::from_second_and_nanosecond(second, nanosecond)))
+    }
+
+    /// Returns the UTC date and time where this mark starts, with the same fields as
+    /// [`LaneMark::from_civil`].
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    pub(crate) fn to_civil(&self) -> ((Mark, Mark, Mark), (Mark, Mark, Mark), Mark) {
+        let (second, nanosecond) = L::to_second_and_nanosecond(self.mark);
+        let (minute, second) = crate::civil::second_to_minute_and_second(second);
+        let (day, hour, minute) = crate::civil::minute_to_day_hour_minute(minute);
+        (
+            crate::civil::day_to_year_month_day(day),
+            (hour, minute, second),
+            nanosecond,
+        )
+    }
+}
+
+impl<L: Lane> From<LaneMark<L>> for Mark {
+    fn from(mark: LaneMark<L>) -> Mark {
+        mark.mark
+    }
+}
+
+/// An error converting between a [`LaneMark`] and the date and time type of another crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ConversionError {
+    /// The date and time does not fit in the target type.
+    OutOfRange,
+    /// The date and time is a leap second that [`crate::LEAP_SECONDS_MARKS`] does not declare.
+    InvalidLeapSecond,
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ConversionError::OutOfRange => "the date and time is out of range",
+            ConversionError::InvalidLeapSecond => "the date and time is not a leap second",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConversionError {}
+
+/// Returns whether a [`Mark`] of a lane starts between [`Lane::MIN_SECOND`] and
+/// [`Lane::MAX_SECOND`], where converting it to a second does not overflow.
+#[cfg(any(feature = "std", feature = "serde"))]
+pub(crate) fn starts_in_lane<L: Lane>(mark: Mark) -> bool {
+    mark >= L::from_second_and_nanosecond_up(L::MIN_SECOND, 0)
+        && mark <= L::from_second_and_nanosecond(L::MAX_SECOND, 999_999_999)
+}
+
+/// Returns the second [`Mark`] to use when rounding up, a second is only rounded up to the next
+/// one if it has some nanoseconds.
+const fn second_up(second: Mark, nanosecond: Mark) -> Mark {
+    if nanosecond > 0 {
+        second + 1
+    } else {
+        second
+    }
+}
+
+impl Lane for Year {
+    const MIN_SECOND: Mark = Mark::MIN;
+    const MAX_SECOND: Mark = Mark::MAX - 1;
+
+    fn to_second_and_nanosecond(mark: Mark) -> (Mark, Mark) {
+        Month::to_second_and_nanosecond(year_to_month(mark))
+    }
+
+    fn from_second_and_nanosecond(second: Mark, nanosecond: Mark) -> Mark {
+        month_to_year(Month::from_second_and_nanosecond(second, nanosecond))
+    }
+
+    fn from_second_and_nanosecond_up(second: Mark, nanosecond: Mark) -> Mark {
+        month_to_year_up(Month::from_second_and_nanosecond_up(second, nanosecond))
+    }
+}
+
+impl Lane for Month {
+    const MIN_SECOND: Mark = Mark::MIN;
+    const MAX_SECOND: Mark = Mark::MAX - 1;
+
+    fn to_second_and_nanosecond(mark: Mark) -> (Mark, Mark) {
+        Day::to_second_and_nanosecond(month_to_day(mark))
+    }
+
+    fn from_second_and_nanosecond(second: Mark, nanosecond: Mark) -> Mark {
+        day_to_month(Day::from_second_and_nanosecond(second, nanosecond))
+    }
+
+    fn from_second_and_nanosecond_up(second: Mark, nanosecond: Mark) -> Mark {
+        day_to_month_up(Day::from_second_and_nanosecond_up(second, nanosecond))
+    }
+}
+
+impl Lane for JulianYear {
+    const MIN_SECOND: Mark = Mark::MIN;
+    const MAX_SECOND: Mark = Mark::MAX - 1;
+
+    fn to_second_and_nanosecond(mark: Mark) -> (Mark, Mark) {
+        JulianMonth::to_second_and_nanosecond(year_to_month(mark))
+    }
+
+    fn from_second_and_nanosecond(second: Mark, nanosecond: Mark) -> Mark {
+        month_to_year(JulianMonth::from_second_and_nanosecond(second, nanosecond))
+    }
+
+    fn from_second_and_nanosecond_up(second: Mark, nanosecond: Mark) -> Mark {
+        month_to_year_up(JulianMonth::from_second_and_nanosecond_up(
+            second, nanosecond,
+        ))
+    }
+}
+
+impl Lane for JulianMonth {
+    const MIN_SECOND: Mark = Mark::MIN;
+    const MAX_SECOND: Mark = Mark::MAX - 1;
+
+    fn to_second_and_nanosecond(mark: Mark) -> (Mark, Mark) {
+        Day::to_second_and_nanosecond(julian::month_to_day(mark))
+    }
+
+    fn from_second_and_nanosecond(second: Mark, nanosecond: Mark) -> Mark {
+        julian::day_to_month(Day::from_second_and_nanosecond(second, nanosecond))
+    }
+
+    fn from_second_and_nanosecond_up(second: Mark, nanosecond: Mark) -> Mark {
+        julian::day_to_month_up(Day::from_second_and_nanosecond_up(second, nanosecond))
+    }
+}
+
+impl Lane for IslamicYear {
+    const MIN_SECOND: Mark = Mark::MIN;
+    const MAX_SECOND: Mark = Mark::MAX - 1;
+
+    fn to_second_and_nanosecond(mark: Mark) -> (Mark, Mark) {
+        IslamicMonth::to_second_and_nanosecond(islamic::year_to_month(mark))
+    }
+
+    fn from_second_and_nanosecond(second: Mark, nanosecond: Mark) -> Mark {
+        islamic::month_to_year(IslamicMonth::from_second_and_nanosecond(second, nanosecond))
+    }
+
+    fn from_second_and_nanosecond_up(second: Mark, nanosecond: Mark) -> Mark {
+        islamic::month_to_year_up(IslamicMonth::from_second_and_nanosecond_up(
+            second, nanosecond,
+        ))
+    }
+}
+
+impl Lane for IslamicMonth {
+    const MIN_SECOND: Mark = Mark::MIN;
+    const MAX_SECOND: Mark = Mark::MAX - 1;
+
+    fn to_second_and_nanosecond(mark: Mark) -> (Mark, Mark) {
+        Day::to_second_and_nanosecond(islamic::month_to_day(mark))
+    }
+
+    fn from_second_and_nanosecond(second: Mark, nanosecond: Mark) -> Mark {
+        islamic::day_to_month(Day::from_second_and_nanosecond(second, nanosecond))
+    }
+
+    fn from_second_and_nanosecond_up(second: Mark, nanosecond: Mark) -> Mark {
+        islamic::day_to_month_up(Day::from_second_and_nanosecond_up(second, nanosecond))
+    }
+}
+
+impl Lane for HebrewYear {
+    const MIN_SECOND: Mark = Mark::MIN;
+    const MAX_SECOND: Mark = Mark::MAX - 1;
+
+    fn to_second_and_nanosecond(mark: Mark) -> (Mark, Mark) {
+        HebrewMonth::to_second_and_nanosecond(hebrew::year_to_month(mark))
+    }
+
+    fn from_second_and_nanosecond(second: Mark, nanosecond: Mark) -> Mark {
+        hebrew::month_to_year(HebrewMonth::from_second_and_nanosecond(second, nanosecond))
+    }
+
+    fn from_second_and_nanosecond_up(second: Mark, nanosecond: Mark) -> Mark {
+        hebrew::month_to_year_up(HebrewMonth::from_second_and_nanosecond_up(
+            second, nanosecond,
+        ))
+    }
+}
+
+impl Lane for HebrewMonth {
+    const MIN_SECOND: Mark = Mark::MIN;
+    const MAX_SECOND: Mark = Mark::MAX - 1;
+
+    fn to_second_and_nanosecond(mark: Mark) -> (Mark, Mark) {
+        Day::to_second_and_nanosecond(hebrew::month_to_day(mark))
+    }
+
+    fn from_second_and_nanosecond(second: Mark, nanosecond: Mark) -> Mark {
+        hebrew::day_to_month(Day::from_second_and_nanosecond(second, nanosecond))
+    }
+
+    fn from_second_and_nanosecond_up(second: Mark, nanosecond: Mark) -> Mark {
+        hebrew::day_to_month_up(Day::from_second_and_nanosecond_up(second, nanosecond))
+    }
+}
+
+impl Lane for Day {
+    const MIN_SECOND: Mark = Mark::MIN;
+    const MAX_SECOND: Mark = Mark::MAX - 1;
+
+    fn to_second_and_nanosecond(mark: Mark) -> (Mark, Mark) {
+        Hour::to_second_and_nanosecond(day_to_hour(mark))
+    }
+
+    fn from_second_and_nanosecond(second: Mark, nanosecond: Mark) -> Mark {
+        hour_to_day(Hour::from_second_and_nanosecond(second, nanosecond))
+    }
+
+    fn from_second_and_nanosecond_up(second: Mark, nanosecond: Mark) -> Mark {
+        hour_to_day_up(Hour::from_second_and_nanosecond_up(second, nanosecond))
+    }
+}
+
+impl Lane for Hour {
+    const MIN_SECOND: Mark = Mark::MIN;
+    const MAX_SECOND: Mark = Mark::MAX - 1;
+
+    fn to_second_and_nanosecond(mark: Mark) -> (Mark, Mark) {
+        Minute::to_second_and_nanosecond(hour_to_minute(mark))
+    }
+
+    fn from_second_and_nanosecond(second: Mark, nanosecond: Mark) -> Mark {
+        minute_to_hour(Minute::from_second_and_nanosecond(second, nanosecond))
+    }
+
+    fn from_second_and_nanosecond_up(second: Mark, nanosecond: Mark) -> Mark {
+        minute_to_hour_up(Minute::from_second_and_nanosecond_up(second, nanosecond))
+    }
+}
+
+impl Lane for Minute {
+    const MIN_SECOND: Mark = Mark::MIN;
+    const MAX_SECOND: Mark = Mark::MAX - 1;
+
+    fn to_second_and_nanosecond(mark: Mark) -> (Mark, Mark) {
+        (minute_to_second(mark), 0)
+    }
+
+    fn from_second_and_nanosecond(second: Mark, _nanosecond: Mark) -> Mark {
+        second_to_minute(second)
+    }
+
+    fn from_second_and_nanosecond_up(second: Mark, nanosecond: Mark) -> Mark {
+        second_to_minute_up(second_up(second, nanosecond))
+    }
+}
+
+impl Lane for Second {
+    const MIN_SECOND: Mark = Mark::MIN;
+    const MAX_SECOND: Mark = Mark::MAX - 1;
+
+    fn to_second_and_nanosecond(mark: Mark) -> (Mark, Mark) {
+        (mark, 0)
+    }
+
+    fn from_second_and_nanosecond(second: Mark, _nanosecond: Mark) -> Mark {
+        second
+    }
+
+    fn from_second_and_nanosecond_up(second: Mark, nanosecond: Mark) -> Mark {
+        second_up(second, nanosecond)
+    }
+}
+
+impl Lane for Millisecond {
+    const MIN_SECOND: Mark = millisecond_to_second_up(Mark::MIN);
+    const MAX_SECOND: Mark = millisecond_to_second(Mark::MAX) - 1;
+
+    fn to_second_and_nanosecond(mark: Mark) -> (Mark, Mark) {
+        let second = millisecond_to_second(mark);
+        (second, (mark - second_to_millisecond(second)) * 1_000_000)
+    }
+
+    fn from_second_and_nanosecond(second: Mark, nanosecond: Mark) -> Mark {
+        second_to_millisecond(second) + nanosecond / 1_000_000
+    }
+
+    fn from_second_and_nanosecond_up(second: Mark, nanosecond: Mark) -> Mark {
+        second_to_millisecond(second) + (nanosecond + 999_999) / 1_000_000
+    }
+}
+
+impl Lane for Microsecond {
+    const MIN_SECOND: Mark = microsecond_to_second_up(Mark::MIN);
+    const MAX_SECOND: Mark = microsecond_to_second(Mark::MAX) - 1;
+
+    fn to_second_and_nanosecond(mark: Mark) -> (Mark, Mark) {
+        let second = microsecond_to_second(mark);
+        (second, (mark - second_to_microsecond(second)) * 1_000)
+    }
+
+    fn from_second_and_nanosecond(second: Mark, nanosecond: Mark) -> Mark {
+        second_to_microsecond(second) + nanosecond / 1_000
+    }
+
+    fn from_second_and_nanosecond_up(second: Mark, nanosecond: Mark) -> Mark {
+        second_to_microsecond(second) + (nanosecond + 999) / 1_000
+    }
+}
+
+impl Lane for Nanosecond {
+    const MIN_SECOND: Mark = nanosecond_to_second_up(Mark::MIN);
+    const MAX_SECOND: Mark = nanosecond_to_second(Mark::MAX) - 1;
+
+    fn to_second_and_nanosecond(mark: Mark) -> (Mark, Mark) {
+        let second = nanosecond_to_second(mark);
+        (second, mark - second_to_nanosecond(second))
+    }
+
+    fn from_second_and_nanosecond(second: Mark, nanosecond: Mark) -> Mark {
+        second_to_nanosecond(second) + nanosecond
+    }
+
+    fn from_second_and_nanosecond_up(second: Mark, nanosecond: Mark) -> Mark {
+        second_to_nanosecond(second) + nanosecond
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip<L: Lane>(marks: core::ops::Range<Mark>) {
+        for mark in marks {
+            let (second, nanosecond) = L::to_second_and_nanosecond(mark);
+            assert_eq!(L::from_second_and_nanosecond(second, nanosecond), mark);
+            assert_eq!(L::from_second_and_nanosecond_up(second, nanosecond), mark);
+        }
+    }
+
+    #[test]
+    fn marks_roundtrip_through_second_and_nanosecond() {
+        roundtrip::<Year>(-3000..3000);
+        roundtrip::<Month>(-3000..3000);
+        roundtrip::<JulianYear>(-3000..3000);
+        roundtrip::<JulianMonth>(-3000..3000);
+        roundtrip::<IslamicYear>(-3000..3000);
+        roundtrip::<IslamicMonth>(-3000..3000);
+        roundtrip::<HebrewYear>(-3000..3000);
+        roundtrip::<HebrewMonth>(-3000..3000);
+        roundtrip::<Day>(-3000..3000);
+        roundtrip::<Hour>(-3000..3000);
+        roundtrip::<Minute>(-3000..3000);
+        roundtrip::<Second>(-3000..3000);
+        roundtrip::<Millisecond>(-3000..3000);
+        roundtrip::<Microsecond>(-3000..3000);
+        roundtrip::<Nanosecond>(-3000..3000);
+    }
+
+    #[test]
+    fn nanoseconds_round_to_enclosing_marks() {
+        assert_eq!(Second::from_second_and_nanosecond(-1, 1), -1);
+        assert_eq!(Second::from_second_and_nanosecond_up(-1, 1), 0);
+        assert_eq!(Minute::from_second_and_nanosecond(59, 1), 0);
+        assert_eq!(Minute::from_second_and_nanosecond_up(59, 1), 1);
+        assert_eq!(Millisecond::from_second_and_nanosecond(-1, 1_500_000), -999);
+        assert_eq!(
+            Millisecond::from_second_and_nanosecond_up(-1, 1_500_000),
+            -998
+        );
+        assert_eq!(Day::from_second_and_nanosecond_up(0, 1), 2);
+    }
+
+    #[test]
+    fn julian_lanes_start_13_days_after_the_gregorian_ones_in_2000() {
+        let (second, nanosecond) = JulianYear::to_second_and_nanosecond(2000);
+        assert_eq!((second, nanosecond), Day::to_second_and_nanosecond(14));
+        assert_eq!(Year::from_second_and_nanosecond(second, nanosecond), 2000);
+        assert_eq!(JulianYear::from_second_and_nanosecond(second - 1, 0), 1999);
+        assert_eq!(JulianMonth::from_second_and_nanosecond_up(second, 1), 2);
+    }
+}
@@ -11,7 +11,9 @@
 //! [`Mark`] values are signed integers.
 //!
 //! [`Scaler`] functions can be composed to convert [`Mark`] from non-adjacent
-//! lanes, like converting a year mark into a second mark.
+//! lanes, like converting a year mark into a second mark. The most common
+//! compositions, like [`second_to_day`] or [`year_to_second`], are also
+//! available as single functions giving the same results faster.
 //!
 //! A mark from a small scale lane can also be converted to a mark in a larger
 //! scale lane, like second to year, but you need to chose between rounding
@@ -29,27 +31,89 @@
 //! Weights and Measures resolved to eliminate leap seconds by or before 2035.
 //!
 //!  - Years before 1AD use the astronomical year numbering: the year 1BC is
-//!     mark `0`, 2BC is `-1`...
+//!    mark `0`, 2BC is `-1`...
 //!  - Leap years follow the proleptic gregorian calendar, and are defined even
-//!     for negative years: 1BC is a leap year.
+//!    for negative years: 1BC is a leap year. The `julian` module converts
+//!    days to the months of the proleptic julian calendar, or of a historical
+//!    calendar switching from the julian to the gregorian calendar. The
+//!    `islamic` module converts days to the months of the tabular islamic
+//!    calendar, and the `hebrew` module to the months of the hebrew calendar.
 //!  - Because of leap seconds, some minutes will contain 61 seconds.
 //!  - Before using this library, make sure you actually want to work with UTC.
 //!    Many systems use GPS or TAI, which do not include leap seconds.
+//!
+//! # Features
+//!
+//! The crate is `no_std` by default, only the `std` feature needs the standard
+//! library. Marks are `isize`, so on 32-bit targets the second lane only spans
+//! the years 1932 to 2068, and the nanosecond lane about two seconds.
+//!
+//!  - `alloc`: containers allocating memory, like the `span_set::SpanSet` of
+//!    disjoint spans, in the `span_set` module, and the expansion of RFC 5545
+//!    recurrence rules into marks in any `zone::Zone`, in the `rrule` module.
+//!  - `std`: conversions between marks and `std::time::SystemTime` or
+//!    `std::time::Duration`, with a choice of how leap seconds are mapped to
+//!    POSIX time, in the `system` module, and the `clock::SystemClock`. Error
+//!    types also implement `std::error::Error`. Enables `alloc`.
+//!  - `serde`: helper modules to serialize marks of any lane as integers or
+//!    RFC 3339 strings, in the `serde` module.
+//!  - `chrono`: conversions between typed `lane::LaneMark` and the date and
+//!    time types of `chrono`, where leap seconds convert exactly, in the
+//!    `chrono` module.
+//!  - `time`: conversions between typed `lane::LaneMark` and the date and time
+//!    types of `time`, where leap seconds are clamped, in the `time` module.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 /// A specific point on a time lane.
 pub type Mark = isize;
 /// A function to convert a [`Mark`] from one lane to another.
 pub type Scaler = fn(mark: Mark) -> Mark;
 
+#[macro_use]
+pub mod parse;
+
+pub mod batch;
+#[cfg(feature = "chrono")]
+pub mod chrono;
+mod civil;
+pub mod clock;
+pub mod cron;
+pub mod hebrew;
+pub mod islamic;
+pub mod iso8601;
+pub mod iter;
+pub mod julian;
+pub mod lane;
+pub mod period;
+#[cfg(test)]
+mod reference_calendar;
+pub mod rfc2822;
+#[cfg(feature = "alloc")]
+pub mod rrule;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub mod span;
+#[cfg(feature = "alloc")]
+pub mod span_set;
+pub mod strftime;
 pub mod subsecond;
+#[cfg(feature = "std")]
+pub mod system;
+#[cfg(test)]
+mod test_util;
+#[cfg(feature = "time")]
+pub mod time;
+pub mod zone;
 
 /// This year is the one where the first second of January 1st is the [`Mark`] 0.
 pub const EPOCH_YEAR: Mark = 2000;
 
 const ZMONTH_STARTS: [Mark; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
 
-const ZMONTH_STARTS_LEAP_YEAR: [Mark; 12] = [0, 31, 60, 91, 121, 152, 182, 213, 244, 274, 305, 335];
-
 /// All known leap seconds, as minute [`Mark`].
 pub const LEAP_SECONDS_MARKS: [Mark; 27] = [
     year_month_to_minute(1972, 7),
@@ -203,9 +267,21 @@ pub const fn minute_to_second(minute: Mark) -> Mark {
 /// assert_eq!(second_to_minute(Mark::MAX), 153722867280912930);
 /// ```
 pub const fn second_to_minute(second: Mark) -> Mark {
-    // The estimate is good-enough because we never have more than +/- 60 leap seconds.
+    // The estimate is at most one minute off because we never have more than +/- 60 leap seconds.
     let estimate = divide_towards_negative_infinity(second, 60);
-    divide_towards_negative_infinity(second - leap_seconds_before_minute(estimate), 60)
+    let minute =
+        divide_towards_negative_infinity(second - leap_seconds_before_minute(estimate), 60);
+    // The estimate can still be on the wrong side of a leap second, a minute starts at or before
+    // the second when `minute * 60 + leap_seconds_before_minute(minute) <= second`.
+    if minute > divide_towards_negative_infinity(second - leap_seconds_before_minute(minute), 60) {
+        minute - 1
+    } else if minute
+        < divide_towards_negative_infinity(second - leap_seconds_before_minute(minute + 1), 60)
+    {
+        minute + 1
+    } else {
+        minute
+    }
 }
 
 /// Converts a second [`Mark`] to a rounded up minute [`Mark`].
@@ -226,9 +302,23 @@ pub const fn second_to_minute(second: Mark) -> Mark {
 /// assert_eq!(second_to_minute_up(Mark::MAX), 153722867280912931);
 /// ```
 pub const fn second_to_minute_up(second: Mark) -> Mark {
-    // The estimate is good-enough because we never have more than +/- 60 leap seconds.
+    // The estimate is at most one minute off because we never have more than +/- 60 leap seconds.
     let estimate = divide_towards_positive_infinity(second, 60);
-    divide_towards_positive_infinity(second - leap_seconds_before_minute(estimate), 60)
+    let minute =
+        divide_towards_positive_infinity(second - leap_seconds_before_minute(estimate), 60);
+    // The estimate can still be on the wrong side of a leap second, a minute starts at or after
+    // the second when `minute * 60 + leap_seconds_before_minute(minute) >= second`.
+    if minute
+        > divide_towards_positive_infinity(second - leap_seconds_before_minute(minute - 1), 60)
+    {
+        minute - 1
+    } else if minute
+        < divide_towards_positive_infinity(second - leap_seconds_before_minute(minute), 60)
+    {
+        minute + 1
+    } else {
+        minute
+    }
 }
 
 /// Converts a minute [`Mark`] to a rounded down hour [`Mark`].
@@ -321,21 +411,12 @@ pub const fn hour_to_day_up(hour: Mark) -> Mark {
 /// assert_eq!(day_to_month(1), 1, "day 1 rounds down to month 1");
 /// assert_eq!(day_to_month(0), 0, "day 0 rounds down to month 0");
 /// use timelane::Mark;
-/// assert_eq!(day_to_month(Mark::MIN), -303032819133198654); // TODO: check this
-/// assert_eq!(day_to_month(Mark::MAX), 303032819133198655); // TODO: check this
+/// assert_eq!(day_to_month(Mark::MIN), -303032819133198654);
+/// assert_eq!(day_to_month(Mark::MAX), 303032819133198655);
 /// ```
 pub const fn day_to_month(day: Mark) -> Mark {
-    let (zyear, zdays_in_year, is_leap_year) = day_to_zyear_and_days(day);
-    let month_ends = if is_leap_year {
-        ZMONTH_STARTS_LEAP_YEAR
-    } else {
-        ZMONTH_STARTS
-    };
-    let mut month = 1;
-    while month < month_ends.len() && zdays_in_year >= month_ends[month] {
-        month += 1;
-    }
-    zyear * 12 + month as Mark
+    let (month, _) = day_to_month_and_zday(day);
+    month
 }
 
 /// Converts a day [`Mark`] to a rounded up month [`Mark`].
@@ -353,49 +434,45 @@ pub const fn day_to_month(day: Mark) -> Mark {
 /// assert_eq!(day_to_month_up(1), 1, "day 1 rounds up to month 1");
 /// assert_eq!(day_to_month_up(0), 1, "day rounds up to is month 1");
 /// use timelane::Mark;
-/// assert_eq!(day_to_month_up(Mark::MIN), -303032819133198653); // TODO: check this
-/// assert_eq!(day_to_month_up(Mark::MAX), 303032819133198656); // TODO: check this
+/// assert_eq!(day_to_month_up(Mark::MIN), -303032819133198653);
+/// assert_eq!(day_to_month_up(Mark::MAX), 303032819133198656);
 /// ```
 pub const fn day_to_month_up(day: Mark) -> Mark {
-    let (zyear, zdays_in_year, is_leap_year) = day_to_zyear_and_days(day);
-    let month_ends = if is_leap_year {
-        ZMONTH_STARTS_LEAP_YEAR
-    } else {
-        ZMONTH_STARTS
-    };
-    let mut month = 1;
-    while month <= month_ends.len() && zdays_in_year > month_ends[month - 1] {
-        month += 1;
-    }
-    zyear * 12 + month as Mark
+    let (month, zday_in_month) = day_to_month_and_zday(day);
+    month + (zday_in_month != 0) as Mark
 }
 
-const fn day_to_zyear_and_days(day: Mark) -> (Mark, Mark, bool) {
-    if day == Mark::MIN {
-        // This avoids underflow when doing day - 1 in the other branch
-        let (zyear, days_in_year, is_leap_year) = day_to_zyear_and_days(day + 97 + 400 * 365);
-        return (zyear - 400, days_in_year, is_leap_year);
-    }
-    let zday = day - 1;
-    // We do a first guess of the zyear containing this zday
-    let mut zyear = divide_towards_negative_infinity(
-        zday - divide_towards_negative_infinity(zday, 97 + 400 * 365) * 97,
-        365,
-    );
-    // Then we compute the day that this year would have started, taking in account leap days, it should be before the zday
-    let mut leap_days =
-        leap_days_before_year(zyear + EPOCH_YEAR) - leap_days_before_year(EPOCH_YEAR);
-    let mut zstart_of_year = zyear * 365 + leap_days;
-    // If it's not, we move back one year
-    if zstart_of_year > zday {
-        zyear -= 1;
-        leap_days = leap_days_before_year(zyear + EPOCH_YEAR) - leap_days_before_year(EPOCH_YEAR);
-        zstart_of_year = zyear * 365 + leap_days;
-    }
-    let is_leap_year = (leap_days_before_year(zyear + 1 + EPOCH_YEAR)
-        - leap_days_before_year(EPOCH_YEAR))
-        > leap_days;
-    (zyear, zday - zstart_of_year, is_leap_year)
+/// The number of days in 400 years, after which leap years repeat.
+const DAYS_IN_400_YEARS: Mark = 97 + 400 * 365;
+
+/// Splits a day [`Mark`] into a month [`Mark`] and the zero-based day in that month.
+///
+/// This is the algorithm of Neri and Schneider, "Euclidean affine functions and their application
+/// to calendar algorithms" (2022): after splitting the days into cycles of 400 years, starting on
+/// March 1st so that leap days end the years, the century, the year of century, the month and
+/// the day are each found with a multiplication and a shift, without branches or tables.
+const fn day_to_month_and_zday(day: Mark) -> (Mark, Mark) {
+    // The offset to March 1st, 2000, the day 61, is taken from the day in the cycle, not the day
+    let mut cycle = day.div_euclid(DAYS_IN_400_YEARS);
+    let mut zday_in_cycle = day.rem_euclid(DAYS_IN_400_YEARS) - 61;
+    let is_before_march = (zday_in_cycle < 0) as Mark;
+    cycle -= is_before_march;
+    zday_in_cycle += is_before_march * DAYS_IN_400_YEARS;
+    // The century in the cycle, and the zero-based day in that century
+    let n1 = 4 * zday_in_cycle as u64 + 3;
+    let century = n1 / DAYS_IN_400_YEARS as u64;
+    let zday_in_century = n1 % DAYS_IN_400_YEARS as u64 / 4;
+    // The year in the century, and the zero-based day in that year
+    let n2 = 4 * zday_in_century + 3;
+    let p2 = 2_939_745 * n2;
+    let year_in_century = p2 >> 32;
+    let zday_in_year = (p2 & 0xffff_ffff) / 2_939_745 / 4;
+    // The month from 3 for March to 14 for February, and the zero-based day in that month
+    let n3 = 2141 * zday_in_year + 197_913;
+    let month_in_year = n3 >> 16;
+    let zday_in_month = (n3 & 0xffff) / 2141;
+    let month_in_cycle = (12 * (100 * century + year_in_century) + month_in_year) as Mark;
+    (cycle * 4800 + month_in_cycle, zday_in_month as Mark)
 }
 
 /// Converts a month [`Mark`] to a rounded down year [`Mark`].
@@ -409,8 +486,8 @@ const fn day_to_zyear_and_days(day: Mark) -> (Mark, Mark, bool) {
 /// assert_eq!(month_to_year(12), 2000);
 /// assert_eq!(month_to_year(13), 2001);
 /// use timelane::Mark;
-/// assert_eq!(month_to_year(Mark::MIN), -768614336404562651); // TODO: check this
-/// assert_eq!(month_to_year(Mark::MAX), 768614336404566650); // TODO: check this
+/// assert_eq!(month_to_year(Mark::MIN), -768614336404562651);
+/// assert_eq!(month_to_year(Mark::MAX), 768614336404566650);
 /// ```
 pub const fn month_to_year(month: Mark) -> Mark {
     if month == Mark::MIN {
@@ -435,8 +512,8 @@ pub const fn month_to_year(month: Mark) -> Mark {
 /// assert_eq!(month_to_year_up(12), 2001);
 /// assert_eq!(month_to_year_up(13), 2001);
 /// use timelane::Mark;
-/// assert_eq!(month_to_year_up(Mark::MIN), -768614336404562650); // TODO: check this
-/// assert_eq!(month_to_year_up(Mark::MAX), 768614336404566651); // TODO: check this
+/// assert_eq!(month_to_year_up(Mark::MIN), -768614336404562650);
+/// assert_eq!(month_to_year_up(Mark::MAX), 768614336404566651);
 /// ```
 pub const fn month_to_year_up(month: Mark) -> Mark {
     if month == Mark::MIN {
@@ -447,6 +524,179 @@ pub const fn month_to_year_up(month: Mark) -> Mark {
     }
 }
 
+/// Converts a second [`Mark`] to a rounded down day [`Mark`].
+///
+/// This gives the same day as [`second_to_minute`], [`minute_to_hour`] and [`hour_to_day`], in
+/// one pass: leap seconds are always inserted at the end of a day.
+///
+/// # Examples
+/// ```
+/// use timelane::second_to_day;
+/// assert_eq!(second_to_day(-1), 0);
+/// assert_eq!(second_to_day(0), 1);
+/// assert_eq!(second_to_day(86_399), 1);
+/// assert_eq!(second_to_day(86_400), 2);
+/// // The leap second at the end of 2016 is still in December 31st
+/// use timelane::{day_to_second, LEAP_SECONDS_MARKS};
+/// let leap_second = timelane::minute_to_second(LEAP_SECONDS_MARKS[26]) - 1;
+/// assert_eq!(day_to_second(second_to_day(leap_second)), leap_second - 86_400);
+/// use timelane::Mark;
+/// assert_eq!(second_to_day(Mark::MIN), -106751991167300);
+/// assert_eq!(second_to_day(Mark::MAX), 106751991167301);
+/// ```
+pub const fn second_to_day(second: Mark) -> Mark {
+    // The estimate is at most one day off because we never have more than +/- 60 leap seconds.
+    let estimate = divide_towards_negative_infinity(second, 86_400);
+    let zday = divide_towards_negative_infinity(
+        second - leap_seconds_before_minute(estimate * 1_440),
+        86_400,
+    );
+    // The estimate can still be on the wrong side of a leap second, a day starts at or before the
+    // second when `zday * 86_400 + leap_seconds_before_minute(zday * 1_440) <= second`.
+    let zday = if zday
+        > divide_towards_negative_infinity(
+            second - leap_seconds_before_minute(zday * 1_440),
+            86_400,
+        ) {
+        zday - 1
+    } else if zday
+        < divide_towards_negative_infinity(
+            second - leap_seconds_before_minute((zday + 1) * 1_440),
+            86_400,
+        )
+    {
+        zday + 1
+    } else {
+        zday
+    };
+    zday + 1
+}
+
+/// Converts a second [`Mark`] to a rounded up day [`Mark`].
+///
+/// This gives the same day as [`second_to_minute_up`], [`minute_to_hour_up`] and
+/// [`hour_to_day_up`], in one pass.
+///
+/// # Examples
+/// ```
+/// use timelane::second_to_day_up;
+/// assert_eq!(second_to_day_up(-1), 1);
+/// assert_eq!(second_to_day_up(0), 1);
+/// assert_eq!(second_to_day_up(1), 2);
+/// assert_eq!(second_to_day_up(86_400), 2);
+/// use timelane::Mark;
+/// assert_eq!(second_to_day_up(Mark::MIN), -106751991167299);
+/// assert_eq!(second_to_day_up(Mark::MAX), 106751991167302);
+/// ```
+pub const fn second_to_day_up(second: Mark) -> Mark {
+    // The estimate is at most one day off because we never have more than +/- 60 leap seconds.
+    let estimate = divide_towards_positive_infinity(second, 86_400);
+    let zday = divide_towards_positive_infinity(
+        second - leap_seconds_before_minute(estimate * 1_440),
+        86_400,
+    );
+    // The estimate can still be on the wrong side of a leap second, a day starts at or after the
+    // second when `zday * 86_400 + leap_seconds_before_minute(zday * 1_440) >= second`.
+    let zday = if zday
+        > divide_towards_positive_infinity(
+            second - leap_seconds_before_minute((zday - 1) * 1_440),
+            86_400,
+        ) {
+        zday - 1
+    } else if zday
+        < divide_towards_positive_infinity(
+            second - leap_seconds_before_minute(zday * 1_440),
+            86_400,
+        )
+    {
+        zday + 1
+    } else {
+        zday
+    };
+    zday + 1
+}
+
+/// Converts a day [`Mark`] to a second [`Mark`].
+///
+/// This gives the same second as [`day_to_hour`], [`hour_to_minute`] and [`minute_to_second`],
+/// in one pass.
+///
+/// # Examples
+/// ```
+/// use timelane::day_to_second;
+/// assert_eq!(day_to_second(0), -86_400);
+/// assert_eq!(day_to_second(1), 0);
+/// assert_eq!(day_to_second(2), 86_400);
+/// // 2000 had no leap second, 2005 had one
+/// assert_eq!(day_to_second(367), 366 * 86_400);
+/// assert_eq!(day_to_second(2193), 2192 * 86_400 + 1);
+/// ```
+pub const fn day_to_second(day: Mark) -> Mark {
+    let zday = day - 1;
+    zday * 86_400 + leap_seconds_before_minute(zday * 1_440)
+}
+
+/// Converts a second [`Mark`] to a rounded down year [`Mark`].
+///
+/// This gives the same year as [`second_to_day`], [`day_to_month`] and [`month_to_year`].
+///
+/// # Examples
+/// ```
+/// use timelane::second_to_year;
+/// assert_eq!(second_to_year(-1), 1999);
+/// assert_eq!(second_to_year(0), 2000);
+/// assert_eq!(second_to_year(366 * 86_400 - 1), 2000);
+/// assert_eq!(second_to_year(366 * 86_400), 2001);
+/// use timelane::Mark;
+/// assert_eq!(second_to_year(Mark::MIN), -292277022627);
+/// assert_eq!(second_to_year(Mark::MAX), 292277026626);
+/// ```
+pub const fn second_to_year(second: Mark) -> Mark {
+    let (month, _) = day_to_month_and_zday(second_to_day(second));
+    month_to_year(month)
+}
+
+/// Converts a second [`Mark`] to a rounded up year [`Mark`].
+///
+/// This gives the same year as [`second_to_day_up`], [`day_to_month_up`] and
+/// [`month_to_year_up`].
+///
+/// # Examples
+/// ```
+/// use timelane::second_to_year_up;
+/// assert_eq!(second_to_year_up(-1), 2000);
+/// assert_eq!(second_to_year_up(0), 2000);
+/// assert_eq!(second_to_year_up(1), 2001);
+/// use timelane::Mark;
+/// assert_eq!(second_to_year_up(Mark::MIN), -292277022626);
+/// assert_eq!(second_to_year_up(Mark::MAX), 292277026627);
+/// ```
+pub const fn second_to_year_up(second: Mark) -> Mark {
+    let day = second_to_day_up(second);
+    let (month, zday_in_month) = day_to_month_and_zday(day);
+    month_to_year_up(month + (zday_in_month != 0) as Mark)
+}
+
+/// Converts a year [`Mark`] to a second [`Mark`].
+///
+/// This gives the same second as [`year_to_month`], [`month_to_day`] and [`day_to_second`], in
+/// one pass.
+///
+/// # Examples
+/// ```
+/// use timelane::year_to_second;
+/// assert_eq!(year_to_second(1999), -365 * 86_400);
+/// // A leap second ended 1998
+/// assert_eq!(year_to_second(1998), -730 * 86_400 - 1);
+/// assert_eq!(year_to_second(2000), 0);
+/// assert_eq!(year_to_second(2001), 366 * 86_400);
+/// ```
+pub const fn year_to_second(year: Mark) -> Mark {
+    let zyear = year - EPOCH_YEAR;
+    let leap_days = leap_days_before_year(year) - leap_days_before_year(EPOCH_YEAR);
+    day_to_second(zyear * 365 + leap_days + 1)
+}
+
 /// Returns the number of leap days between year 1 and a given year according to the proleptic gregorian calendar.
 ///
 /// Years before 1AD follow the ISO8601 convention: 1BC is year zero, 2BC is year -1...
@@ -491,17 +741,80 @@ pub const fn leap_days_before_year(year: Mark) -> Mark {
 /// assert_eq!(leap_seconds_before_minute(Mark::MIN), -22);
 /// ```
 pub const fn leap_seconds_before_minute(minute: Mark) -> Mark {
-    let mut leap_seconds = LEAP_SECONDS_MARKS.len();
-    while leap_seconds > 0 && minute < LEAP_SECONDS_MARKS[leap_seconds - 1] {
-        leap_seconds -= 1;
+    leap_seconds_until_minute(minute) - LEAP_SECONDS_BEFORE_EPOCH
+}
+
+/// The number of leap seconds inserted before the first second of [`EPOCH_YEAR`].
+const LEAP_SECONDS_BEFORE_EPOCH: Mark =
+    leap_seconds_until_minute(year_month_to_minute(EPOCH_YEAR, 1));
+
+/// The month [`Mark`] starting with the first leap second.
+const FIRST_LEAP_SECOND_MONTH: Mark = minute_to_month(LEAP_SECONDS_MARKS[0]);
+
+/// The number of months from the first leap second to the last one included.
+const LEAP_SECOND_MONTHS: usize =
+    (minute_to_month(LEAP_SECONDS_MARKS[LEAP_SECONDS_MARKS.len() - 1]) - FIRST_LEAP_SECOND_MONTH
+        + 1) as usize;
+
+/// The first minute [`Mark`] of each month from the first leap second to the one after the last
+/// leap second.
+const LEAP_SECOND_MONTH_STARTS: [Mark; LEAP_SECOND_MONTHS + 1] = {
+    let mut starts = [0; LEAP_SECOND_MONTHS + 1];
+    let mut index = 0;
+    while index < starts.len() {
+        starts[index] = hour_to_minute(day_to_hour(month_to_day(
+            FIRST_LEAP_SECOND_MONTH + index as Mark,
+        )));
+        index += 1;
     }
-    let mut leap_seconds_offset = LEAP_SECONDS_MARKS.len();
-    while leap_seconds_offset > 0
-        && year_month_to_minute(EPOCH_YEAR, 1) < LEAP_SECONDS_MARKS[leap_seconds_offset - 1]
-    {
-        leap_seconds_offset -= 1;
+    starts
+};
+
+/// The number of leap seconds inserted before the first minute of each month from the first leap
+/// second to the last one, since the first one. Leap seconds are only inserted at the end of
+/// months.
+const LEAP_SECOND_MONTH_COUNTS: [u8; LEAP_SECOND_MONTHS] = {
+    let mut counts = [0; LEAP_SECOND_MONTHS];
+    let mut index = 0;
+    let mut count = 0;
+    while index < counts.len() {
+        while count < LEAP_SECONDS_MARKS.len()
+            && LEAP_SECONDS_MARKS[count] <= LEAP_SECOND_MONTH_STARTS[index]
+        {
+            count += 1;
+        }
+        counts[index] = count as u8;
+        index += 1;
+    }
+    counts
+};
+
+/// Returns the month [`Mark`] of a minute [`Mark`].
+const fn minute_to_month(minute: Mark) -> Mark {
+    day_to_month(hour_to_day(minute_to_hour(minute)))
+}
+
+/// Returns the number of leap seconds inserted before a minute [`Mark`], since the first one.
+const fn leap_seconds_until_minute(minute: Mark) -> Mark {
+    // Most marks are after the last leap second or before the first one
+    if minute >= LEAP_SECONDS_MARKS[LEAP_SECONDS_MARKS.len() - 1] {
+        return LEAP_SECONDS_MARKS.len() as Mark;
+    }
+    if minute < LEAP_SECONDS_MARKS[0] {
+        return 0;
+    }
+    // Months last 43 829 minutes on average, and their starts never drift by more than a month
+    // from that average: the estimated month is at most one off
+    let mut index = ((minute - LEAP_SECONDS_MARKS[0]) / 43_829) as usize;
+    if index >= LEAP_SECOND_MONTHS {
+        index = LEAP_SECOND_MONTHS - 1;
     }
-    leap_seconds as Mark - leap_seconds_offset as Mark
+    if minute < LEAP_SECOND_MONTH_STARTS[index] {
+        index -= 1;
+    } else if minute >= LEAP_SECOND_MONTH_STARTS[index + 1] {
+        index += 1;
+    }
+    LEAP_SECOND_MONTH_COUNTS[index] as Mark
 }
 
 /// Divides two [`Mark`], rounding towards negative infinity.
@@ -517,6 +830,8 @@ const fn divide_towards_positive_infinity(a: Mark, b: Mark) -> Mark {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::xorshift;
+    use proptest::prelude::*;
 
     const fn year_month_day_hour_minute_to_second(
         year: Mark,
@@ -535,6 +850,196 @@ mod tests {
         )
     }
 
+    /// The previous lookup of leap seconds, a linear scan of the table from its end, as a
+    /// reference.
+    const fn leap_seconds_before_minute_by_scan(minute: Mark) -> Mark {
+        let mut leap_seconds = LEAP_SECONDS_MARKS.len();
+        while leap_seconds > 0 && minute < LEAP_SECONDS_MARKS[leap_seconds - 1] {
+            leap_seconds -= 1;
+        }
+        let mut leap_seconds_offset = LEAP_SECONDS_MARKS.len();
+        while leap_seconds_offset > 0
+            && year_month_to_minute(EPOCH_YEAR, 1) < LEAP_SECONDS_MARKS[leap_seconds_offset - 1]
+        {
+            leap_seconds_offset -= 1;
+        }
+        leap_seconds as Mark - leap_seconds_offset as Mark
+    }
+
+    #[test]
+    fn leap_seconds_lookup_matches_a_scan() {
+        let first = LEAP_SECONDS_MARKS[0];
+        let last = LEAP_SECONDS_MARKS[LEAP_SECONDS_MARKS.len() - 1];
+        let around_leap_seconds = LEAP_SECONDS_MARKS
+            .iter()
+            .flat_map(|&leap_minute| leap_minute - 1440..leap_minute + 1440);
+        for minute in around_leap_seconds {
+            assert_eq!(
+                leap_seconds_before_minute(minute),
+                leap_seconds_before_minute_by_scan(minute),
+                "{}",
+                minute
+            );
+        }
+        // Walking every minute, the count goes up by one at each leap second
+        let mut expected = leap_seconds_before_minute_by_scan(first - 1);
+        let mut next_leap_second = 0;
+        for minute in first - 1..=last {
+            if minute == LEAP_SECONDS_MARKS[next_leap_second] {
+                expected += 1;
+                next_leap_second = (next_leap_second + 1).min(LEAP_SECONDS_MARKS.len() - 1);
+            }
+            assert_eq!(leap_seconds_before_minute(minute), expected, "{}", minute);
+        }
+        for minute in [Mark::MIN, Mark::MIN + 1, -1, 0, 1, Mark::MAX - 1, Mark::MAX] {
+            assert_eq!(
+                leap_seconds_before_minute(minute),
+                leap_seconds_before_minute_by_scan(minute)
+            );
+        }
+    }
+
+    /// The conversion of days to months before it used the algorithm of Neri and Schneider, as a
+    /// reference.
+    const fn day_to_month_and_zday_by_search(day: Mark) -> (Mark, Mark) {
+        const ZMONTH_STARTS_LEAP_YEAR: [Mark; 12] =
+            [0, 31, 60, 91, 121, 152, 182, 213, 244, 274, 305, 335];
+        const fn day_to_zyear_and_days(day: Mark) -> (Mark, Mark, bool) {
+            if day == Mark::MIN {
+                let (zyear, days_in_year, is_leap_year) =
+                    day_to_zyear_and_days(day + 97 + 400 * 365);
+                return (zyear - 400, days_in_year, is_leap_year);
+            }
+            let zday = day - 1;
+            let mut zyear = divide_towards_negative_infinity(
+                zday - divide_towards_negative_infinity(zday, 97 + 400 * 365) * 97,
+                365,
+            );
+            let mut leap_days =
+                leap_days_before_year(zyear + EPOCH_YEAR) - leap_days_before_year(EPOCH_YEAR);
+            let mut zstart_of_year = zyear * 365 + leap_days;
+            if zstart_of_year > zday {
+                zyear -= 1;
+                leap_days =
+                    leap_days_before_year(zyear + EPOCH_YEAR) - leap_days_before_year(EPOCH_YEAR);
+                zstart_of_year = zyear * 365 + leap_days;
+            }
+            let is_leap_year = (leap_days_before_year(zyear + 1 + EPOCH_YEAR)
+                - leap_days_before_year(EPOCH_YEAR))
+                > leap_days;
+            (zyear, zday - zstart_of_year, is_leap_year)
+        }
+        let (zyear, zdays_in_year, is_leap_year) = day_to_zyear_and_days(day);
+        let month_starts = if is_leap_year {
+            ZMONTH_STARTS_LEAP_YEAR
+        } else {
+            ZMONTH_STARTS
+        };
+        let mut month = 1;
+        while month < month_starts.len() && zdays_in_year >= month_starts[month] {
+            month += 1;
+        }
+        (
+            zyear * 12 + month as Mark,
+            zdays_in_year - month_starts[month - 1],
+        )
+    }
+
+    #[test]
+    fn day_to_month_matches_a_search() {
+        let sequential = -3 * DAYS_IN_400_YEARS..3 * DAYS_IN_400_YEARS;
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        // The search overflows close to the ends of the lane
+        let random = (0..100_000).map(|_| xorshift(&mut state) as Mark / 2);
+        for day in sequential.chain(random).chain([Mark::MIN]) {
+            assert_eq!(
+                day_to_month_and_zday(day),
+                day_to_month_and_zday_by_search(day),
+                "{}",
+                day
+            );
+        }
+    }
+
+    #[test]
+    fn day_to_month_is_continuous_at_the_ends_of_the_lane() {
+        for days in [Mark::MIN..Mark::MIN + 1000, Mark::MAX - 1000..Mark::MAX] {
+            for day in days {
+                let (month, zday) = day_to_month_and_zday(day);
+                let (next_month, next_zday) = day_to_month_and_zday(day + 1);
+                if next_zday == 0 {
+                    assert_eq!(next_month, month + 1, "{}", day);
+                    assert!((27..31).contains(&zday), "{}", day);
+                } else {
+                    assert_eq!((next_month, next_zday), (month, zday + 1), "{}", day);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fused_scalers_match_the_chains() {
+        let around_leap_seconds = LEAP_SECONDS_MARKS.iter().flat_map(|&leap_minute| {
+            let leap_second = minute_to_second(leap_minute) - 1;
+            leap_second - 1_000..leap_second + 1_000
+        });
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        let random = (0..100_000).map(|_| {
+            let random = xorshift(&mut state);
+            (random as Mark) >> (random % 48)
+        });
+        let extremes = [Mark::MIN, Mark::MIN + 1, -1, 0, 1, Mark::MAX - 1, Mark::MAX];
+        for second in around_leap_seconds.chain(random).chain(extremes) {
+            let day = hour_to_day(minute_to_hour(second_to_minute(second)));
+            let day_up = hour_to_day_up(minute_to_hour_up(second_to_minute_up(second)));
+            assert_eq!(second_to_day(second), day, "{}", second);
+            assert_eq!(second_to_day_up(second), day_up, "{}", second);
+            assert_eq!(
+                second_to_year(second),
+                month_to_year(day_to_month(day)),
+                "{}",
+                second
+            );
+            assert_eq!(
+                second_to_year_up(second),
+                month_to_year_up(day_to_month_up(day_up)),
+                "{}",
+                second
+            );
+            // Days and years are converted back only when they start inside the second lane
+            if day > second_to_day(Mark::MIN) {
+                assert_eq!(
+                    day_to_second(day),
+                    minute_to_second(hour_to_minute(day_to_hour(day))),
+                    "{}",
+                    day
+                );
+            }
+            let nanosecond = second.wrapping_mul(1_000_000_000) / 3;
+            assert_eq!(
+                subsecond::nanosecond_to_day(nanosecond),
+                second_to_day(subsecond::nanosecond_to_second(nanosecond)),
+                "{}",
+                nanosecond
+            );
+            assert_eq!(
+                subsecond::nanosecond_to_day_up(nanosecond),
+                second_to_day_up(subsecond::nanosecond_to_second_up(nanosecond)),
+                "{}",
+                nanosecond
+            );
+            let year = second_to_year(second);
+            if year > second_to_year(Mark::MIN) {
+                assert_eq!(
+                    year_to_second(year),
+                    day_to_second(month_to_day(year_to_month(year))),
+                    "{}",
+                    year
+                );
+            }
+        }
+    }
+
     #[test]
     fn day_0_is_hour_minus_24() {
         assert_eq!(day_to_hour(0), -24);
@@ -761,7 +1266,7 @@ mod tests {
     fn leap_years_are_every_4_years_except_100_except_400() {
         for year in 1..2021 {
             let leap_days = leap_days_before_year(year + 1) - leap_days_before_year(year);
-            let is_leap_year = year % 4 == 0 && !(year % 100 == 0 && !(year % 400 == 0));
+            let is_leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
             assert_eq!(
                 leap_days,
                 if is_leap_year { 1 } else { 0 },
@@ -951,11 +1456,11 @@ mod tests {
         let high_end = second_to_minute(Mark::MAX);
         for second in low_start..low_start + 120 {
             let delta = second - minute_to_second(second_to_minute(second));
-            assert!(delta >= 0 && delta < 60);
+            assert!((0..60).contains(&delta));
         }
         for second in high_end - 120..=high_end {
             let delta = second - minute_to_second(second_to_minute(second));
-            assert!(delta >= 0 && delta < 60);
+            assert!((0..60).contains(&delta));
         }
     }
 
@@ -965,11 +1470,11 @@ mod tests {
         let high_end = second_to_minute(Mark::MAX);
         for second in low_start..low_start + 120 {
             let delta = minute_to_second(second_to_minute_up(second)) - second;
-            assert!(delta >= 0 && delta < 60);
+            assert!((0..60).contains(&delta));
         }
         for second in high_end - 120..=high_end {
             let delta = minute_to_second(second_to_minute_up(second)) - second;
-            assert!(delta >= 0 && delta < 60);
+            assert!((0..60).contains(&delta));
         }
     }
 
@@ -994,4 +1499,307 @@ mod tests {
         assert_eq!(divide_towards_positive_infinity(1, 60), 1);
         assert_eq!(divide_towards_positive_infinity(60, 60), 1);
     }
+
+    #[test]
+    fn second_to_minute_handles_all_leap_seconds() {
+        for leap_minute in LEAP_SECONDS_MARKS {
+            let minute_end = minute_to_second(leap_minute);
+            for second in minute_end - 125..minute_end + 125 {
+                let minute = second_to_minute(second);
+                assert!(minute_to_second(minute) <= second, "{}", second);
+                assert!(minute_to_second(minute + 1) > second, "{}", second);
+                let minute = second_to_minute_up(second);
+                assert!(minute_to_second(minute) >= second, "{}", second);
+                assert!(minute_to_second(minute - 1) < second, "{}", second);
+            }
+            assert_eq!(second_to_minute(minute_end - 1), leap_minute - 1);
+            assert_eq!(second_to_minute_up(minute_end - 1), leap_minute);
+        }
+    }
+
+    /// A lane, a finer lane, and the scalers between them.
+    struct ScalerPair {
+        name: &'static str,
+        to_fine: Scaler,
+        down: Scaler,
+        up: Scaler,
+    }
+
+    const YEAR_MONTH: ScalerPair = ScalerPair {
+        name: "year/month",
+        to_fine: year_to_month,
+        down: month_to_year,
+        up: month_to_year_up,
+    };
+    const MONTH_DAY: ScalerPair = ScalerPair {
+        name: "month/day",
+        to_fine: month_to_day,
+        down: day_to_month,
+        up: day_to_month_up,
+    };
+    const DAY_HOUR: ScalerPair = ScalerPair {
+        name: "day/hour",
+        to_fine: day_to_hour,
+        down: hour_to_day,
+        up: hour_to_day_up,
+    };
+    const HOUR_MINUTE: ScalerPair = ScalerPair {
+        name: "hour/minute",
+        to_fine: hour_to_minute,
+        down: minute_to_hour,
+        up: minute_to_hour_up,
+    };
+    const MINUTE_SECOND: ScalerPair = ScalerPair {
+        name: "minute/second",
+        to_fine: minute_to_second,
+        down: second_to_minute,
+        up: second_to_minute_up,
+    };
+    const SECOND_MILLISECOND: ScalerPair = ScalerPair {
+        name: "second/millisecond",
+        to_fine: subsecond::second_to_millisecond,
+        down: subsecond::millisecond_to_second,
+        up: subsecond::millisecond_to_second_up,
+    };
+    const SECOND_MICROSECOND: ScalerPair = ScalerPair {
+        name: "second/microsecond",
+        to_fine: subsecond::second_to_microsecond,
+        down: subsecond::microsecond_to_second,
+        up: subsecond::microsecond_to_second_up,
+    };
+    const SECOND_NANOSECOND: ScalerPair = ScalerPair {
+        name: "second/nanosecond",
+        to_fine: subsecond::second_to_nanosecond,
+        down: subsecond::nanosecond_to_second,
+        up: subsecond::nanosecond_to_second_up,
+    };
+    const DAY_SECOND: ScalerPair = ScalerPair {
+        name: "day/second",
+        to_fine: day_to_second,
+        down: second_to_day,
+        up: second_to_day_up,
+    };
+    const YEAR_SECOND: ScalerPair = ScalerPair {
+        name: "year/second",
+        to_fine: year_to_second,
+        down: second_to_year,
+        up: second_to_year_up,
+    };
+    const DAY_NANOSECOND: ScalerPair = ScalerPair {
+        name: "day/nanosecond",
+        to_fine: subsecond::day_to_nanosecond,
+        down: subsecond::nanosecond_to_day,
+        up: subsecond::nanosecond_to_day_up,
+    };
+    const JULIAN_MONTH_DAY: ScalerPair = ScalerPair {
+        name: "julian month/day",
+        to_fine: julian::month_to_day,
+        down: julian::day_to_month,
+        up: julian::day_to_month_up,
+    };
+    const ISLAMIC_YEAR_MONTH: ScalerPair = ScalerPair {
+        name: "islamic year/month",
+        to_fine: islamic::year_to_month,
+        down: islamic::month_to_year,
+        up: islamic::month_to_year_up,
+    };
+    const ISLAMIC_MONTH_DAY: ScalerPair = ScalerPair {
+        name: "islamic month/day",
+        to_fine: islamic::month_to_day,
+        down: islamic::day_to_month,
+        up: islamic::day_to_month_up,
+    };
+    const HEBREW_YEAR_MONTH: ScalerPair = ScalerPair {
+        name: "hebrew year/month",
+        to_fine: hebrew::year_to_month,
+        down: hebrew::month_to_year,
+        up: hebrew::month_to_year_up,
+    };
+    const HEBREW_MONTH_DAY: ScalerPair = ScalerPair {
+        name: "hebrew month/day",
+        to_fine: hebrew::month_to_day,
+        down: hebrew::day_to_month,
+        up: hebrew::day_to_month_up,
+    };
+
+    const SCALER_PAIRS: [ScalerPair; 16] = [
+        YEAR_MONTH,
+        MONTH_DAY,
+        DAY_HOUR,
+        HOUR_MINUTE,
+        MINUTE_SECOND,
+        SECOND_MILLISECOND,
+        SECOND_MICROSECOND,
+        SECOND_NANOSECOND,
+        DAY_SECOND,
+        YEAR_SECOND,
+        DAY_NANOSECOND,
+        JULIAN_MONTH_DAY,
+        ISLAMIC_YEAR_MONTH,
+        ISLAMIC_MONTH_DAY,
+        HEBREW_YEAR_MONTH,
+        HEBREW_MONTH_DAY,
+    ];
+
+    impl ScalerPair {
+        /// Returns the first and last marks that convert to the finer lane without overflowing.
+        fn coarse_range(&self) -> (Mark, Mark) {
+            ((self.up)(Mark::MIN), (self.down)(Mark::MAX))
+        }
+
+        /// Maps any mark to a mark of the coarse range.
+        fn to_coarse_range(&self, mark: Mark) -> Mark {
+            let (first, last) = self.coarse_range();
+            first + mark.rem_euclid(last - first + 1)
+        }
+
+        /// Checks that a coarse mark survives a round trip through the finer lane, and that the
+        /// next coarse mark starts later.
+        fn check_coarse(&self, coarse: Mark) {
+            let (first, last) = self.coarse_range();
+            assert!((first..=last).contains(&coarse), "{} {}", self.name, coarse);
+            let fine = (self.to_fine)(coarse);
+            assert_eq!((self.down)(fine), coarse, "{} {}", self.name, coarse);
+            assert_eq!((self.up)(fine), coarse, "{} {}", self.name, coarse);
+            if coarse < last {
+                assert!(
+                    (self.to_fine)(coarse + 1) > fine,
+                    "{} {}",
+                    self.name,
+                    coarse
+                );
+            }
+        }
+
+        /// Checks that a fine mark is bracketed by its rounded down and rounded up coarse marks,
+        /// which are equal only at the start of a coarse mark, and that they are continuous.
+        fn check_fine(&self, fine: Mark) {
+            let (first, last) = self.coarse_range();
+            let down = (self.down)(fine);
+            let up = (self.up)(fine);
+            assert!(up == down || up == down + 1, "{} {}", self.name, fine);
+            if (first..=last).contains(&down) {
+                let start = (self.to_fine)(down);
+                assert!(start <= fine, "{} {}", self.name, fine);
+                assert_eq!(up == down, start == fine, "{} {}", self.name, fine);
+            }
+            if (first..=last).contains(&up) {
+                assert!((self.to_fine)(up) >= fine, "{} {}", self.name, fine);
+            }
+            if fine < Mark::MAX {
+                let next_down = (self.down)(fine + 1);
+                let next_up = (self.up)(fine + 1);
+                assert!(
+                    next_down == down || next_down == down + 1,
+                    "{} {}",
+                    self.name,
+                    fine
+                );
+                assert!(next_up == up || next_up == up + 1, "{} {}", self.name, fine);
+            }
+        }
+
+        /// Checks that the scalers never go backwards between two marks.
+        fn check_monotonic(&self, a: Mark, b: Mark) {
+            let (a, b) = if a <= b { (a, b) } else { (b, a) };
+            assert!(
+                (self.down)(a) <= (self.down)(b),
+                "{} {} {}",
+                self.name,
+                a,
+                b
+            );
+            assert!((self.up)(a) <= (self.up)(b), "{} {} {}", self.name, a, b);
+            let (a, b) = (self.to_coarse_range(a), self.to_coarse_range(b));
+            let (a, b) = if a <= b { (a, b) } else { (b, a) };
+            assert!(
+                (self.to_fine)(a) <= (self.to_fine)(b),
+                "{} {} {}",
+                self.name,
+                a,
+                b
+            );
+        }
+    }
+
+    /// Any mark, or a mark close to zero where most dates of interest are.
+    fn marks() -> impl Strategy<Value = Mark> {
+        prop_oneof![any::<Mark>(), any::<i32>().prop_map(|mark| mark as Mark)]
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(2_048))]
+
+        #[test]
+        fn scalers_round_trip_and_bracket(fine in marks(), coarse in marks()) {
+            for pair in &SCALER_PAIRS {
+                pair.check_fine(fine);
+                pair.check_coarse(pair.to_coarse_range(coarse));
+            }
+        }
+
+        #[test]
+        fn scalers_are_monotonic(a in marks(), b in marks()) {
+            for pair in &SCALER_PAIRS {
+                pair.check_monotonic(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn scalers_round_trip_and_bracket_at_the_ends_of_the_lanes() {
+        for pair in &SCALER_PAIRS {
+            let (first, last) = pair.coarse_range();
+            for fine in (Mark::MIN..Mark::MIN + 1_000).chain(Mark::MAX - 1_000..=Mark::MAX) {
+                pair.check_fine(fine);
+            }
+            for coarse in (first..first + 1_000).chain(last - 1_000..=last) {
+                pair.check_coarse(coarse);
+            }
+        }
+    }
+
+    #[test]
+    fn scalers_round_trip_and_bracket_over_centuries_of_days() {
+        let first_day = month_to_day(year_to_month(1600));
+        let last_day = month_to_day(year_to_month(2400));
+        for day in first_day..last_day {
+            MONTH_DAY.check_fine(day);
+            DAY_HOUR.check_coarse(day);
+            DAY_SECOND.check_coarse(day);
+            let (first_nanosecond_day, last_nanosecond_day) = DAY_NANOSECOND.coarse_range();
+            if (first_nanosecond_day..=last_nanosecond_day).contains(&day) {
+                DAY_NANOSECOND.check_coarse(day);
+            }
+        }
+        for month in day_to_month(first_day)..day_to_month(last_day) {
+            YEAR_MONTH.check_fine(month);
+            MONTH_DAY.check_coarse(month);
+        }
+        for year in 1600..2400 {
+            YEAR_MONTH.check_coarse(year);
+            YEAR_SECOND.check_coarse(year);
+        }
+    }
+
+    #[test]
+    fn scalers_round_trip_and_bracket_over_all_leap_second_minutes() {
+        for leap_minute in LEAP_SECONDS_MARKS {
+            assert_eq!(
+                minute_to_second(leap_minute) - minute_to_second(leap_minute - 1),
+                61
+            );
+            for minute in leap_minute - 2..leap_minute + 2 {
+                MINUTE_SECOND.check_coarse(minute);
+                HOUR_MINUTE.check_fine(minute);
+                for second in minute_to_second(minute)..minute_to_second(minute + 1) {
+                    MINUTE_SECOND.check_fine(second);
+                    DAY_SECOND.check_fine(second);
+                    YEAR_SECOND.check_fine(second);
+                    SECOND_NANOSECOND.check_coarse(second);
+                }
+            }
+            DAY_SECOND.check_coarse(hour_to_day(minute_to_hour(leap_minute)));
+        }
+    }
 }
@@ -0,0 +1,332 @@
+//! Errors and shared helpers for the text parsers of this crate.
+use core::fmt;
+
+use crate::civil::minute_has_leap_second;
+use crate::{day_to_hour, hour_to_minute, minute_to_second, Mark, EPOCH_YEAR};
+
+/// Propagates the error of a [`Result`] from a `const fn`, where the `?` operator is not available.
+macro_rules! tri {
+    ($result:expr) => {
+        match $result {
+            Ok(value) => value,
+            Err(error) => return Err(error),
+        }
+    };
+}
+
+/// An error returned when a text representation cannot be parsed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    position: usize,
+    kind: ParseErrorKind,
+}
+
+impl ParseError {
+    pub(crate) const fn new(position: usize, kind: ParseErrorKind) -> Self {
+        Self { position, kind }
+    }
+
+    /// Returns the byte offset in the input where the error was detected.
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the kind of error.
+    pub const fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.kind, self.position)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// The reasons why a text representation cannot be parsed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// The input ended before the representation was complete.
+    UnexpectedEnd,
+    /// A character does not match what the format expects at this position.
+    UnexpectedCharacter,
+    /// The representation is complete but more characters follow.
+    TrailingCharacters,
+    /// The month is not between 1 and 12.
+    InvalidMonth,
+    /// The day is not a day of the month, or not a day of the year for ordinal dates.
+    InvalidDay,
+    /// The week is not a week of the ISO 8601 week-numbering year.
+    InvalidWeek,
+    /// The day of week is not between 1 and 7.
+    InvalidWeekday,
+    /// The day of week does not match the date.
+    MismatchedWeekday,
+    /// The hour is not between 0 and 23, or 24 when not followed by zero minutes and seconds.
+    InvalidHour,
+    /// The minute is not between 0 and 59.
+    InvalidMinute,
+    /// The second is not between 0 and 60.
+    InvalidSecond,
+    /// The second is 60 but no leap second was inserted at the end of this minute.
+    InvalidLeapSecond,
+    /// The UTC offset is not between -23:59 and +23:59.
+    InvalidOffset,
+    /// The result does not fit in a [`Mark`] of the requested lane.
+    OutOfRange,
+    /// The parts of a recurrence rule are missing, repeated, or cannot be combined.
+    InvalidRule,
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::UnexpectedEnd => "unexpected end of input",
+            Self::UnexpectedCharacter => "unexpected character",
+            Self::TrailingCharacters => "trailing characters",
+            Self::InvalidMonth => "invalid month",
+            Self::InvalidDay => "invalid day",
+            Self::InvalidWeek => "invalid week",
+            Self::InvalidWeekday => "invalid day of week",
+            Self::MismatchedWeekday => "day of week does not match the date",
+            Self::InvalidHour => "invalid hour",
+            Self::InvalidMinute => "invalid minute",
+            Self::InvalidSecond => "invalid second",
+            Self::InvalidLeapSecond => "invalid leap second",
+            Self::InvalidOffset => "invalid offset",
+            Self::OutOfRange => "out of range",
+            Self::InvalidRule => "invalid recurrence rule",
+        })
+    }
+}
+
+/// The largest distance in years from [`EPOCH_YEAR`] whose seconds all fit in a [`Mark`].
+///
+/// A year never lasts more than 366 days and a minute of leap seconds, we keep a year of margin
+/// for UTC offsets and the rounding of the division.
+pub(crate) const MAX_SECOND_ZYEAR: Mark = Mark::MAX / ((366 * 24 * 60 + 1) * 60) - 1;
+
+/// The fields of a date and time, as found in a text representation.
+///
+/// The positions are kept to report errors that can only be detected once all the fields are
+/// known, like an invalid leap second.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct DateTimeFields {
+    /// The local day [`Mark`].
+    pub(crate) day: Mark,
+    pub(crate) hour: Mark,
+    pub(crate) minute: Mark,
+    pub(crate) second: Mark,
+    pub(crate) nanosecond: Mark,
+    /// The UTC offset, in minutes.
+    pub(crate) offset: Mark,
+    pub(crate) start_position: usize,
+    pub(crate) second_position: usize,
+}
+
+impl DateTimeFields {
+    /// Returns the UTC second [`Mark`] of these fields, ignoring the nanoseconds.
+    pub(crate) const fn to_second(self) -> Result<Mark, ParseError> {
+        let local_minute = hour_to_minute(day_to_hour(self.day) + self.hour) + self.minute;
+        let minute = local_minute - self.offset;
+        if self.second == 60 && !minute_has_leap_second(minute) {
+            return Err(ParseError::new(
+                self.second_position,
+                ParseErrorKind::InvalidLeapSecond,
+            ));
+        }
+        Ok(minute_to_second(minute) + self.second)
+    }
+
+    /// Returns the UTC nanosecond [`Mark`] of these fields.
+    pub(crate) const fn to_nanosecond(self) -> Result<Mark, ParseError> {
+        let second = tri!(self.to_second());
+        match second.checked_mul(1_000_000_000) {
+            Some(nanosecond) => match nanosecond.checked_add(self.nanosecond) {
+                Some(nanosecond) => Ok(nanosecond),
+                None => Err(ParseError::new(
+                    self.start_position,
+                    ParseErrorKind::OutOfRange,
+                )),
+            },
+            None => Err(ParseError::new(
+                self.start_position,
+                ParseErrorKind::OutOfRange,
+            )),
+        }
+    }
+}
+
+/// Checks that a year is close enough to [`EPOCH_YEAR`] for all its seconds to fit in a [`Mark`].
+pub(crate) const fn check_year(year: Mark, position: usize) -> Result<Mark, ParseError> {
+    if year < EPOCH_YEAR - MAX_SECOND_ZYEAR || year > EPOCH_YEAR + MAX_SECOND_ZYEAR {
+        Err(ParseError::new(position, ParseErrorKind::OutOfRange))
+    } else {
+        Ok(year)
+    }
+}
+
+/// Checks that a value is in an inclusive range.
+pub(crate) const fn check_range(
+    value: Mark,
+    min: Mark,
+    max: Mark,
+    position: usize,
+    kind: ParseErrorKind,
+) -> Result<Mark, ParseError> {
+    if value < min || value > max {
+        Err(ParseError::new(position, kind))
+    } else {
+        Ok(value)
+    }
+}
+
+/// Returns the byte at a position, or an [`ParseErrorKind::UnexpectedEnd`] error.
+pub(crate) const fn peek(bytes: &[u8], position: usize) -> Result<u8, ParseError> {
+    if position < bytes.len() {
+        Ok(bytes[position])
+    } else {
+        Err(ParseError::new(position, ParseErrorKind::UnexpectedEnd))
+    }
+}
+
+/// Checks that the byte at a position is the expected one, returns the next position.
+pub(crate) const fn expect(
+    bytes: &[u8],
+    position: usize,
+    expected: u8,
+) -> Result<usize, ParseError> {
+    if tri!(peek(bytes, position)) == expected {
+        Ok(position + 1)
+    } else {
+        Err(ParseError::new(
+            position,
+            ParseErrorKind::UnexpectedCharacter,
+        ))
+    }
+}
+
+/// Checks that the byte at a position is the expected ASCII letter in any case, returns the next
+/// position.
+pub(crate) const fn expect_ignore_case(
+    bytes: &[u8],
+    position: usize,
+    expected: u8,
+) -> Result<usize, ParseError> {
+    if tri!(peek(bytes, position)).eq_ignore_ascii_case(&expected) {
+        Ok(position + 1)
+    } else {
+        Err(ParseError::new(
+            position,
+            ParseErrorKind::UnexpectedCharacter,
+        ))
+    }
+}
+
+/// Checks that there is nothing left after a position.
+pub(crate) const fn expect_end(bytes: &[u8], position: usize) -> Result<(), ParseError> {
+    if position < bytes.len() {
+        Err(ParseError::new(
+            position,
+            ParseErrorKind::TrailingCharacters,
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the number of consecutive ASCII digits starting at a position.
+pub(crate) const fn count_digits(bytes: &[u8], position: usize) -> usize {
+    let mut end = position;
+    while end < bytes.len() && bytes[end].is_ascii_digit() {
+        end += 1;
+    }
+    end - position
+}
+
+/// Parses exactly `count` ASCII digits, returns the value and the next position.
+pub(crate) const fn digits(
+    bytes: &[u8],
+    position: usize,
+    count: usize,
+) -> Result<(Mark, usize), ParseError> {
+    let mut value: Mark = 0;
+    let mut index = position;
+    while index < position + count {
+        let byte = tri!(peek(bytes, index));
+        if !byte.is_ascii_digit() {
+            return Err(ParseError::new(index, ParseErrorKind::UnexpectedCharacter));
+        }
+        value = match value.checked_mul(10) {
+            Some(value) => match value.checked_add((byte - b'0') as Mark) {
+                Some(value) => value,
+                None => return Err(ParseError::new(position, ParseErrorKind::OutOfRange)),
+            },
+            None => return Err(ParseError::new(position, ParseErrorKind::OutOfRange)),
+        };
+        index += 1;
+    }
+    Ok((value, index))
+}
+
+/// Returns the position of the first non-whitespace byte at or after a position.
+pub(crate) fn skip_whitespace(bytes: &[u8], mut position: usize) -> usize {
+    while position < bytes.len() && bytes[position].is_ascii_whitespace() {
+        position += 1;
+    }
+    position
+}
+
+/// Parses one of the names, or one of their three letters abbreviations, returns the one-based
+/// index of the name.
+pub(crate) fn parse_name(
+    bytes: &[u8],
+    position: usize,
+    names: &[&str],
+) -> Result<(Mark, usize), ParseError> {
+    let rest = &bytes[position.min(bytes.len())..];
+    for (index, name) in names.iter().enumerate() {
+        if rest.len() >= name.len() && rest[..name.len()].eq_ignore_ascii_case(name.as_bytes()) {
+            return Ok((index as Mark + 1, position + name.len()));
+        }
+    }
+    for (index, name) in names.iter().enumerate() {
+        if name.len() > 3
+            && rest.len() >= 3
+            && rest[..3].eq_ignore_ascii_case(&name.as_bytes()[..3])
+        {
+            return Ok((index as Mark + 1, position + 3));
+        }
+    }
+    peek(bytes, position)?;
+    Err(ParseError::new(
+        position,
+        ParseErrorKind::UnexpectedCharacter,
+    ))
+}
+
+/// Parses a decimal fraction of a second, returns the nanoseconds and the next position.
+///
+/// Digits after the ninth one are accepted but ignored: the fraction is rounded down.
+pub(crate) const fn fraction(bytes: &[u8], position: usize) -> Result<(Mark, usize), ParseError> {
+    let count = count_digits(bytes, position);
+    if count == 0 {
+        let _ = tri!(peek(bytes, position));
+        return Err(ParseError::new(
+            position,
+            ParseErrorKind::UnexpectedCharacter,
+        ));
+    }
+    let significant = if count > 9 { 9 } else { count };
+    let (mut nanosecond, _) = tri!(digits(bytes, position, significant));
+    let mut scale = significant;
+    while scale < 9 {
+        nanosecond *= 10;
+        scale += 1;
+    }
+    Ok((nanosecond, position + count))
+}
@@ -0,0 +1,950 @@
+//! Calendar periods, such as the ISO 8601 duration `P1Y2M10DT2H30M`, added to [`Mark`].
+//!
+//! A [`Period`] is added by walking up and down the lanes, from the largest unit to the smallest:
+//!  - years and months are added in the month lane,
+//!  - days are added in the day lane,
+//!  - hours and minutes are added in the minute lane,
+//!  - seconds, and then nanoseconds, are added in the second and nanosecond lanes.
+//!
+//! Adding years and months keeps the day of month, clamped to the last day of the target month:
+//! January 31st plus one month is the last day of February. Likewise, a leap second plus a period
+//! landing on a minute without leap second is clamped to the 59th second of that minute.
+//!
+//! Because of this clamping, and because months, days and minutes do not always have the same
+//! length, adding a period and then its negation does not always give back the original mark.
+//!
+//! The other way around, [`Period::difference`] breaks down the time between two marks into a
+//! period that, added to the first mark, gives back the second one.
+use core::fmt;
+use core::str::FromStr;
+
+use crate::civil::{
+    minute_has_leap_second, minute_to_day_hour_minute, second_to_minute_and_second,
+};
+use crate::lane::Lane;
+use crate::parse::{count_digits, digits, fraction, peek, ParseError, ParseErrorKind};
+use crate::subsecond::{
+    microsecond_to_second, millisecond_to_second, nanosecond_to_second, second_to_microsecond,
+    second_to_millisecond, second_to_nanosecond,
+};
+use crate::{
+    day_to_hour, day_to_month, day_to_second, hour_to_minute, minute_to_second, month_to_day,
+    second_to_day, second_to_minute, year_to_second, Mark,
+};
+
+/// A calendar period, counting each unit separately.
+///
+/// # Examples
+/// ```
+/// use timelane::period::Period;
+/// use timelane::iso8601::parse_rfc3339_second;
+/// let period = Period::parse("P1Y2M10DT2H30M").unwrap();
+/// let start = parse_rfc3339_second("2000-01-01T00:00:00Z").unwrap();
+/// let end = parse_rfc3339_second("2001-03-11T02:30:00Z").unwrap();
+/// assert_eq!(period.add_to_second(start), end);
+/// assert_eq!(period.to_string(), "P1Y2M10DT2H30M");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Period {
+    /// The number of years, added in the month lane.
+    pub years: Mark,
+    /// The number of months, added in the month lane.
+    pub months: Mark,
+    /// The number of days, added in the day lane.
+    pub days: Mark,
+    /// The number of hours, added in the minute lane.
+    pub hours: Mark,
+    /// The number of minutes, added in the minute lane.
+    pub minutes: Mark,
+    /// The number of seconds, added in the second lane.
+    pub seconds: Mark,
+    /// The number of nanoseconds, added in the nanosecond lane.
+    pub nanoseconds: Mark,
+}
+
+impl Period {
+    /// The empty period.
+    pub const ZERO: Period = Period {
+        years: 0,
+        months: 0,
+        days: 0,
+        hours: 0,
+        minutes: 0,
+        seconds: 0,
+        nanoseconds: 0,
+    };
+
+    /// Returns the period with all units negated.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::period::Period;
+    /// assert_eq!(Period::parse("P1DT1S").unwrap().negate(), Period::parse("-P1DT1S").unwrap());
+    /// ```
+    pub const fn negate(&self) -> Period {
+        Period {
+            years: -self.years,
+            months: -self.months,
+            days: -self.days,
+            hours: -self.hours,
+            minutes: -self.minutes,
+            seconds: -self.seconds,
+            nanoseconds: -self.nanoseconds,
+        }
+    }
+
+    /// Parses an ISO 8601 duration, such as `P1Y2M10DT2H30M`.
+    ///
+    /// Weeks (`P2W`) are converted to days, and only seconds can have a fraction, which is
+    /// rounded down to the nanosecond. The duration can start with a `-` sign to negate all its
+    /// units, and each unit can have its own `-` sign, as in the `P1M-1D` formatted for periods
+    /// with units of mixed signs.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::period::Period;
+    /// let period = Period::parse("P2WT1.5S").unwrap();
+    /// assert_eq!((period.days, period.seconds, period.nanoseconds), (14, 1, 500_000_000));
+    /// use timelane::parse::ParseErrorKind;
+    /// let error = Period::parse("PT1D").unwrap_err();
+    /// assert_eq!((error.position(), error.kind()), (3, ParseErrorKind::UnexpectedCharacter));
+    /// let period = Period::parse("P1M-1D").unwrap();
+    /// assert_eq!((period.months, period.days), (1, -1));
+    /// ```
+    pub const fn parse(input: &str) -> Result<Period, ParseError> {
+        let bytes = input.as_bytes();
+        let negative = !bytes.is_empty() && bytes[0] == b'-';
+        let mut position = if !bytes.is_empty() && (bytes[0] == b'-' || bytes[0] == b'+') {
+            1
+        } else {
+            0
+        };
+        if tri!(peek(bytes, position)) != b'P' {
+            return Err(ParseError::new(
+                position,
+                ParseErrorKind::UnexpectedCharacter,
+            ));
+        }
+        position += 1;
+        let mut period = Period::ZERO;
+        let mut in_time = false;
+        let mut has_component = false;
+        // The designators must appear in this order, each at most once
+        let mut next_designator = 0;
+        loop {
+            if position == bytes.len() && has_component {
+                break;
+            }
+            if !in_time && tri!(peek(bytes, position)) == b'T' {
+                in_time = true;
+                has_component = false;
+                position += 1;
+                continue;
+            }
+            let number_position = position;
+            let unit_negative = position < bytes.len() && bytes[position] == b'-';
+            if unit_negative {
+                position += 1;
+            }
+            let count = count_digits(bytes, position);
+            if count == 0 {
+                let _ = tri!(peek(bytes, position));
+                return Err(ParseError::new(
+                    position,
+                    ParseErrorKind::UnexpectedCharacter,
+                ));
+            }
+            let (value, next) = tri!(digits(bytes, position, count));
+            position = next;
+            let mut nanoseconds = 0;
+            let separator = tri!(peek(bytes, position));
+            let has_fraction = separator == b'.' || separator == b',';
+            if has_fraction {
+                let (value, next) = tri!(fraction(bytes, position + 1));
+                nanoseconds = value;
+                position = next;
+            }
+            let designator_position = position;
+            let designator = match (in_time, tri!(peek(bytes, position))) {
+                (false, b'Y') => 1,
+                (false, b'M') => 2,
+                (false, b'W') => 3,
+                (false, b'D') => 4,
+                (true, b'H') => 5,
+                (true, b'M') => 6,
+                (true, b'S') => 7,
+                _ => 0,
+            };
+            if designator <= next_designator || (has_fraction && designator != 7) {
+                return Err(ParseError::new(
+                    designator_position,
+                    ParseErrorKind::UnexpectedCharacter,
+                ));
+            }
+            next_designator = designator;
+            let negative_value = negative != unit_negative;
+            let value = if negative_value { -value } else { value };
+            match designator {
+                1 => period.years = value,
+                2 => period.months = value,
+                3 => {
+                    period.days = match value.checked_mul(7) {
+                        Some(days) => days,
+                        None => {
+                            return Err(ParseError::new(
+                                number_position,
+                                ParseErrorKind::OutOfRange,
+                            ))
+                        }
+                    }
+                }
+                4 => {
+                    period.days = match period.days.checked_add(value) {
+                        Some(days) => days,
+                        None => {
+                            return Err(ParseError::new(
+                                number_position,
+                                ParseErrorKind::OutOfRange,
+                            ))
+                        }
+                    }
+                }
+                5 => period.hours = value,
+                6 => period.minutes = value,
+                _ => {
+                    period.seconds = value;
+                    period.nanoseconds = if negative_value {
+                        -nanoseconds
+                    } else {
+                        nanoseconds
+                    };
+                }
+            }
+            has_component = true;
+            position += 1;
+        }
+        Ok(period)
+    }
+
+    /// Adds this period to a second [`Mark`].
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::period::Period;
+    /// use timelane::iso8601::parse_rfc3339_second;
+    /// let one_month = Period { months: 1, ..Period::ZERO };
+    /// assert_eq!(
+    ///     one_month.add_to_second(parse_rfc3339_second("2001-01-31T12:00:00Z").unwrap()),
+    ///     parse_rfc3339_second("2001-02-28T12:00:00Z").unwrap()
+    /// );
+    /// let one_day = Period { days: 1, ..Period::ZERO };
+    /// assert_eq!(
+    ///     one_day.add_to_second(parse_rfc3339_second("2016-12-31T00:00:00Z").unwrap()),
+    ///     parse_rfc3339_second("2017-01-01T00:00:00Z").unwrap()
+    /// );
+    /// ```
+    pub const fn add_to_second(&self, second: Mark) -> Mark {
+        let (minute, second_of_minute) = second_to_minute_and_second(second);
+        let (day, hour, minute_of_hour) = minute_to_day_hour_minute(minute);
+        let day = self.add_to_day(day);
+        let minute =
+            hour_to_minute(day_to_hour(day) + hour + self.hours) + minute_of_hour + self.minutes;
+        let second_of_minute = if second_of_minute == 60 && !minute_has_leap_second(minute) {
+            59
+        } else {
+            second_of_minute
+        };
+        minute_to_second(minute) + second_of_minute + self.seconds
+    }
+
+    /// Adds this period to a nanosecond [`Mark`].
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::period::Period;
+    /// let period = Period::parse("PT0.5S").unwrap();
+    /// assert_eq!(period.add_to_nanosecond(999_999_999), 1_499_999_999);
+    /// ```
+    pub const fn add_to_nanosecond(&self, nanosecond: Mark) -> Mark {
+        let second = nanosecond_to_second(nanosecond);
+        let nanosecond_of_second = nanosecond - second_to_nanosecond(second);
+        second_to_nanosecond(self.add_to_second(second)) + nanosecond_of_second + self.nanoseconds
+    }
+
+    /// Returns the period between two [`Mark`] of any lane.
+    ///
+    /// See [`Period::difference_at`] for the breakdown of the period. The `const` functions such
+    /// as [`Period::day_difference`] do the same for each lane of the gregorian calendar.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Day;
+    /// use timelane::period::Period;
+    /// assert_eq!(Period::difference::<Day>(1, 32).to_string(), "P1M");
+    /// assert_eq!(Period::difference::<Day>(32, 1).to_string(), "-P1M");
+    /// ```
+    pub fn difference<L: Lane>(start: Mark, end: Mark) -> Period {
+        let (start_second, start_nanosecond) = L::to_second_and_nanosecond(start);
+        let (end_second, end_nanosecond) = L::to_second_and_nanosecond(end);
+        Period::difference_at(start_second, start_nanosecond, end_second, end_nanosecond)
+    }
+
+    /// Returns the period between two second [`Mark`].
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::period::Period;
+    /// use timelane::iso8601::parse_rfc3339_second;
+    /// let start = parse_rfc3339_second("2016-12-31T23:59:59Z").unwrap();
+    /// let end = parse_rfc3339_second("2017-01-01T00:00:00Z").unwrap();
+    /// assert_eq!(Period::second_difference(start, end).to_string(), "PT2S");
+    /// ```
+    pub const fn second_difference(start: Mark, end: Mark) -> Period {
+        Period::difference_at(start, 0, end, 0)
+    }
+
+    /// Returns the period between two year [`Mark`].
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::period::Period;
+    /// assert_eq!(Period::year_difference(2000, 2004).to_string(), "P4Y");
+    /// ```
+    pub const fn year_difference(start: Mark, end: Mark) -> Period {
+        Period::second_difference(year_to_second(start), year_to_second(end))
+    }
+
+    /// Returns the period between two month [`Mark`].
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::period::Period;
+    /// assert_eq!(Period::month_difference(1, 14).to_string(), "P1Y1M");
+    /// ```
+    pub const fn month_difference(start: Mark, end: Mark) -> Period {
+        Period::day_difference(month_to_day(start), month_to_day(end))
+    }
+
+    /// Returns the period between two day [`Mark`].
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::period::Period;
+    /// const ONE_MONTH: Period = Period::day_difference(1, 32);
+    /// assert_eq!(ONE_MONTH.to_string(), "P1M");
+    /// assert_eq!(Period::day_difference(32, 1).to_string(), "-P1M");
+    /// ```
+    pub const fn day_difference(start: Mark, end: Mark) -> Period {
+        Period::second_difference(day_to_second(start), day_to_second(end))
+    }
+
+    /// Returns the period between two hour [`Mark`].
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::period::Period;
+    /// assert_eq!(Period::hour_difference(0, 25).to_string(), "P1DT1H");
+    /// ```
+    pub const fn hour_difference(start: Mark, end: Mark) -> Period {
+        Period::minute_difference(hour_to_minute(start), hour_to_minute(end))
+    }
+
+    /// Returns the period between two minute [`Mark`].
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::period::Period;
+    /// assert_eq!(Period::minute_difference(0, 90).to_string(), "PT1H30M");
+    /// ```
+    pub const fn minute_difference(start: Mark, end: Mark) -> Period {
+        Period::second_difference(minute_to_second(start), minute_to_second(end))
+    }
+
+    /// Returns the period between two millisecond [`Mark`].
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::period::Period;
+    /// assert_eq!(Period::millisecond_difference(-500, 250).to_string(), "PT0.75S");
+    /// ```
+    pub const fn millisecond_difference(start: Mark, end: Mark) -> Period {
+        let start_second = millisecond_to_second(start);
+        let end_second = millisecond_to_second(end);
+        Period::difference_at(
+            start_second,
+            (start - second_to_millisecond(start_second)) * 1_000_000,
+            end_second,
+            (end - second_to_millisecond(end_second)) * 1_000_000,
+        )
+    }
+
+    /// Returns the period between two microsecond [`Mark`].
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::period::Period;
+    /// assert_eq!(Period::microsecond_difference(0, 1_500_000).to_string(), "PT1.5S");
+    /// ```
+    pub const fn microsecond_difference(start: Mark, end: Mark) -> Period {
+        let start_second = microsecond_to_second(start);
+        let end_second = microsecond_to_second(end);
+        Period::difference_at(
+            start_second,
+            (start - second_to_microsecond(start_second)) * 1_000,
+            end_second,
+            (end - second_to_microsecond(end_second)) * 1_000,
+        )
+    }
+
+    /// Returns the period between two nanosecond [`Mark`].
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::period::Period;
+    /// use timelane::iso8601::parse_rfc3339;
+    /// let start = parse_rfc3339("2000-01-31T12:00:00.75Z").unwrap();
+    /// let end = parse_rfc3339("2000-03-01T12:00:00.5Z").unwrap();
+    /// assert_eq!(Period::nanosecond_difference(start, end).to_string(), "P1MT23H59M59.75S");
+    /// ```
+    pub const fn nanosecond_difference(start: Mark, end: Mark) -> Period {
+        let start_second = nanosecond_to_second(start);
+        let end_second = nanosecond_to_second(end);
+        Period::difference_at(
+            start_second,
+            start - second_to_nanosecond(start_second),
+            end_second,
+            end - second_to_nanosecond(end_second),
+        )
+    }
+
+    /// Returns the period between two positions, each given as a second [`Mark`] and a number of
+    /// nanoseconds after that second.
+    ///
+    /// The period is broken down from the largest unit to the smallest: it has as many months as
+    /// possible without passing the end, then as many days, minutes, seconds and nanoseconds.
+    /// All its units have the same sign, and adding it to the start gives back the end.
+    ///
+    /// Because months are clamped when added, the period from January 31st to February 28th is
+    /// one month. A leap second is counted in the seconds, but not in the minutes.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::period::Period;
+    /// use timelane::iso8601::parse_rfc3339_second;
+    /// let start = parse_rfc3339_second("2000-02-29T00:00:00Z").unwrap();
+    /// let end = parse_rfc3339_second("2001-02-28T00:00:00Z").unwrap();
+    /// let period = Period::difference_at(start, 0, end, 0);
+    /// assert_eq!(period.to_string(), "P1Y");
+    /// assert_eq!(period.add_to_second(start), end);
+    /// ```
+    pub const fn difference_at(
+        start_second: Mark,
+        start_nanosecond: Mark,
+        end_second: Mark,
+        end_nanosecond: Mark,
+    ) -> Period {
+        let sign = compare(start_second, start_nanosecond, end_second, end_nanosecond);
+        let mut period = Period::ZERO;
+        if sign == 0 {
+            return period;
+        }
+        let start_day = second_to_day(start_second);
+        let end_day = second_to_day(end_second);
+        // Each unit is first estimated from the difference of marks, and if adding it passes the
+        // end, corrected by one mark
+        period.months = day_to_month(end_day) - day_to_month(start_day);
+        if period.passes(
+            start_second,
+            start_nanosecond,
+            end_second,
+            end_nanosecond,
+            sign,
+        ) {
+            period.months -= sign;
+        }
+        period.days = end_day - period.add_to_day(start_day);
+        if period.passes(
+            start_second,
+            start_nanosecond,
+            end_second,
+            end_nanosecond,
+            sign,
+        ) {
+            period.days -= sign;
+        }
+        period.minutes =
+            second_to_minute(end_second) - second_to_minute(period.add_to_second(start_second));
+        if period.passes(
+            start_second,
+            start_nanosecond,
+            end_second,
+            end_nanosecond,
+            sign,
+        ) {
+            period.minutes -= sign;
+        }
+        period.seconds = end_second - period.add_to_second(start_second);
+        period.nanoseconds = end_nanosecond - start_nanosecond;
+        if period.nanoseconds * sign < 0 {
+            period.seconds -= sign;
+            period.nanoseconds += sign * 1_000_000_000;
+        }
+        period.years = period.months / 12;
+        period.months %= 12;
+        period.hours = period.minutes / 60;
+        period.minutes %= 60;
+        period
+    }
+
+    /// Returns whether adding this period to the start goes past the end, in the direction of
+    /// `sign`.
+    const fn passes(
+        &self,
+        start_second: Mark,
+        start_nanosecond: Mark,
+        end_second: Mark,
+        end_nanosecond: Mark,
+        sign: Mark,
+    ) -> bool {
+        let second = self.add_to_second(start_second);
+        compare(second, start_nanosecond, end_second, end_nanosecond) == -sign
+    }
+
+    /// Adds the years, months and days of this period to a day [`Mark`].
+    const fn add_to_day(&self, day: Mark) -> Mark {
+        let month = day_to_month(day);
+        let day_of_month = day - month_to_day(month);
+        let month = month + self.years * 12 + self.months;
+        let days_in_month = month_to_day(month + 1) - month_to_day(month);
+        let day_of_month = if day_of_month < days_in_month {
+            day_of_month
+        } else {
+            days_in_month - 1
+        };
+        month_to_day(month) + day_of_month + self.days
+    }
+}
+
+/// Returns 1 if the first position is before the second one, -1 if it is after, and 0 if they
+/// are equal.
+const fn compare(
+    first_second: Mark,
+    first_nanosecond: Mark,
+    second_second: Mark,
+    second_nanosecond: Mark,
+) -> Mark {
+    if first_second < second_second
+        || (first_second == second_second && first_nanosecond < second_nanosecond)
+    {
+        1
+    } else if first_second == second_second && first_nanosecond == second_nanosecond {
+        0
+    } else {
+        -1
+    }
+}
+
+impl FromStr for Period {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Period, ParseError> {
+        Period::parse(input)
+    }
+}
+
+impl fmt::Display for Period {
+    /// Formats the period as an ISO 8601 duration.
+    ///
+    /// When all units are negative or zero, the duration starts with a `-` sign, otherwise the
+    /// negative units keep their own sign.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.seconds as i128 * 1_000_000_000 + self.nanoseconds as i128;
+        let units = [self.years, self.months, self.days, self.hours, self.minutes];
+        let negative = (units.iter().any(|&unit| unit < 0) || seconds < 0)
+            && units.iter().all(|&unit| unit <= 0)
+            && seconds <= 0;
+        let sign = if negative { -1 } else { 1 };
+        if negative {
+            f.write_str("-")?;
+        }
+        f.write_str("P")?;
+        for (value, designator) in [(self.years, 'Y'), (self.months, 'M'), (self.days, 'D')] {
+            if value != 0 {
+                write_unit(f, value, negative, designator)?;
+            }
+        }
+        if self.hours == 0 && self.minutes == 0 && seconds == 0 {
+            if units[..3].iter().all(|&unit| unit == 0) {
+                f.write_str("T0S")?;
+            }
+            return Ok(());
+        }
+        f.write_str("T")?;
+        for (value, designator) in [(self.hours, 'H'), (self.minutes, 'M')] {
+            if value != 0 {
+                write_unit(f, value, negative, designator)?;
+            }
+        }
+        if seconds != 0 {
+            let seconds = seconds * sign as i128;
+            let (whole, fraction) = (seconds / 1_000_000_000, (seconds % 1_000_000_000).abs());
+            if seconds < 0 && whole == 0 {
+                f.write_str("-")?;
+            }
+            write!(f, "{}", whole)?;
+            if fraction != 0 {
+                let mut digits = 9;
+                let mut fraction = fraction;
+                while fraction % 10 == 0 {
+                    fraction /= 10;
+                    digits -= 1;
+                }
+                write!(f, ".{:0digits$}", fraction, digits = digits)?;
+            }
+            f.write_str("S")?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a unit of a period and its designator, without the sign of the whole period when it
+/// is negative.
+fn write_unit(
+    f: &mut fmt::Formatter<'_>,
+    value: Mark,
+    negative: bool,
+    designator: char,
+) -> fmt::Result {
+    if negative {
+        write!(f, "{}{}", value.unsigned_abs(), designator)
+    } else {
+        write!(f, "{}{}", value, designator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iso8601::{parse_rfc3339, parse_rfc3339_second};
+
+    fn second(text: &str) -> Mark {
+        parse_rfc3339_second(text).unwrap()
+    }
+
+    fn error(input: &str) -> (usize, ParseErrorKind) {
+        let error = Period::parse(input).unwrap_err();
+        (error.position(), error.kind())
+    }
+
+    #[test]
+    fn durations_roundtrip() {
+        for text in [
+            "P1Y2M10DT2H30M",
+            "P1Y",
+            "P1M",
+            "PT1M",
+            "P1DT1S",
+            "PT0.5S",
+            "PT1.000000001S",
+            "-P1Y2M",
+            "-PT0.25S",
+            "PT0S",
+        ] {
+            assert_eq!(Period::parse(text).unwrap().to_string(), text);
+        }
+    }
+
+    #[test]
+    fn durations_accept_alternative_syntax() {
+        assert_eq!(Period::parse("P1W"), Period::parse("P7D"));
+        assert_eq!(Period::parse("P1W1D"), Period::parse("P8D"));
+        assert_eq!(Period::parse("+P1D"), Period::parse("P1D"));
+        assert_eq!(Period::parse("PT1,5S"), Period::parse("PT1.5S"));
+        assert_eq!("P1D".parse(), Period::parse("P1D"));
+    }
+
+    #[test]
+    fn mixed_signs_are_formatted_per_unit() {
+        let period = Period {
+            months: 1,
+            days: -1,
+            ..Period::ZERO
+        };
+        assert_eq!(period.to_string(), "P1M-1D");
+    }
+
+    #[test]
+    fn mixed_signs_roundtrip() {
+        for text in [
+            "P1M-1D",
+            "P-1Y2M",
+            "P1DT-2H30M",
+            "PT1M-0.5S",
+            "PT-1M1.25S",
+            "P-1MT1S",
+        ] {
+            assert_eq!(Period::parse(text).unwrap().to_string(), text);
+        }
+        let period = Period {
+            years: -1,
+            days: 2,
+            minutes: -3,
+            seconds: 4,
+            nanoseconds: 5,
+            ..Period::ZERO
+        };
+        assert_eq!(period.to_string().parse(), Ok(period));
+        assert_eq!(Period::parse("-P-1D"), Period::parse("P1D"));
+        assert_eq!(error("P-"), (2, ParseErrorKind::UnexpectedEnd));
+        assert_eq!(error("P-T1S"), (2, ParseErrorKind::UnexpectedCharacter));
+    }
+
+    #[test]
+    fn extreme_units_are_formatted() {
+        let period = Period {
+            years: Mark::MIN,
+            ..Period::ZERO
+        };
+        assert_eq!(
+            period.to_string(),
+            format!("-P{}Y", Mark::MIN.unsigned_abs())
+        );
+        let period = Period {
+            hours: Mark::MIN,
+            minutes: Mark::MIN,
+            ..Period::ZERO
+        };
+        let minimum = Mark::MIN.unsigned_abs();
+        assert_eq!(period.to_string(), format!("-PT{}H{}M", minimum, minimum));
+    }
+
+    #[test]
+    fn invalid_durations_are_reported() {
+        assert_eq!(error(""), (0, ParseErrorKind::UnexpectedEnd));
+        assert_eq!(error("P"), (1, ParseErrorKind::UnexpectedEnd));
+        assert_eq!(error("PT"), (2, ParseErrorKind::UnexpectedEnd));
+        assert_eq!(error("P1DT"), (4, ParseErrorKind::UnexpectedEnd));
+        assert_eq!(error("1D"), (0, ParseErrorKind::UnexpectedCharacter));
+        assert_eq!(error("P1H"), (2, ParseErrorKind::UnexpectedCharacter));
+        assert_eq!(error("P1D1Y"), (4, ParseErrorKind::UnexpectedCharacter));
+        assert_eq!(error("P1Y1Y"), (4, ParseErrorKind::UnexpectedCharacter));
+        assert_eq!(error("P1.5Y"), (4, ParseErrorKind::UnexpectedCharacter));
+        assert_eq!(error("P1"), (2, ParseErrorKind::UnexpectedEnd));
+        assert_eq!(error("PT1S1"), (5, ParseErrorKind::UnexpectedEnd));
+    }
+
+    #[test]
+    fn months_clamp_to_the_last_day() {
+        let one_month = Period {
+            months: 1,
+            ..Period::ZERO
+        };
+        assert_eq!(
+            one_month.add_to_second(second("2000-01-31T00:00:00Z")),
+            second("2000-02-29T00:00:00Z")
+        );
+        assert_eq!(
+            one_month.add_to_second(second("2000-03-31T00:00:00Z")),
+            second("2000-04-30T00:00:00Z")
+        );
+        let one_year = Period {
+            years: 1,
+            ..Period::ZERO
+        };
+        assert_eq!(
+            one_year.add_to_second(second("2000-02-29T00:00:00Z")),
+            second("2001-02-28T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn units_are_added_from_largest_to_smallest() {
+        let period = Period::parse("P1M1D").unwrap();
+        assert_eq!(
+            period.add_to_second(second("2001-01-31T00:00:00Z")),
+            second("2001-03-01T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn leap_seconds_count_as_seconds_but_not_in_minutes() {
+        let before = second("2016-12-31T23:59:59Z");
+        assert_eq!(
+            Period::parse("PT1S").unwrap().add_to_second(before),
+            second("2016-12-31T23:59:60Z")
+        );
+        assert_eq!(
+            Period::parse("PT1M").unwrap().add_to_second(before),
+            second("2017-01-01T00:00:59Z")
+        );
+        assert_eq!(
+            Period::parse("P1D")
+                .unwrap()
+                .add_to_second(second("2016-12-31T12:00:00Z")),
+            second("2017-01-01T12:00:00Z")
+        );
+    }
+
+    #[test]
+    fn leap_seconds_are_clamped() {
+        let leap = second("2016-12-31T23:59:60Z");
+        assert_eq!(
+            Period::parse("P1D").unwrap().add_to_second(leap),
+            second("2017-01-01T23:59:59Z")
+        );
+        assert_eq!(
+            Period::parse("P18M").unwrap().add_to_second(leap),
+            second("2018-06-30T23:59:59Z")
+        );
+        assert_eq!(
+            Period::parse("-P18M").unwrap().add_to_second(leap),
+            second("2015-06-30T23:59:60Z")
+        );
+    }
+
+    #[test]
+    fn negative_periods_move_back() {
+        assert_eq!(
+            Period::parse("-P1M")
+                .unwrap()
+                .add_to_second(second("2000-03-31T00:00:00Z")),
+            second("2000-02-29T00:00:00Z")
+        );
+        assert_eq!(
+            Period::parse("-PT0.5S")
+                .unwrap()
+                .add_to_nanosecond(parse_rfc3339("2000-01-01T00:00:00Z").unwrap()),
+            parse_rfc3339("1999-12-31T23:59:59.5Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn differences_are_broken_down_from_the_largest_unit() {
+        let difference = |start, end| Period::second_difference(second(start), second(end));
+        assert_eq!(
+            difference("2000-01-01T00:00:00Z", "2001-03-11T02:30:00Z").to_string(),
+            "P1Y2M10DT2H30M"
+        );
+        assert_eq!(
+            difference("2001-01-31T00:00:00Z", "2001-02-28T00:00:00Z").to_string(),
+            "P1M"
+        );
+        assert_eq!(
+            difference("2001-01-31T00:00:01Z", "2001-02-28T00:00:00Z").to_string(),
+            "P27DT23H59M59S"
+        );
+        assert_eq!(
+            difference("2000-02-29T00:00:00Z", "2004-02-29T00:00:00Z").to_string(),
+            "P4Y"
+        );
+        assert_eq!(
+            difference("2004-02-29T00:00:00Z", "2000-03-01T00:00:00Z").to_string(),
+            "-P3Y11M28D"
+        );
+        assert_eq!(
+            difference("2000-01-01T00:00:00Z", "2000-01-01T00:00:00Z"),
+            Period::ZERO
+        );
+    }
+
+    #[test]
+    fn differences_count_leap_seconds_as_seconds() {
+        let difference = |start, end| Period::second_difference(second(start), second(end));
+        assert_eq!(
+            difference("2016-12-31T23:59:00Z", "2017-01-01T00:00:00Z").to_string(),
+            "PT1M"
+        );
+        assert_eq!(
+            difference("2016-12-31T23:59:60Z", "2017-01-01T23:59:59Z").to_string(),
+            "P1D"
+        );
+        assert_eq!(
+            difference("2017-01-01T00:00:00Z", "2016-12-31T23:59:59Z").to_string(),
+            "-PT2S"
+        );
+    }
+
+    #[test]
+    fn differences_carry_nanoseconds() {
+        let difference = |start, end| {
+            Period::nanosecond_difference(
+                parse_rfc3339(start).unwrap(),
+                parse_rfc3339(end).unwrap(),
+            )
+        };
+        assert_eq!(
+            difference("2000-01-01T00:00:00.5Z", "2000-01-01T00:01:00.25Z").to_string(),
+            "PT59.75S"
+        );
+        assert_eq!(
+            difference("2000-01-01T00:01:00.25Z", "2000-01-01T00:00:00.5Z").to_string(),
+            "-PT59.75S"
+        );
+    }
+
+    #[test]
+    fn lane_differences_match_the_generic_one() {
+        use crate::lane::{Day, Hour, Microsecond, Millisecond, Minute, Month, Year};
+        for (start, end) in [(-1_000, 1_000), (1_000, -1_000), (7, 7), (-3, 5_000)] {
+            assert_eq!(
+                Period::year_difference(start, end),
+                Period::difference::<Year>(start, end)
+            );
+            assert_eq!(
+                Period::month_difference(start, end),
+                Period::difference::<Month>(start, end)
+            );
+            assert_eq!(
+                Period::day_difference(start, end),
+                Period::difference::<Day>(start, end)
+            );
+            assert_eq!(
+                Period::hour_difference(start, end),
+                Period::difference::<Hour>(start, end)
+            );
+            assert_eq!(
+                Period::minute_difference(start, end),
+                Period::difference::<Minute>(start, end)
+            );
+            assert_eq!(
+                Period::millisecond_difference(start, end),
+                Period::difference::<Millisecond>(start, end)
+            );
+            assert_eq!(
+                Period::microsecond_difference(start, end),
+                Period::difference::<Microsecond>(start, end)
+            );
+        }
+    }
+
+    #[test]
+    fn differences_added_to_the_start_give_the_end() {
+        let leap = second("2016-12-31T23:59:60Z");
+        for start in (leap - 4_000_000..leap + 4_000_000).step_by(999_983) {
+            for end in (leap - 80_000_000..leap + 80_000_000).step_by(86_399) {
+                let period = Period::second_difference(start, end);
+                assert_eq!(
+                    period.add_to_second(start),
+                    end,
+                    "{} {} {}",
+                    start,
+                    end,
+                    period
+                );
+            }
+        }
+        for start in leap - 2..leap + 2 {
+            for end in leap - 5_000..leap + 5_000 {
+                let period = Period::second_difference(start, end);
+                assert_eq!(
+                    period.add_to_second(start),
+                    end,
+                    "{} {} {}",
+                    start,
+                    end,
+                    period
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,237 @@
+//! A deliberately naive proleptic gregorian calendar, and dates from published tables, to check
+//! [`crate::month_to_day`] and [`crate::day_to_month`] against.
+//!
+//! The naive calendar walks years and months one by one, with the leap year rule spelled out,
+//! and only relies on the calendar repeating every 400 years to reach the ends of the lanes.
+use crate::test_util::xorshift;
+use crate::{day_to_month, day_to_month_up, month_to_day, Mark, EPOCH_YEAR};
+
+/// Dates as a Rata Die and a gregorian year, month and day, see [`rata_die_to_day`].
+const RATA_DIE_DATES: [(i128, i128, i128, i128); 34] = [
+    (1, 1, 1, 1),
+    (-214_193, -586, 7, 24),
+    (-61_387, -168, 12, 5),
+    (25_469, 70, 9, 24),
+    (49_217, 135, 10, 2),
+    (171_307, 470, 1, 8),
+    (210_155, 576, 5, 20),
+    (253_427, 694, 11, 10),
+    (369_740, 1013, 4, 25),
+    (400_085, 1096, 5, 24),
+    (434_355, 1190, 3, 23),
+    (452_605, 1240, 3, 10),
+    (470_160, 1288, 4, 2),
+    (473_837, 1298, 4, 27),
+    (507_850, 1391, 6, 12),
+    (524_156, 1436, 2, 3),
+    (544_676, 1492, 4, 9),
+    (567_118, 1553, 9, 19),
+    (569_477, 1560, 3, 5),
+    (601_716, 1648, 6, 10),
+    (613_424, 1680, 6, 30),
+    (626_596, 1716, 7, 24),
+    (645_554, 1768, 6, 19),
+    (664_224, 1819, 8, 2),
+    (671_401, 1839, 3, 27),
+    (694_799, 1903, 4, 19),
+    (704_424, 1929, 8, 25),
+    (708_842, 1941, 9, 29),
+    (709_409, 1943, 4, 19),
+    (709_580, 1943, 10, 7),
+    (727_274, 1992, 3, 17),
+    (728_714, 1996, 2, 25),
+    (744_313, 2038, 11, 10),
+    (764_652, 2094, 7, 18),
+];
+
+/// The Rata Die of January 1st, 2000, the day 1.
+const RATA_DIE_OF_DAY_1: Mark = 730_120;
+
+/// Returns the day [`Mark`] of a Rata Die, the day count of the sample data of Reingold and
+/// Dershowitz, "Calendrical Calculations", which the calendar modules are checked against. Rata
+/// Die 1 is January 1st of year 1 in the gregorian calendar.
+pub(crate) const fn rata_die_to_day(rata_die: Mark) -> Mark {
+    rata_die - RATA_DIE_OF_DAY_1 + 1
+}
+
+/// Dates as a Julian Day Number and a gregorian year, month and day: the start of the Julian
+/// period, the start of the common era, the start of the gregorian calendar, the start of the
+/// Modified Julian Days, the POSIX epoch and the last day of year 9999.
+const JULIAN_DAY_DATES: [(i128, i128, i128, i128); 7] = [
+    (0, -4713, 11, 24),
+    (1_721_426, 1, 1, 1),
+    (2_299_161, 1582, 10, 15),
+    (2_400_001, 1858, 11, 17),
+    (2_440_588, 1970, 1, 1),
+    (2_451_545, 2000, 1, 1),
+    (5_373_484, 9999, 12, 31),
+];
+
+/// The Julian Day Number of January 1st, 2000, the day 1.
+const JULIAN_DAY_OF_DAY_1: i128 = 2_451_545;
+
+fn is_leap_year(year: i128) -> bool {
+    if year.rem_euclid(400) == 0 {
+        true
+    } else if year.rem_euclid(100) == 0 {
+        false
+    } else {
+        year.rem_euclid(4) == 0
+    }
+}
+
+fn days_in_month(year: i128, month: i128) -> i128 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The days from the start of [`EPOCH_YEAR`] to the start of each month of the following 400
+/// years, counted month by month, and the days in these 400 years.
+struct Cycle {
+    month_starts: Vec<i128>,
+    days: i128,
+}
+
+impl Cycle {
+    fn new() -> Cycle {
+        let mut month_starts = Vec::new();
+        let mut days = 0;
+        for year in EPOCH_YEAR as i128..EPOCH_YEAR as i128 + 400 {
+            for month in 1..=12 {
+                month_starts.push(days);
+                days += days_in_month(year, month);
+            }
+        }
+        Cycle { month_starts, days }
+    }
+
+    /// Returns the day of the start of a month, which can be outside the lanes.
+    fn month_to_day(&self, month: i128) -> i128 {
+        let zmonth = month - 1;
+        let months_in_cycle = self.month_starts.len() as i128;
+        let cycle = zmonth.div_euclid(months_in_cycle);
+        let month_in_cycle = zmonth.rem_euclid(months_in_cycle) as usize;
+        cycle * self.days + self.month_starts[month_in_cycle] + 1
+    }
+
+    /// Returns the month containing a day, and the zero-based day in that month.
+    fn day_to_month(&self, day: i128) -> (i128, i128) {
+        let zday = day - 1;
+        let cycle = zday.div_euclid(self.days);
+        let day_in_cycle = zday.rem_euclid(self.days);
+        let month_in_cycle = self
+            .month_starts
+            .partition_point(|&start| start <= day_in_cycle)
+            - 1;
+        let month = cycle * self.month_starts.len() as i128 + month_in_cycle as i128 + 1;
+        (month, day_in_cycle - self.month_starts[month_in_cycle])
+    }
+}
+
+/// Returns the day [`Mark`] of a gregorian date, through the month lane.
+fn date_to_day(year: i128, month: i128, day: i128) -> Mark {
+    let month = (year - EPOCH_YEAR as i128) * 12 + month;
+    month_to_day(month as Mark) + day as Mark - 1
+}
+
+#[test]
+fn scalers_match_published_dates() {
+    for (rata_die, year, month, day) in RATA_DIE_DATES {
+        let mark = date_to_day(year, month, day);
+        assert_eq!(mark, rata_die_to_day(rata_die as Mark), "{}", rata_die);
+        assert_eq!(
+            day_to_month(mark) as i128,
+            (year - EPOCH_YEAR as i128) * 12 + month
+        );
+    }
+    for (julian_day, year, month, day) in JULIAN_DAY_DATES {
+        let mark = date_to_day(year, month, day);
+        assert_eq!(
+            mark as i128,
+            julian_day - JULIAN_DAY_OF_DAY_1 + 1,
+            "{}",
+            julian_day
+        );
+        assert_eq!(
+            day_to_month(mark) as i128,
+            (year - EPOCH_YEAR as i128) * 12 + month
+        );
+    }
+}
+
+#[test]
+fn naive_cycle_matches_published_dates() {
+    let cycle = Cycle::new();
+    assert_eq!(cycle.days, 146_097);
+    for (rata_die, year, month, day) in RATA_DIE_DATES {
+        let month = (year - EPOCH_YEAR as i128) * 12 + month;
+        assert_eq!(
+            cycle.month_to_day(month) + day - 1,
+            rata_die_to_day(rata_die as Mark) as i128
+        );
+    }
+}
+
+#[test]
+fn scalers_match_a_walk_over_millions_of_years() {
+    // Walk backwards and forwards from the epoch, one month at a time
+    let years = 1_200_000;
+    let mut first_day_of_month = 1;
+    for zmonth in 0..years * 12 {
+        let year = EPOCH_YEAR as i128 + zmonth / 12;
+        let month = (zmonth + 1) as Mark;
+        let days = days_in_month(year, zmonth % 12 + 1);
+        assert_eq!(month_to_day(month) as i128, first_day_of_month, "{}", month);
+        let last_day_of_month = (first_day_of_month + days - 1) as Mark;
+        assert_eq!(day_to_month(last_day_of_month), month, "{}", month);
+        assert_eq!(
+            day_to_month_up(last_day_of_month + 1),
+            month + 1,
+            "{}",
+            month
+        );
+        first_day_of_month += days;
+    }
+    let mut first_day_of_month = 1;
+    for zmonth in (-years * 12..0).rev() {
+        let year = EPOCH_YEAR as i128 + zmonth.div_euclid(12);
+        let month = (zmonth + 1) as Mark;
+        first_day_of_month -= days_in_month(year, zmonth.rem_euclid(12) + 1);
+        assert_eq!(month_to_day(month) as i128, first_day_of_month, "{}", month);
+        assert_eq!(day_to_month(first_day_of_month as Mark), month, "{}", month);
+    }
+}
+
+#[test]
+fn scalers_match_the_naive_calendar_over_the_lanes() {
+    let cycle = Cycle::new();
+    // The months whose first day is a day mark
+    let first_month = day_to_month_up(Mark::MIN);
+    let last_month = day_to_month(Mark::MAX);
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let random_months = (0..100_000).map(|_| {
+        let span = (last_month - first_month) as u64;
+        first_month + (xorshift(&mut state) % span) as Mark
+    });
+    let ends = (first_month..first_month + 5_000).chain(last_month - 5_000..=last_month);
+    for month in random_months.chain(ends) {
+        assert_eq!(
+            month_to_day(month) as i128,
+            cycle.month_to_day(month as i128),
+            "{}",
+            month
+        );
+    }
+    let random_days = (0..100_000).map(|_| xorshift(&mut state) as Mark);
+    let ends = (Mark::MIN..Mark::MIN + 5_000).chain(Mark::MAX - 5_000..=Mark::MAX);
+    for day in random_days.chain(ends) {
+        let (month, zday_in_month) = cycle.day_to_month(day as i128);
+        assert_eq!(day_to_month(day) as i128, month, "{}", day);
+        let month_up = month + (zday_in_month != 0) as i128;
+        assert_eq!(day_to_month_up(day) as i128, month_up, "{}", day);
+    }
+}
@@ -0,0 +1,444 @@
+//! Format and parse the dates of emails (RFC 2822) and of HTTP headers (RFC 7231).
+//!
+//! The parsers are lenient: they accept the obsolete syntax of RFC 2822, like two digits years,
+//! comments and zone names, and for HTTP dates the obsolete RFC 850 and asctime formats that
+//! RFC 7231 requires recipients to accept.
+//!
+//! Two digits years from `00` to `49` are years 2000 to 2049, and from `50` to `99` are years
+//! 1950 to 1999, as specified by RFC 2822.
+//!
+//! When present, the day of week must match the date. A second of `60` is only accepted during
+//! leap seconds.
+use crate::civil::{
+    day_to_weekday, days_in_month, year_month_day_to_day, MONTH_NAMES, WEEKDAY_NAMES,
+};
+use crate::lane::Second;
+use crate::parse::{
+    check_range, check_year, count_digits, digits, expect, expect_end, parse_name, peek,
+    DateTimeFields, ParseError, ParseErrorKind,
+};
+use crate::strftime::{Formatted, Pattern};
+use crate::Mark;
+
+const RFC2822: Pattern<'static> = crate::strftime!("%a, %d %b %Y %H:%M:%S %z");
+
+const HTTP_DATE: Pattern<'static> = crate::strftime!("%a, %d %b %Y %H:%M:%S GMT");
+
+/// Formats a second [`Mark`] as an RFC 2822 date, with a UTC offset in minutes.
+///
+/// # Examples
+/// ```
+/// use timelane::rfc2822::format_rfc2822;
+/// assert_eq!(format_rfc2822(0, 0).to_string(), "Sat, 01 Jan 2000 00:00:00 +0000");
+/// assert_eq!(format_rfc2822(0, -90).to_string(), "Fri, 31 Dec 1999 22:30:00 -0130");
+/// ```
+pub fn format_rfc2822(second: Mark, offset: Mark) -> Formatted<'static, Second> {
+    RFC2822.format::<Second>(second).with_offset(offset)
+}
+
+/// Formats a second [`Mark`] as an HTTP date, in the preferred IMF-fixdate format.
+///
+/// # Examples
+/// ```
+/// use timelane::rfc2822::{format_http_date, parse_http_date};
+/// let second = parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+/// assert_eq!(format_http_date(second).to_string(), "Sun, 06 Nov 1994 08:49:37 GMT");
+/// ```
+pub fn format_http_date(second: Mark) -> Formatted<'static, Second> {
+    HTTP_DATE.format::<Second>(second)
+}
+
+/// Parses an RFC 2822 date into a second [`Mark`].
+///
+/// # Examples
+/// ```
+/// use timelane::rfc2822::parse_rfc2822;
+/// assert_eq!(parse_rfc2822("Sat, 01 Jan 2000 00:00:00 +0000"), Ok(0));
+/// assert_eq!(parse_rfc2822("1 Jan 2000 01:00 +0100"), Ok(0));
+/// assert_eq!(parse_rfc2822("Fri, 31 Dec 99 19:00:00 EST"), Ok(0));
+/// assert_eq!(parse_rfc2822("Sat (weekend), 1 Jan 2000 00 : 00 : 00 GMT"), Ok(0));
+/// ```
+pub fn parse_rfc2822(input: &str) -> Result<Mark, ParseError> {
+    fields(input.as_bytes(), false)?.to_second()
+}
+
+/// Parses an HTTP date into a second [`Mark`].
+///
+/// This accepts the IMF-fixdate, RFC 850 and asctime formats, and the same lenient syntax as
+/// [`parse_rfc2822`].
+///
+/// The two digits years of the RFC 850 format are expanded with the fixed rule of RFC 2822, and
+/// not as RFC 7231 asks, to the year at most 50 years after the current one: the parser does not
+/// depend on a clock. `70` is always 1970 and `49` always 2049.
+///
+/// # Examples
+/// ```
+/// use timelane::rfc2822::parse_http_date;
+/// let expected = parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT");
+/// assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), expected);
+/// assert_eq!(parse_http_date("Sun Nov  6 08:49:37 1994"), expected);
+/// assert_eq!(
+///     parse_http_date("Friday, 01-Jan-49 00:00:00 GMT"),
+///     parse_http_date("Fri, 01 Jan 2049 00:00:00 GMT"),
+/// );
+/// ```
+pub fn parse_http_date(input: &str) -> Result<Mark, ParseError> {
+    fields(input.as_bytes(), true)?.to_second()
+}
+
+/// Parses the fields of a date, `http` enables the RFC 850 and asctime formats.
+fn fields(bytes: &[u8], http: bool) -> Result<DateTimeFields, ParseError> {
+    let mut position = skip_cfws(bytes, 0)?;
+    let mut weekday = None;
+    if position < bytes.len() && bytes[position].is_ascii_alphabetic() {
+        let (value, next) = parse_name(bytes, position, &WEEKDAY_NAMES)?;
+        weekday = Some((value, position));
+        position = skip_cfws(bytes, next)?;
+        if position < bytes.len() && bytes[position] == b',' {
+            position = skip_cfws(bytes, position + 1)?;
+        }
+    }
+    let asctime = http && position < bytes.len() && bytes[position].is_ascii_alphabetic();
+    let (year, month, day, day_position);
+    let time_position;
+    if asctime {
+        let (value, next) = parse_name(bytes, position, &MONTH_NAMES)?;
+        month = value;
+        day_position = skip_cfws(bytes, next)?;
+        let (value, next) = one_or_two_digits(bytes, day_position)?;
+        day = value;
+        time_position = skip_cfws(bytes, next)?;
+        year = None;
+    } else {
+        day_position = position;
+        let (value, next) = one_or_two_digits(bytes, day_position)?;
+        day = value;
+        let dashed = http && next < bytes.len() && bytes[next] == b'-';
+        position = if dashed {
+            next + 1
+        } else {
+            skip_cfws(bytes, next)?
+        };
+        let (value, next) = parse_name(bytes, position, &MONTH_NAMES)?;
+        month = value;
+        position = if dashed {
+            expect(bytes, next, b'-')?
+        } else {
+            skip_cfws(bytes, next)?
+        };
+        let (value, next) = parse_year(bytes, position)?;
+        year = Some(value);
+        time_position = skip_cfws(bytes, next)?;
+    }
+    let (hour, minute, second, second_position, next) = parse_time(bytes, time_position)?;
+    position = skip_cfws(bytes, next)?;
+    let (year, offset) = match year {
+        // The asctime format has the year after the time, and no zone
+        None => {
+            let (year, next) = parse_year(bytes, position)?;
+            position = skip_cfws(bytes, next)?;
+            if position < bytes.len() {
+                let (offset, next) = parse_zone(bytes, position)?;
+                position = next;
+                (year, offset)
+            } else {
+                (year, 0)
+            }
+        }
+        Some(year) => {
+            let (offset, next) = parse_zone(bytes, position)?;
+            position = next;
+            (year, offset)
+        }
+    };
+    position = skip_cfws(bytes, position)?;
+    expect_end(bytes, position)?;
+    check_range(
+        day,
+        1,
+        days_in_month(year, month),
+        day_position,
+        ParseErrorKind::InvalidDay,
+    )?;
+    let day = year_month_day_to_day(year, month, day);
+    if let Some((weekday, position)) = weekday {
+        if day_to_weekday(day) != weekday {
+            return Err(ParseError::new(position, ParseErrorKind::MismatchedWeekday));
+        }
+    }
+    Ok(DateTimeFields {
+        day,
+        hour,
+        minute,
+        second,
+        nanosecond: 0,
+        offset,
+        start_position: 0,
+        second_position,
+    })
+}
+
+/// Skips whitespace and comments, which are nested parentheses.
+fn skip_cfws(bytes: &[u8], mut position: usize) -> Result<usize, ParseError> {
+    let mut depth = 0;
+    while position < bytes.len() {
+        match bytes[position] {
+            b'(' => depth += 1,
+            b')' if depth > 0 => depth -= 1,
+            b'\\' if depth > 0 => position += 1,
+            byte if depth == 0 && !byte.is_ascii_whitespace() => return Ok(position),
+            _ => {}
+        }
+        position += 1;
+    }
+    if depth > 0 {
+        return Err(ParseError::new(bytes.len(), ParseErrorKind::UnexpectedEnd));
+    }
+    Ok(position)
+}
+
+fn one_or_two_digits(bytes: &[u8], position: usize) -> Result<(Mark, usize), ParseError> {
+    let count = count_digits(bytes, position);
+    if count == 0 {
+        peek(bytes, position)?;
+        return Err(ParseError::new(
+            position,
+            ParseErrorKind::UnexpectedCharacter,
+        ));
+    }
+    digits(bytes, position, count.min(2))
+}
+
+/// Parses a year of at least two digits, returns the year and the next position.
+fn parse_year(bytes: &[u8], position: usize) -> Result<(Mark, usize), ParseError> {
+    let count = count_digits(bytes, position);
+    if count < 2 {
+        peek(bytes, position + count)?;
+        return Err(ParseError::new(
+            position + count,
+            ParseErrorKind::UnexpectedCharacter,
+        ));
+    }
+    let (year, next) = digits(bytes, position, count)?;
+    let year = match count {
+        2 if year < 50 => year + 2000,
+        2 | 3 => year + 1900,
+        _ => year,
+    };
+    Ok((check_year(year, position)?, next))
+}
+
+/// Parses `HH:MM` or `HH:MM:SS`, returns the hour, minute, second, position of the second and
+/// the next position.
+fn parse_time(
+    bytes: &[u8],
+    position: usize,
+) -> Result<(Mark, Mark, Mark, usize, usize), ParseError> {
+    let (hour, next) = digits(bytes, position, 2)?;
+    check_range(hour, 0, 23, position, ParseErrorKind::InvalidHour)?;
+    let next = expect(bytes, skip_cfws(bytes, next)?, b':')?;
+    let minute_position = skip_cfws(bytes, next)?;
+    let (minute, next) = digits(bytes, minute_position, 2)?;
+    check_range(
+        minute,
+        0,
+        59,
+        minute_position,
+        ParseErrorKind::InvalidMinute,
+    )?;
+    let after_minute = skip_cfws(bytes, next)?;
+    if after_minute < bytes.len() && bytes[after_minute] == b':' {
+        let second_position = skip_cfws(bytes, after_minute + 1)?;
+        let (second, next) = digits(bytes, second_position, 2)?;
+        check_range(
+            second,
+            0,
+            60,
+            second_position,
+            ParseErrorKind::InvalidSecond,
+        )?;
+        Ok((hour, minute, second, second_position, next))
+    } else {
+        Ok((hour, minute, 0, minute_position, next))
+    }
+}
+
+/// Parses a numeric `+HHMM` zone, or an obsolete zone name, returns the UTC offset in minutes.
+///
+/// Military zones are treated as `-0000`, as recommended by RFC 2822.
+fn parse_zone(bytes: &[u8], position: usize) -> Result<(Mark, usize), ParseError> {
+    let sign = peek(bytes, position)?;
+    if sign == b'+' || sign == b'-' {
+        let (hours, next) = digits(bytes, position + 1, 2)?;
+        check_range(hours, 0, 23, position + 1, ParseErrorKind::InvalidOffset)?;
+        let (minutes, next_position) = digits(bytes, next, 2)?;
+        check_range(minutes, 0, 59, next, ParseErrorKind::InvalidOffset)?;
+        let offset = hours * 60 + minutes;
+        return Ok((if sign == b'-' { -offset } else { offset }, next_position));
+    }
+    let mut end = position;
+    while end < bytes.len() && bytes[end].is_ascii_alphabetic() {
+        end += 1;
+    }
+    let name = &bytes[position..end];
+    const ZONES: [(&str, Mark); 11] = [
+        ("UT", 0),
+        ("UTC", 0),
+        ("GMT", 0),
+        ("EST", -5),
+        ("EDT", -4),
+        ("CST", -6),
+        ("CDT", -5),
+        ("MST", -7),
+        ("MDT", -6),
+        ("PST", -8),
+        ("PDT", -7),
+    ];
+    for (zone, hours) in ZONES {
+        if name.eq_ignore_ascii_case(zone.as_bytes()) {
+            return Ok((hours * 60, end));
+        }
+    }
+    if name.len() == 1 && !name[0].eq_ignore_ascii_case(&b'J') {
+        return Ok((0, end));
+    }
+    Err(ParseError::new(
+        position,
+        ParseErrorKind::UnexpectedCharacter,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iso8601::parse_rfc3339_second;
+
+    fn error(result: Result<Mark, ParseError>) -> (usize, ParseErrorKind) {
+        let error = result.unwrap_err();
+        (error.position(), error.kind())
+    }
+
+    #[test]
+    fn rfc2822_examples_are_parsed() {
+        assert_eq!(
+            parse_rfc2822("Fri, 21 Nov 1997 09:55:06 -0600"),
+            parse_rfc3339_second("1997-11-21T09:55:06-06:00")
+        );
+        assert_eq!(
+            parse_rfc2822("Thu, 13 Feb 1969 23:32:54 -0330"),
+            parse_rfc3339_second("1969-02-13T23:32:54-03:30")
+        );
+        assert_eq!(
+            parse_rfc2822("Thu,\r\n      13\r\n        Feb\r\n          1969\r\n      23:32\r\n               -0330 (Newfoundland Time)"),
+            parse_rfc3339_second("1969-02-13T23:32:00-03:30")
+        );
+        assert_eq!(
+            parse_rfc2822("21 Nov 97 09:55:06 GMT"),
+            parse_rfc3339_second("1997-11-21T09:55:06Z")
+        );
+        assert_eq!(
+            parse_rfc2822("Fri, 21 Nov 1997 09(comment):   55  :  06 -0600"),
+            parse_rfc3339_second("1997-11-21T09:55:06-06:00")
+        );
+    }
+
+    #[test]
+    fn rfc2822_obsolete_years_are_expanded() {
+        assert_eq!(
+            parse_rfc2822("1 Jan 49 00:00 +0000"),
+            parse_rfc3339_second("2049-01-01T00:00:00Z")
+        );
+        assert_eq!(
+            parse_rfc2822("1 Jan 50 00:00 +0000"),
+            parse_rfc3339_second("1950-01-01T00:00:00Z")
+        );
+        assert_eq!(
+            parse_rfc2822("1 Jan 103 00:00 +0000"),
+            parse_rfc3339_second("2003-01-01T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn rfc2822_obsolete_zones_are_parsed() {
+        let expected = parse_rfc3339_second("2000-01-01T00:00:00Z");
+        assert_eq!(parse_rfc2822("1 Jan 2000 00:00 UT"), expected);
+        assert_eq!(parse_rfc2822("1 Jan 2000 00:00 Z"), expected);
+        assert_eq!(parse_rfc2822("1 Jan 2000 00:00 A"), expected);
+        assert_eq!(parse_rfc2822("31 Dec 1999 16:00 PST"), expected);
+        assert_eq!(parse_rfc2822("31 Dec 1999 17:00 PDT"), expected);
+        assert_eq!(
+            error(parse_rfc2822("1 Jan 2000 00:00 J")),
+            (17, ParseErrorKind::UnexpectedCharacter)
+        );
+    }
+
+    #[test]
+    fn formatting_roundtrips_with_offsets() {
+        let second = parse_rfc3339_second("1997-11-21T09:55:06-06:00").unwrap();
+        let text = format_rfc2822(second, -6 * 60).to_string();
+        assert_eq!(text, "Fri, 21 Nov 1997 09:55:06 -0600");
+        assert_eq!(parse_rfc2822(&text), Ok(second));
+    }
+
+    #[test]
+    fn leap_seconds_roundtrip() {
+        let second = parse_rfc3339_second("2016-12-31T23:59:60Z").unwrap();
+        let text = format_http_date(second).to_string();
+        assert_eq!(text, "Sat, 31 Dec 2016 23:59:60 GMT");
+        assert_eq!(parse_http_date(&text), Ok(second));
+        assert_eq!(
+            error(parse_http_date("Thu, 31 Dec 2015 23:59:60 GMT")),
+            (23, ParseErrorKind::InvalidLeapSecond)
+        );
+    }
+
+    #[test]
+    fn day_of_week_must_match_the_date() {
+        assert_eq!(
+            error(parse_http_date("Mon, 06 Nov 1994 08:49:37 GMT")),
+            (0, ParseErrorKind::MismatchedWeekday)
+        );
+        assert_eq!(
+            error(parse_http_date("Monday, 06-Nov-94 08:49:37 GMT")),
+            (0, ParseErrorKind::MismatchedWeekday)
+        );
+        assert_eq!(
+            error(parse_http_date("Mon Nov  6 08:49:37 1994")),
+            (0, ParseErrorKind::MismatchedWeekday)
+        );
+    }
+
+    #[test]
+    fn http_formats_are_not_rfc2822() {
+        assert_eq!(
+            error(parse_rfc2822("Sunday, 06-Nov-94 08:49:37 GMT")),
+            (10, ParseErrorKind::UnexpectedCharacter)
+        );
+        assert_eq!(
+            error(parse_rfc2822("Sun Nov  6 08:49:37 1994")),
+            (4, ParseErrorKind::UnexpectedCharacter)
+        );
+    }
+
+    #[test]
+    fn invalid_dates_are_reported() {
+        assert_eq!(
+            error(parse_http_date("Sun, 31 Nov 1994 08:49:37 GMT")),
+            (5, ParseErrorKind::InvalidDay)
+        );
+        assert_eq!(
+            error(parse_http_date("Sun, 06 Nov 1994 24:49:37 GMT")),
+            (17, ParseErrorKind::InvalidHour)
+        );
+        assert_eq!(
+            error(parse_http_date("Sun, 06 Nov 1994 08:49:37")),
+            (25, ParseErrorKind::UnexpectedEnd)
+        );
+        assert_eq!(
+            error(parse_http_date(
+                "Sun, 06 Nov 1994 08:49:37 GMT (unterminated"
+            )),
+            (43, ParseErrorKind::UnexpectedEnd)
+        );
+    }
+}
@@ -0,0 +1,1397 @@
+//! Recurrence rules of RFC 5545 (iCalendar), expanded lazily into second marks, with the `alloc`
+//! feature.
+//!
+//! A [`RecurrenceRule`] is parsed from the value of an `RRULE` property, like
+//! `FREQ=MONTHLY;BYDAY=-1FR;COUNT=12`. Its occurrences are expanded from a start second
+//! [`Mark`], the `DTSTART` of the event, in the wall clock time of a [`Zone`]: a rule repeating
+//! at 09:00 stays at 09:00 local time across daylight saving time changes. Local times skipped by
+//! the clock are moved forward by the length of the gap, repeated local times use their first
+//! occurrence.
+//!
+//! All the rule parts are supported: `FREQ`, `INTERVAL`, `COUNT`, `UNTIL`, `BYSECOND`,
+//! `BYMINUTE`, `BYHOUR`, `BYDAY`, `BYMONTHDAY`, `BYYEARDAY`, `BYWEEKNO`, `BYMONTH`, `BYSETPOS`
+//! and `WKST`. The `EXDATE` of an event are removed with [`Occurrences::excluding`].
+//!
+//! # Examples
+//! ```
+//! use timelane::iso8601::parse_rfc3339_second;
+//! use timelane::rrule::RecurrenceRule;
+//! use timelane::zone::Utc;
+//! let rule: RecurrenceRule = "FREQ=MONTHLY;BYDAY=-1FR;COUNT=12".parse().unwrap();
+//! let start = parse_rfc3339_second("2024-01-01T17:00:00Z").unwrap();
+//! let fridays: Vec<_> = rule.occurrences(start, Utc).collect();
+//! assert_eq!(fridays.len(), 12);
+//! assert_eq!(fridays[0], parse_rfc3339_second("2024-01-26T17:00:00Z").unwrap());
+//! assert_eq!(fridays[11], parse_rfc3339_second("2024-12-27T17:00:00Z").unwrap());
+//! ```
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use core::str::FromStr;
+
+use crate::civil::{
+    day_to_weekday, day_to_year_month_day, days_in_month, days_in_year, minute_has_leap_second,
+    minute_to_day_hour_minute, second_to_minute_and_second, year_month_day_to_day,
+};
+use crate::parse::{check_range, check_year, count_digits, digits, ParseError, ParseErrorKind};
+use crate::zone::Zone;
+use crate::{day_to_hour, hour_to_minute, minute_to_second, month_to_day, year_to_month, Mark};
+
+/// The number of days in 400 years, after which the Gregorian calendar and the days of week
+/// repeat.
+const CYCLE_DAYS: Mark = 146_097;
+
+/// How often a [`RecurrenceRule`] repeats, the `FREQ` rule part.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Frequency {
+    /// Every second.
+    Secondly,
+    /// Every minute.
+    Minutely,
+    /// Every hour.
+    Hourly,
+    /// Every day.
+    Daily,
+    /// Every week, starting on the `WKST` day of week.
+    Weekly,
+    /// Every month.
+    Monthly,
+    /// Every year.
+    Yearly,
+}
+
+/// A day of week.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Weekday {
+    /// Monday, `MO`.
+    Monday = 1,
+    /// Tuesday, `TU`.
+    Tuesday,
+    /// Wednesday, `WE`.
+    Wednesday,
+    /// Thursday, `TH`.
+    Thursday,
+    /// Friday, `FR`.
+    Friday,
+    /// Saturday, `SA`.
+    Saturday,
+    /// Sunday, `SU`.
+    Sunday,
+}
+
+impl Weekday {
+    const ALL: [Weekday; 7] = [
+        Self::Monday,
+        Self::Tuesday,
+        Self::Wednesday,
+        Self::Thursday,
+        Self::Friday,
+        Self::Saturday,
+        Self::Sunday,
+    ];
+
+    /// Returns the day of week of a day [`Mark`].
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::rrule::Weekday;
+    /// assert_eq!(Weekday::of_day(1), Weekday::Saturday);
+    /// ```
+    pub const fn of_day(day: Mark) -> Self {
+        Self::ALL[day_to_weekday(day) as usize - 1]
+    }
+
+    /// Returns the number of days from a day of week to the next day of week `other`, from 0 to
+    /// 6.
+    const fn days_until(self, other: Self) -> Mark {
+        (other as Mark - self as Mark).rem_euclid(7)
+    }
+}
+
+/// A day of week of the `BYDAY` rule part, with an optional ordinal like `-1` in `-1FR`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct WeekdayNumber {
+    /// The ordinal of the day of week in the month or the year, negative from the end, or 0 for
+    /// all of them.
+    ordinal: Mark,
+    weekday: Weekday,
+}
+
+/// The end of a [`RecurrenceRule`], the `UNTIL` rule part.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Until {
+    /// A UTC date and time, as a second [`Mark`].
+    Utc(Mark),
+    /// A local date and time, as a local minute [`Mark`] and a second of minute.
+    Local(Mark, Mark),
+    /// A local date, as a day [`Mark`], including all of that day.
+    Date(Mark),
+}
+
+/// A recurrence rule of RFC 5545, the value of an `RRULE` property.
+///
+/// # Examples
+/// ```
+/// use timelane::iso8601::parse_rfc3339_second;
+/// use timelane::rrule::{Frequency, RecurrenceRule};
+/// use timelane::zone::FixedOffset;
+/// let rule = RecurrenceRule::parse("RRULE:FREQ=WEEKLY;BYDAY=TU,TH;UNTIL=20240118T235959Z").unwrap();
+/// assert_eq!(rule.frequency(), Frequency::Weekly);
+/// let start = parse_rfc3339_second("2024-01-02T09:00:00+01:00").unwrap();
+/// assert_eq!(rule.occurrences(start, FixedOffset(60)).count(), 6);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RecurrenceRule {
+    frequency: Frequency,
+    interval: Mark,
+    count: Option<Mark>,
+    until: Option<Until>,
+    by_second: Vec<Mark>,
+    by_minute: Vec<Mark>,
+    by_hour: Vec<Mark>,
+    by_day: Vec<WeekdayNumber>,
+    by_month_day: Vec<Mark>,
+    by_year_day: Vec<Mark>,
+    by_week_no: Vec<Mark>,
+    by_month: Vec<Mark>,
+    by_set_pos: Vec<Mark>,
+    week_start: Weekday,
+}
+
+impl RecurrenceRule {
+    /// Parses the value of an `RRULE` property, with or without the `RRULE:` prefix.
+    ///
+    /// Rule part names and values are case-insensitive. The combinations of rule parts that
+    /// RFC 5545 forbids are rejected with [`ParseErrorKind::InvalidRule`].
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::parse::ParseErrorKind;
+    /// use timelane::rrule::RecurrenceRule;
+    /// assert!(RecurrenceRule::parse("FREQ=YEARLY;BYWEEKNO=20;BYDAY=MO").is_ok());
+    /// let error = RecurrenceRule::parse("FREQ=MONTHLY;BYWEEKNO=20").unwrap_err();
+    /// assert_eq!((error.position(), error.kind()), (13, ParseErrorKind::InvalidRule));
+    /// let error = RecurrenceRule::parse("FREQ=DAILY;BYHOUR=24").unwrap_err();
+    /// assert_eq!((error.position(), error.kind()), (18, ParseErrorKind::InvalidHour));
+    /// ```
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let bytes = text.as_bytes();
+        let mut position = 0;
+        if bytes.len() >= 6 && bytes[..6].eq_ignore_ascii_case(b"RRULE:") {
+            position = 6;
+        }
+        let mut frequency = None;
+        let mut rule = Self {
+            frequency: Frequency::Yearly,
+            interval: 1,
+            count: None,
+            until: None,
+            by_second: Vec::new(),
+            by_minute: Vec::new(),
+            by_hour: Vec::new(),
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_year_day: Vec::new(),
+            by_week_no: Vec::new(),
+            by_month: Vec::new(),
+            by_set_pos: Vec::new(),
+            week_start: Weekday::Monday,
+        };
+        // The position of each part seen, to reject repeated parts and report invalid combinations
+        let mut seen: [Option<usize>; PART_NAMES.len()] = [None; PART_NAMES.len()];
+        loop {
+            let name_end = find(bytes, position, b'=');
+            let part = PART_NAMES
+                .iter()
+                .position(|name| bytes[position..name_end].eq_ignore_ascii_case(name.as_bytes()))
+                .ok_or_else(|| unexpected(bytes, position))?;
+            if seen[part].is_some() {
+                return Err(ParseError::new(position, ParseErrorKind::InvalidRule));
+            }
+            seen[part] = Some(position);
+            if name_end == bytes.len() {
+                return Err(ParseError::new(name_end, ParseErrorKind::UnexpectedEnd));
+            }
+            let start = name_end + 1;
+            let end = find(bytes, start, b';');
+            let value = Value { bytes, start, end };
+            match part {
+                FREQ => frequency = Some(value.frequency()?),
+                INTERVAL => {
+                    rule.interval = value.integer(1, Mark::MAX, ParseErrorKind::InvalidRule)?
+                }
+                COUNT => {
+                    rule.count = Some(value.integer(1, Mark::MAX, ParseErrorKind::InvalidRule)?)
+                }
+                UNTIL => rule.until = Some(value.until()?),
+                BYSECOND => {
+                    rule.by_second =
+                        value.list(|item| item.integer(0, 60, ParseErrorKind::InvalidSecond))?
+                }
+                BYMINUTE => {
+                    rule.by_minute =
+                        value.list(|item| item.integer(0, 59, ParseErrorKind::InvalidMinute))?
+                }
+                BYHOUR => {
+                    rule.by_hour =
+                        value.list(|item| item.integer(0, 23, ParseErrorKind::InvalidHour))?
+                }
+                BYDAY => rule.by_day = value.list(Value::weekday_number)?,
+                BYMONTHDAY => {
+                    rule.by_month_day =
+                        value.list(|item| item.signed(31, ParseErrorKind::InvalidDay))?
+                }
+                BYYEARDAY => {
+                    rule.by_year_day =
+                        value.list(|item| item.signed(366, ParseErrorKind::InvalidDay))?
+                }
+                BYWEEKNO => {
+                    rule.by_week_no =
+                        value.list(|item| item.signed(53, ParseErrorKind::InvalidWeek))?
+                }
+                BYMONTH => {
+                    rule.by_month =
+                        value.list(|item| item.integer(1, 12, ParseErrorKind::InvalidMonth))?
+                }
+                BYSETPOS => {
+                    rule.by_set_pos =
+                        value.list(|item| item.signed(366, ParseErrorKind::InvalidRule))?
+                }
+                _ => rule.week_start = value.weekday()?,
+            }
+            if end == bytes.len() {
+                break;
+            }
+            position = end + 1;
+        }
+        rule.frequency =
+            frequency.ok_or(ParseError::new(bytes.len(), ParseErrorKind::InvalidRule))?;
+        let invalid = |part: usize| {
+            seen[part].map(|position| ParseError::new(position, ParseErrorKind::InvalidRule))
+        };
+        let frequency = rule.frequency;
+        let checks = [
+            (UNTIL, seen[COUNT].is_some()),
+            (BYWEEKNO, frequency != Frequency::Yearly),
+            (
+                BYYEARDAY,
+                matches!(
+                    frequency,
+                    Frequency::Daily | Frequency::Weekly | Frequency::Monthly
+                ),
+            ),
+            (BYMONTHDAY, frequency == Frequency::Weekly),
+            (
+                BYDAY,
+                rule.by_day.iter().any(|day| day.ordinal != 0)
+                    && (frequency < Frequency::Monthly
+                        || (frequency == Frequency::Yearly && seen[BYWEEKNO].is_some())),
+            ),
+            (
+                BYSETPOS,
+                seen[BYSECOND..=BYMONTH].iter().all(Option::is_none),
+            ),
+        ];
+        for (part, is_invalid) in checks {
+            if is_invalid {
+                if let Some(error) = invalid(part) {
+                    return Err(error);
+                }
+            }
+        }
+        Ok(rule)
+    }
+
+    /// Returns how often the rule repeats.
+    pub const fn frequency(&self) -> Frequency {
+        self.frequency
+    }
+
+    /// Returns an iterator over the UTC second marks of the occurrences of the rule, from a start
+    /// second [`Mark`] included, in the wall clock time of a [`Zone`].
+    ///
+    /// The start is the first occurrence when it matches the rule. Dates and times that do not
+    /// exist, like February 30th or a second 60 without a leap second, are skipped. The iterator
+    /// ends after `COUNT` occurrences, after `UNTIL`, or when no occurrence is left in the next
+    /// 400 years, or 400 times the interval when it is longer than a day.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::iso8601::parse_rfc3339_second;
+    /// use timelane::rrule::RecurrenceRule;
+    /// use timelane::zone::Utc;
+    /// let rule = RecurrenceRule::parse("FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=29").unwrap();
+    /// let start = parse_rfc3339_second("2000-01-01T00:00:00Z").unwrap();
+    /// let mut leap_days = rule.occurrences(start, Utc);
+    /// assert_eq!(leap_days.nth(1), Some(parse_rfc3339_second("2004-02-29T00:00:00Z").unwrap()));
+    /// let never = RecurrenceRule::parse("FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30").unwrap();
+    /// assert_eq!(never.occurrences(start, Utc).next(), None);
+    /// ```
+    pub fn occurrences<Z: Zone>(&self, start: Mark, zone: Z) -> Occurrences<'_, Z> {
+        let (utc_minute, second) = second_to_minute_and_second(start);
+        let local_minute = zone.local_minute(utc_minute);
+        let (day, hour, minute) = minute_to_day_hour_minute(local_minute);
+        let (_, month, month_day) = day_to_year_month_day(day);
+        let mut expansion = Expansion {
+            rule: self,
+            by_second: self.by_second.clone(),
+            by_minute: self.by_minute.clone(),
+            by_hour: self.by_hour.clone(),
+            by_day: self.by_day.clone(),
+            by_month_day: self.by_month_day.clone(),
+            by_month: self.by_month.clone(),
+        };
+        // Missing parts default to the start, RFC 5545 section 3.3.10
+        let second = second.min(59);
+        for (part, value, frequency) in [
+            (&mut expansion.by_second, second, Frequency::Secondly),
+            (&mut expansion.by_minute, minute, Frequency::Minutely),
+            (&mut expansion.by_hour, hour, Frequency::Hourly),
+        ] {
+            if part.is_empty() && self.frequency > frequency {
+                part.push(value);
+            }
+        }
+        if self.by_day.is_empty()
+            && self.by_month_day.is_empty()
+            && self.by_year_day.is_empty()
+            && self.by_week_no.is_empty()
+        {
+            match self.frequency {
+                Frequency::Weekly => expansion.by_day.push(WeekdayNumber {
+                    ordinal: 0,
+                    weekday: Weekday::of_day(day),
+                }),
+                Frequency::Monthly => expansion.by_month_day.push(month_day),
+                Frequency::Yearly => {
+                    if expansion.by_month.is_empty() {
+                        expansion.by_month.push(month);
+                    }
+                    expansion.by_month_day.push(month_day);
+                }
+                _ => {}
+            }
+        }
+        let period = match self.frequency {
+            Frequency::Yearly => day_to_year_month_day(day).0,
+            Frequency::Monthly => crate::day_to_month(day),
+            Frequency::Weekly => day - self.week_start.days_until(Weekday::of_day(day)),
+            Frequency::Daily => day,
+            Frequency::Hourly => crate::minute_to_hour(local_minute),
+            Frequency::Minutely => local_minute,
+            Frequency::Secondly => local_minute * 60 + second,
+        };
+        let until = self.until.map(|until| match until {
+            Until::Utc(second) => second,
+            Until::Local(local_minute, second) => {
+                minute_to_second(zone.utc_minute(local_minute)) + second
+            }
+            Until::Date(day) => {
+                minute_to_second(zone.utc_minute(hour_to_minute(day_to_hour(day + 1)))) - 1
+            }
+        });
+        Occurrences {
+            expansion,
+            zone,
+            start,
+            until,
+            remaining: self.count,
+            excluded: BTreeSet::new(),
+            period,
+            productive_day: day,
+            buffer: Vec::new(),
+            index: 0,
+            last: None,
+            finished: false,
+        }
+    }
+}
+
+impl FromStr for RecurrenceRule {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        Self::parse(text)
+    }
+}
+
+/// The rule parts of RFC 5545, indexed by the constants below.
+const PART_NAMES: [&str; 14] = [
+    "FREQ",
+    "INTERVAL",
+    "COUNT",
+    "UNTIL",
+    "BYSECOND",
+    "BYMINUTE",
+    "BYHOUR",
+    "BYDAY",
+    "BYMONTHDAY",
+    "BYYEARDAY",
+    "BYWEEKNO",
+    "BYMONTH",
+    "BYSETPOS",
+    "WKST",
+];
+const FREQ: usize = 0;
+const INTERVAL: usize = 1;
+const COUNT: usize = 2;
+const UNTIL: usize = 3;
+const BYSECOND: usize = 4;
+const BYMINUTE: usize = 5;
+const BYHOUR: usize = 6;
+const BYDAY: usize = 7;
+const BYMONTHDAY: usize = 8;
+const BYYEARDAY: usize = 9;
+const BYWEEKNO: usize = 10;
+const BYMONTH: usize = 11;
+const BYSETPOS: usize = 12;
+
+const FREQUENCY_NAMES: [&str; 7] = [
+    "SECONDLY", "MINUTELY", "HOURLY", "DAILY", "WEEKLY", "MONTHLY", "YEARLY",
+];
+const FREQUENCIES: [Frequency; 7] = [
+    Frequency::Secondly,
+    Frequency::Minutely,
+    Frequency::Hourly,
+    Frequency::Daily,
+    Frequency::Weekly,
+    Frequency::Monthly,
+    Frequency::Yearly,
+];
+const WEEKDAY_CODES: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+/// Returns the position of the first `byte` at or after a position, or the end of the input.
+fn find(bytes: &[u8], position: usize, byte: u8) -> usize {
+    bytes[position..]
+        .iter()
+        .position(|&other| other == byte)
+        .map_or(bytes.len(), |offset| position + offset)
+}
+
+/// Returns the error of an unexpected character at a position, or of an unexpected end.
+fn unexpected(bytes: &[u8], position: usize) -> ParseError {
+    if position < bytes.len() {
+        ParseError::new(position, ParseErrorKind::UnexpectedCharacter)
+    } else {
+        ParseError::new(position, ParseErrorKind::UnexpectedEnd)
+    }
+}
+
+/// The value of a rule part, or of an item of a list, from `start` to `end` excluded.
+#[derive(Clone, Copy)]
+struct Value<'a> {
+    bytes: &'a [u8],
+    start: usize,
+    end: usize,
+}
+
+impl Value<'_> {
+    /// Returns the error of unexpected characters after a position.
+    fn check_end(&self, position: usize) -> Result<(), ParseError> {
+        if position < self.end {
+            Err(unexpected(self.bytes, position))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Parses a list of items separated by commas.
+    fn list<T>(&self, item: impl Fn(&Self) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+        let mut items = Vec::new();
+        let mut start = self.start;
+        loop {
+            let end = find(&self.bytes[..self.end], start, b',');
+            items.push(item(&Value {
+                bytes: self.bytes,
+                start,
+                end,
+            })?);
+            if end == self.end {
+                return Ok(items);
+            }
+            start = end + 1;
+        }
+    }
+
+    /// Parses one of `names`, in any case, returns its index.
+    fn name(&self, names: &[&str]) -> Result<usize, ParseError> {
+        let value = &self.bytes[self.start..self.end];
+        names
+            .iter()
+            .position(|name| value.eq_ignore_ascii_case(name.as_bytes()))
+            .ok_or_else(|| unexpected(self.bytes, self.start))
+    }
+
+    fn frequency(&self) -> Result<Frequency, ParseError> {
+        Ok(FREQUENCIES[self.name(&FREQUENCY_NAMES)?])
+    }
+
+    fn weekday(&self) -> Result<Weekday, ParseError> {
+        Ok(Weekday::ALL[self.name(&WEEKDAY_CODES)?])
+    }
+
+    /// Parses an unsigned integer from `min` to `max`.
+    fn integer(&self, min: Mark, max: Mark, kind: ParseErrorKind) -> Result<Mark, ParseError> {
+        let count = count_digits(&self.bytes[..self.end], self.start);
+        if count == 0 {
+            return Err(unexpected(self.bytes, self.start));
+        }
+        let (value, next) = digits(self.bytes, self.start, count)?;
+        self.check_end(next)?;
+        check_range(value, min, max, self.start, kind)
+    }
+
+    /// Parses an integer with an optional sign, from 1 to `max` or from `-max` to -1.
+    fn signed(&self, max: Mark, kind: ParseErrorKind) -> Result<Mark, ParseError> {
+        let (sign, digits) = self.sign();
+        Ok(sign * digits.integer(1, max, kind)?)
+    }
+
+    /// Splits an optional sign from the rest of the value.
+    fn sign(&self) -> (Mark, Self) {
+        let rest = Value {
+            start: self.start + 1,
+            ..*self
+        };
+        match self.bytes.get(self.start).filter(|_| self.start < self.end) {
+            Some(b'-') => (-1, rest),
+            Some(b'+') => (1, rest),
+            _ => (1, *self),
+        }
+    }
+
+    /// Parses a day of week with an optional ordinal, like `MO`, `2TU` or `-1FR`.
+    fn weekday_number(&self) -> Result<WeekdayNumber, ParseError> {
+        let weekday = Value {
+            start: self.end.saturating_sub(2).max(self.start),
+            ..*self
+        }
+        .weekday()?;
+        let ordinal = Value {
+            end: self.end - 2,
+            ..*self
+        };
+        let ordinal = if ordinal.start == ordinal.end {
+            0
+        } else {
+            ordinal.signed(53, ParseErrorKind::InvalidWeekday)?
+        };
+        Ok(WeekdayNumber { ordinal, weekday })
+    }
+
+    /// Parses a date, a local date and time, or a UTC date and time.
+    fn until(&self) -> Result<Until, ParseError> {
+        let bytes = &self.bytes[..self.end];
+        let (year, next) = digits(bytes, self.start, 4)?;
+        let year = check_year(year, self.start)?;
+        let (month, next) = digits(bytes, next, 2)?;
+        let month = check_range(month, 1, 12, next - 2, ParseErrorKind::InvalidMonth)?;
+        let (day, next) = digits(bytes, next, 2)?;
+        let day = check_range(
+            day,
+            1,
+            days_in_month(year, month),
+            next - 2,
+            ParseErrorKind::InvalidDay,
+        )?;
+        let day = year_month_day_to_day(year, month, day);
+        if next == self.end {
+            return Ok(Until::Date(day));
+        }
+        if !bytes[next].eq_ignore_ascii_case(&b'T') {
+            return Err(unexpected(bytes, next));
+        }
+        let (hour, next) = digits(bytes, next + 1, 2)?;
+        let hour = check_range(hour, 0, 23, next - 2, ParseErrorKind::InvalidHour)?;
+        let (minute, next) = digits(bytes, next, 2)?;
+        let minute = check_range(minute, 0, 59, next - 2, ParseErrorKind::InvalidMinute)?;
+        let (second, next) = digits(bytes, next, 2)?;
+        let second = check_range(second, 0, 60, next - 2, ParseErrorKind::InvalidSecond)?;
+        let local_minute = hour_to_minute(day_to_hour(day) + hour) + minute;
+        if next < self.end && bytes[next].eq_ignore_ascii_case(&b'Z') {
+            self.check_end(next + 1)?;
+            if second == 60 && !minute_has_leap_second(local_minute) {
+                return Err(ParseError::new(next - 2, ParseErrorKind::InvalidLeapSecond));
+            }
+            return Ok(Until::Utc(minute_to_second(local_minute) + second));
+        }
+        self.check_end(next)?;
+        Ok(Until::Local(local_minute, second))
+    }
+}
+
+/// The rule parts of a [`RecurrenceRule`], with the defaults taken from the start.
+#[derive(Clone, Debug)]
+struct Expansion<'a> {
+    rule: &'a RecurrenceRule,
+    by_second: Vec<Mark>,
+    by_minute: Vec<Mark>,
+    by_hour: Vec<Mark>,
+    by_day: Vec<WeekdayNumber>,
+    by_month_day: Vec<Mark>,
+    by_month: Vec<Mark>,
+}
+
+impl Expansion<'_> {
+    /// Returns the days of a period, from the first day included to the last day excluded.
+    fn period_days(&self, period: Mark) -> (Mark, Mark) {
+        match self.rule.frequency {
+            Frequency::Yearly => (
+                month_to_day(year_to_month(period)),
+                month_to_day(year_to_month(period + 1)),
+            ),
+            Frequency::Monthly => (month_to_day(period), month_to_day(period + 1)),
+            Frequency::Weekly => (period, period + 7),
+            Frequency::Daily => (period, period + 1),
+            Frequency::Hourly => {
+                let day = crate::hour_to_day(period);
+                (day, day + 1)
+            }
+            Frequency::Minutely => {
+                let day = crate::hour_to_day(crate::minute_to_hour(period));
+                (day, day + 1)
+            }
+            Frequency::Secondly => {
+                let day = crate::hour_to_day(crate::minute_to_hour(period.div_euclid(60)));
+                (day, day + 1)
+            }
+        }
+    }
+
+    /// Returns the last period whose days and times convert to second marks without overflow,
+    /// a week before the end of the lane to leave room for the offsets of the zones.
+    fn last_period(&self) -> Mark {
+        let day = crate::second_to_day(Mark::MAX - 1) - 7;
+        match self.rule.frequency {
+            Frequency::Yearly => day_to_year_month_day(day).0 - 1,
+            Frequency::Monthly => crate::day_to_month(day) - 1,
+            Frequency::Weekly | Frequency::Daily => day - 7,
+            Frequency::Hourly => day_to_hour(day),
+            Frequency::Minutely => hour_to_minute(day_to_hour(day)),
+            Frequency::Secondly => hour_to_minute(day_to_hour(day)) * 60,
+        }
+    }
+
+    /// Returns `true` if a day matches the day rule parts.
+    fn matches_day(&self, day: Mark) -> bool {
+        let rule = self.rule;
+        let (year, month, month_day) = day_to_year_month_day(day);
+        if !self.by_month.is_empty() && !self.by_month.contains(&month) {
+            return false;
+        }
+        if !rule.by_week_no.is_empty() {
+            let (week, weeks) = week_number(day, rule.week_start);
+            if !rule
+                .by_week_no
+                .iter()
+                .any(|&number| number == week || number == week - weeks - 1)
+            {
+                return false;
+            }
+        }
+        let year_start = month_to_day(year_to_month(year));
+        if !rule.by_year_day.is_empty() {
+            let year_day = day - year_start + 1;
+            let days = days_in_year(year);
+            if !rule
+                .by_year_day
+                .iter()
+                .any(|&number| number == year_day || number == year_day - days - 1)
+            {
+                return false;
+            }
+        }
+        if !self.by_month_day.is_empty() {
+            let days = days_in_month(year, month);
+            if !self
+                .by_month_day
+                .iter()
+                .any(|&number| number == month_day || number == month_day - days - 1)
+            {
+                return false;
+            }
+        }
+        if !self.by_day.is_empty() {
+            // Ordinals count in the month for monthly rules or yearly rules limited to some months
+            let (scope_start, scope_end) = if rule.frequency == Frequency::Monthly
+                || (rule.frequency == Frequency::Yearly && !self.by_month.is_empty())
+            {
+                let month_start = day - month_day + 1;
+                (month_start, month_start + days_in_month(year, month))
+            } else {
+                (year_start, year_start + days_in_year(year))
+            };
+            let weekday = Weekday::of_day(day);
+            let ordinal = (day - scope_start) / 7 + 1;
+            let ordinal_from_end = -((scope_end - 1 - day) / 7 + 1);
+            if !self.by_day.iter().any(|number| {
+                number.weekday == weekday
+                    && (number.ordinal == 0
+                        || number.ordinal == ordinal
+                        || number.ordinal == ordinal_from_end)
+            }) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Returns the values of a time field of a period: the field of the period itself, if it
+    /// matches the rule part, for frequencies at least as fine as the field, or all the values of
+    /// the rule part otherwise.
+    fn field_values(&self, values: &[Mark], field: Frequency, period_value: Mark) -> Vec<Mark> {
+        if self.rule.frequency <= field {
+            if values.is_empty() || values.contains(&period_value) {
+                alloc::vec![period_value]
+            } else {
+                Vec::new()
+            }
+        } else {
+            let mut values = values.to_vec();
+            values.sort_unstable();
+            values.dedup();
+            values
+        }
+    }
+
+    /// Returns the local times of a period, as local minute marks and seconds of minute, in
+    /// order.
+    fn period_times(&self, period: Mark) -> Vec<(Mark, Mark)> {
+        let (first_day, end_day) = self.period_days(period);
+        // The local minute and second of the period, for the frequencies finer than a day
+        let (local_minute, second) = match self.rule.frequency {
+            Frequency::Hourly => (hour_to_minute(period), 0),
+            Frequency::Minutely => (period, 0),
+            Frequency::Secondly => (period.div_euclid(60), period.rem_euclid(60)),
+            _ => (0, 0),
+        };
+        let (_, hour, minute) = minute_to_day_hour_minute(local_minute);
+        let hours = self.field_values(&self.by_hour, Frequency::Hourly, hour);
+        let minutes = self.field_values(&self.by_minute, Frequency::Minutely, minute);
+        let seconds = self.field_values(&self.by_second, Frequency::Secondly, second);
+        let mut times = Vec::new();
+        for day in first_day..end_day {
+            if !self.matches_day(day) {
+                continue;
+            }
+            for &hour in &hours {
+                for &minute in &minutes {
+                    for &second in &seconds {
+                        times.push((hour_to_minute(day_to_hour(day) + hour) + minute, second));
+                    }
+                }
+            }
+        }
+        if self.rule.by_set_pos.is_empty() {
+            return times;
+        }
+        let mut selected: Vec<_> = self
+            .rule
+            .by_set_pos
+            .iter()
+            .filter_map(|&position| {
+                let index = if position > 0 {
+                    position - 1
+                } else {
+                    times.len() as Mark + position
+                };
+                usize::try_from(index)
+                    .ok()
+                    .and_then(|index| times.get(index).copied())
+            })
+            .collect();
+        selected.sort_unstable();
+        selected.dedup();
+        selected
+    }
+
+    /// Returns the first period after `period` that can match, skipping whole days, hours or
+    /// minutes that cannot for the frequencies finer than a day.
+    fn next_period(&self, period: Mark) -> Option<Mark> {
+        // Weekly periods are the days starting each week
+        let interval = match self.rule.frequency {
+            Frequency::Weekly => self.rule.interval.checked_mul(7)?,
+            _ => self.rule.interval,
+        };
+        let next = period.checked_add(interval)?;
+        // The first period of the next day, hour or minute, when this one is excluded
+        let target = match self.rule.frequency {
+            Frequency::Hourly | Frequency::Minutely | Frequency::Secondly => {
+                let per_minute = if self.rule.frequency == Frequency::Secondly {
+                    60
+                } else {
+                    1
+                };
+                let per_hour = if self.rule.frequency == Frequency::Hourly {
+                    1
+                } else {
+                    60 * per_minute
+                };
+                let per_day = 24 * per_hour;
+                let (day, _) = self.period_days(period);
+                let day_start = day_to_hour(day) * per_hour;
+                let hour_start = period - period.rem_euclid(per_hour);
+                let minute_start = period - period.rem_euclid(per_minute);
+                let local_minute = match self.rule.frequency {
+                    Frequency::Hourly => hour_to_minute(period),
+                    _ => period.div_euclid(per_minute),
+                };
+                let (_, hour, minute) = minute_to_day_hour_minute(local_minute);
+                if !self.matches_day(day) {
+                    day_start + per_day
+                } else if self.rule.frequency < Frequency::Hourly
+                    && !self.by_hour.is_empty()
+                    && !self.by_hour.contains(&hour)
+                {
+                    hour_start + per_hour
+                } else if self.rule.frequency < Frequency::Minutely
+                    && !self.by_minute.is_empty()
+                    && !self.by_minute.contains(&minute)
+                {
+                    minute_start + per_minute
+                } else {
+                    next
+                }
+            }
+            _ => next,
+        };
+        if target <= next {
+            return Some(next);
+        }
+        let steps = (target - period + interval - 1) / interval;
+        period.checked_add(steps.checked_mul(interval)?)
+    }
+}
+
+/// Returns the week number of a day, and the number of weeks in its week-numbering year, where
+/// weeks start on `week_start` and the first week of a year has at least four days in that year.
+fn week_number(day: Mark, week_start: Weekday) -> (Mark, Mark) {
+    let first_week_start = |year: Mark| {
+        let january_4th = year_month_day_to_day(year, 1, 4);
+        january_4th - week_start.days_until(Weekday::of_day(january_4th))
+    };
+    let (mut year, _, _) = day_to_year_month_day(day);
+    if day < first_week_start(year) {
+        year -= 1;
+    } else if day >= first_week_start(year + 1) {
+        year += 1;
+    }
+    let start = first_week_start(year);
+    (
+        (day - start) / 7 + 1,
+        (first_week_start(year + 1) - start) / 7,
+    )
+}
+
+/// An iterator over the UTC second marks of the occurrences of a [`RecurrenceRule`], see
+/// [`RecurrenceRule::occurrences`].
+#[derive(Clone, Debug)]
+pub struct Occurrences<'a, Z> {
+    expansion: Expansion<'a>,
+    zone: Z,
+    start: Mark,
+    /// The last second [`Mark`] included.
+    until: Option<Mark>,
+    /// The number of occurrences left, for rules with a `COUNT`.
+    remaining: Option<Mark>,
+    excluded: BTreeSet<Mark>,
+    /// The current period: a year, a month, the day starting a week, a day, or a local hour,
+    /// minute or second, depending on the frequency.
+    period: Mark,
+    /// The first day of the last period with occurrences.
+    productive_day: Mark,
+    /// The UTC second marks of the occurrences of the current period.
+    buffer: Vec<Mark>,
+    index: usize,
+    /// The last occurrence returned, occurrences are strictly increasing.
+    last: Option<Mark>,
+    finished: bool,
+}
+
+impl<Z: Zone> Occurrences<'_, Z> {
+    /// Removes some UTC second marks from the occurrences, like the `EXDATE` of an event.
+    ///
+    /// Excluded occurrences still count towards the `COUNT` of the rule.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::rrule::RecurrenceRule;
+    /// use timelane::zone::Utc;
+    /// let rule = RecurrenceRule::parse("FREQ=DAILY;COUNT=3").unwrap();
+    /// let days: Vec<_> = rule.occurrences(0, Utc).excluding([86_400]).collect();
+    /// assert_eq!(days, [0, 172_800]);
+    /// ```
+    pub fn excluding<I: IntoIterator<Item = Mark>>(mut self, seconds: I) -> Self {
+        self.excluded.extend(seconds);
+        self
+    }
+
+    /// Fills the buffer with the occurrences of the current period, and moves to the next one.
+    fn fill(&mut self) {
+        if self.period > self.expansion.last_period() {
+            self.finished = true;
+            return;
+        }
+        let (first_day, _) = self.expansion.period_days(self.period);
+        // The periods repeat on the same days of the calendar after at most as many cycles as
+        // there are days in the interval
+        let interval = self.expansion.rule.interval;
+        let interval_days = match self.expansion.rule.frequency {
+            Frequency::Hourly => interval / 24,
+            Frequency::Minutely => interval / 1_440,
+            Frequency::Secondly => interval / 86_400,
+            _ => interval,
+        };
+        if first_day - self.productive_day > CYCLE_DAYS.saturating_mul(interval_days.max(1)) {
+            self.finished = true;
+            return;
+        }
+        self.buffer.clear();
+        self.index = 0;
+        for (local_minute, second) in self.expansion.period_times(self.period) {
+            let utc_minute = self.zone.utc_minute(local_minute);
+            if second == 60 && !minute_has_leap_second(utc_minute) {
+                continue;
+            }
+            self.buffer.push(minute_to_second(utc_minute) + second);
+        }
+        if !self.buffer.is_empty() {
+            self.productive_day = first_day;
+        }
+        match self.expansion.next_period(self.period) {
+            Some(period) => self.period = period,
+            None => self.finished = true,
+        }
+    }
+}
+
+impl<Z: Zone> Iterator for Occurrences<'_, Z> {
+    type Item = Mark;
+
+    fn next(&mut self) -> Option<Mark> {
+        loop {
+            if self.index == self.buffer.len() {
+                if self.finished {
+                    return None;
+                }
+                self.fill();
+                continue;
+            }
+            let second = self.buffer[self.index];
+            self.index += 1;
+            if second < self.start || self.last.is_some_and(|last| second <= last) {
+                continue;
+            }
+            if self.until.is_some_and(|until| second > until) || self.remaining == Some(0) {
+                self.finished = true;
+                self.buffer.clear();
+                self.index = 0;
+                return None;
+            }
+            if let Some(remaining) = &mut self.remaining {
+                *remaining -= 1;
+            }
+            self.last = Some(second);
+            if !self.excluded.contains(&second) {
+                return Some(second);
+            }
+        }
+    }
+}
+
+impl<Z: Zone> core::iter::FusedIterator for Occurrences<'_, Z> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iso8601::{format_rfc3339, parse_rfc3339_second};
+    use crate::lane::Second;
+    use crate::zone::Utc;
+
+    /// Returns the first occurrences of a rule from a start, formatted in RFC 3339.
+    fn expand(rule: &str, start: &str, count: usize) -> Vec<String> {
+        expand_in(rule, start, count, Utc)
+    }
+
+    fn expand_in<Z: Zone>(rule: &str, start: &str, count: usize, zone: Z) -> Vec<String> {
+        let rule: RecurrenceRule = rule.parse().unwrap();
+        rule.occurrences(parse_rfc3339_second(start).unwrap(), zone)
+            .take(count)
+            .map(|second| format_rfc3339::<Second>(second).to_string())
+            .collect()
+    }
+
+    /// Returns the dates of some RFC 3339 strings.
+    fn dates(times: &[String]) -> Vec<&str> {
+        times.iter().map(|time| &time[..10]).collect()
+    }
+
+    #[test]
+    fn daily_rules_follow_rfc_5545_examples() {
+        let days = expand("FREQ=DAILY;COUNT=10", "1997-09-02T09:00:00Z", 20);
+        assert_eq!(days.len(), 10);
+        assert_eq!(days[9], "1997-09-11T09:00:00Z");
+        let days = expand("FREQ=DAILY;INTERVAL=10;COUNT=5", "1997-09-02T09:00:00Z", 20);
+        assert_eq!(
+            dates(&days),
+            [
+                "1997-09-02",
+                "1997-09-12",
+                "1997-09-22",
+                "1997-10-02",
+                "1997-10-12"
+            ]
+        );
+        let januaries = expand(
+            "FREQ=YEARLY;UNTIL=20000131T140000Z;BYMONTH=1;BYDAY=SU,MO,TU,WE,TH,FR,SA",
+            "1998-01-01T09:00:00Z",
+            200,
+        );
+        assert_eq!(januaries.len(), 93);
+        assert_eq!(januaries[92], "2000-01-31T09:00:00Z");
+    }
+
+    #[test]
+    fn weekly_rules_start_weeks_on_wkst() {
+        let days = expand(
+            "FREQ=WEEKLY;INTERVAL=2;COUNT=4;BYDAY=TU,SU;WKST=MO",
+            "1997-08-05T09:00:00Z",
+            10,
+        );
+        assert_eq!(
+            dates(&days),
+            ["1997-08-05", "1997-08-10", "1997-08-19", "1997-08-24"]
+        );
+        let days = expand(
+            "FREQ=WEEKLY;INTERVAL=2;COUNT=4;BYDAY=TU,SU;WKST=SU",
+            "1997-08-05T09:00:00Z",
+            10,
+        );
+        assert_eq!(
+            dates(&days),
+            ["1997-08-05", "1997-08-17", "1997-08-19", "1997-08-31"]
+        );
+    }
+
+    #[test]
+    fn monthly_rules_follow_rfc_5545_examples() {
+        let days = expand(
+            "FREQ=MONTHLY;COUNT=10;BYDAY=1FR",
+            "1997-09-05T09:00:00Z",
+            20,
+        );
+        assert_eq!(days.len(), 10);
+        assert_eq!(
+            dates(&days[..4]),
+            ["1997-09-05", "1997-10-03", "1997-11-07", "1997-12-05"]
+        );
+        assert_eq!(days[9], "1998-06-05T09:00:00Z");
+        let days = expand("FREQ=MONTHLY;BYMONTHDAY=-3", "1997-09-28T09:00:00Z", 6);
+        assert_eq!(
+            dates(&days),
+            [
+                "1997-09-28",
+                "1997-10-29",
+                "1997-11-28",
+                "1997-12-29",
+                "1998-01-29",
+                "1998-02-26"
+            ]
+        );
+        let days = expand(
+            "FREQ=MONTHLY;BYMONTHDAY=15,30;COUNT=5",
+            "2007-01-15T09:00:00Z",
+            10,
+        );
+        assert_eq!(
+            dates(&days),
+            [
+                "2007-01-15",
+                "2007-01-30",
+                "2007-02-15",
+                "2007-03-15",
+                "2007-03-30"
+            ]
+        );
+        let rule: RecurrenceRule = "FREQ=MONTHLY;BYDAY=FR;BYMONTHDAY=13".parse().unwrap();
+        let start = parse_rfc3339_second("1997-09-02T09:00:00Z").unwrap();
+        let days: Vec<_> = rule
+            .occurrences(start, Utc)
+            .excluding([start])
+            .take(5)
+            .map(|second| format_rfc3339::<Second>(second).to_string())
+            .collect();
+        assert_eq!(
+            dates(&days),
+            [
+                "1998-02-13",
+                "1998-03-13",
+                "1998-11-13",
+                "1999-08-13",
+                "2000-10-13"
+            ]
+        );
+    }
+
+    #[test]
+    fn set_positions_select_within_each_period() {
+        let days = expand(
+            "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1",
+            "1997-09-29T09:00:00Z",
+            5,
+        );
+        assert_eq!(
+            dates(&days),
+            [
+                "1997-09-30",
+                "1997-10-31",
+                "1997-11-28",
+                "1997-12-31",
+                "1998-01-30"
+            ]
+        );
+        let days = expand(
+            "FREQ=MONTHLY;COUNT=3;BYDAY=TU,WE,TH;BYSETPOS=3",
+            "1997-09-04T09:00:00Z",
+            10,
+        );
+        assert_eq!(dates(&days), ["1997-09-04", "1997-10-07", "1997-11-06"]);
+    }
+
+    #[test]
+    fn yearly_rules_follow_rfc_5545_examples() {
+        let days = expand(
+            "FREQ=YEARLY;BYWEEKNO=20;BYDAY=MO",
+            "1997-05-12T09:00:00Z",
+            3,
+        );
+        assert_eq!(dates(&days), ["1997-05-12", "1998-05-11", "1999-05-17"]);
+        let days = expand("FREQ=YEARLY;BYDAY=20MO", "1997-05-19T09:00:00Z", 3);
+        assert_eq!(dates(&days), ["1997-05-19", "1998-05-18", "1999-05-17"]);
+        let days = expand(
+            "FREQ=YEARLY;INTERVAL=3;COUNT=4;BYYEARDAY=1,100,200",
+            "1997-01-01T09:00:00Z",
+            10,
+        );
+        assert_eq!(
+            dates(&days),
+            ["1997-01-01", "1997-04-10", "1997-07-19", "2000-01-01"]
+        );
+        let days = expand(
+            "FREQ=YEARLY;BYWEEKNO=-1;BYDAY=SU",
+            "2020-01-01T00:00:00Z",
+            3,
+        );
+        assert_eq!(dates(&days), ["2021-01-03", "2022-01-02", "2023-01-01"]);
+    }
+
+    #[test]
+    fn sub_daily_rules_skip_filtered_days_and_hours() {
+        let daily = expand(
+            "FREQ=DAILY;BYHOUR=9,10,11,12,13,14,15,16;BYMINUTE=0,20,40",
+            "1997-09-02T09:00:00Z",
+            50,
+        );
+        let minutely = expand(
+            "FREQ=MINUTELY;INTERVAL=20;BYHOUR=9,10,11,12,13,14,15,16",
+            "1997-09-02T09:00:00Z",
+            50,
+        );
+        assert_eq!(daily, minutely);
+        assert_eq!(daily[23], "1997-09-02T16:40:00Z");
+        assert_eq!(daily[24], "1997-09-03T09:00:00Z");
+        let hours = expand(
+            "FREQ=HOURLY;INTERVAL=3;UNTIL=19970902T170000Z",
+            "1997-09-02T09:00:00Z",
+            10,
+        );
+        assert_eq!(
+            hours,
+            [
+                "1997-09-02T09:00:00Z",
+                "1997-09-02T12:00:00Z",
+                "1997-09-02T15:00:00Z"
+            ]
+        );
+        let seconds = expand(
+            "FREQ=SECONDLY;INTERVAL=7;BYDAY=SA;BYMINUTE=0",
+            "2000-01-01T23:59:50Z",
+            3,
+        );
+        assert_eq!(
+            seconds,
+            [
+                "2000-01-08T00:00:03Z",
+                "2000-01-08T00:00:10Z",
+                "2000-01-08T00:00:17Z"
+            ]
+        );
+    }
+
+    #[test]
+    fn sub_daily_rules_that_never_match_end() {
+        for rule in [
+            "FREQ=HOURLY;INTERVAL=24;BYMONTH=2;BYMONTHDAY=30",
+            "FREQ=MINUTELY;INTERVAL=1440;BYMONTH=2;BYMONTHDAY=30",
+            "FREQ=SECONDLY;INTERVAL=86400;BYMONTH=2;BYMONTHDAY=30",
+            "FREQ=SECONDLY;INTERVAL=86401;BYMONTH=2;BYMONTHDAY=30",
+        ] {
+            assert!(
+                expand(rule, "2000-01-01T00:00:00Z", 1).is_empty(),
+                "{}",
+                rule
+            );
+        }
+    }
+
+    #[test]
+    fn huge_intervals_end_at_the_end_of_the_lane() {
+        for rule in [
+            "FREQ=DAILY;INTERVAL=9223372036854775807",
+            "FREQ=WEEKLY;INTERVAL=9223372036854775807",
+            "FREQ=SECONDLY;INTERVAL=9223372036854775807",
+            "FREQ=DAILY;INTERVAL=9223372036854775807;COUNT=5",
+            "FREQ=DAILY;INTERVAL=1000000000000000000",
+            "FREQ=MONTHLY;INTERVAL=400000000000000000",
+            "FREQ=YEARLY;INTERVAL=1000000000000000",
+        ] {
+            assert_eq!(
+                expand(rule, "2000-01-01T00:00:00Z", 2),
+                ["2000-01-01T00:00:00Z"],
+                "{}",
+                rule
+            );
+        }
+        assert_eq!(
+            expand(
+                "FREQ=YEARLY;INTERVAL=200000000000",
+                "2000-01-01T00:00:00Z",
+                3
+            )
+            .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn second_60_only_happens_on_leap_seconds() {
+        let rule = "FREQ=YEARLY;BYMONTH=6,12;BYMONTHDAY=-1;BYHOUR=23;BYMINUTE=59;BYSECOND=60";
+        let leaps = expand(rule, "2010-01-01T00:00:00Z", 10);
+        assert_eq!(
+            leaps,
+            [
+                "2012-06-30T23:59:60Z",
+                "2015-06-30T23:59:60Z",
+                "2016-12-31T23:59:60Z"
+            ]
+        );
+    }
+
+    #[test]
+    fn local_times_stay_on_the_wall_clock() {
+        /// Central European Time in 2024, on summer time from March 31st to October 27th.
+        struct Europe2024;
+
+        impl Zone for Europe2024 {
+            fn offset(&self, utc_minute: Mark) -> Mark {
+                let summer = parse_rfc3339_second("2024-03-31T01:00:00Z").unwrap() / 60
+                    ..parse_rfc3339_second("2024-10-27T01:00:00Z").unwrap() / 60;
+                if summer.contains(&utc_minute) {
+                    120
+                } else {
+                    60
+                }
+            }
+        }
+
+        let spring = expand_in("FREQ=DAILY;COUNT=3", "2024-03-30T01:30:00Z", 5, Europe2024);
+        assert_eq!(
+            spring,
+            [
+                "2024-03-30T01:30:00Z",
+                "2024-03-31T01:30:00Z",
+                "2024-04-01T00:30:00Z"
+            ]
+        );
+        let autumn = expand_in("FREQ=HOURLY;COUNT=4", "2024-10-27T00:00:00Z", 5, Europe2024);
+        assert_eq!(
+            autumn,
+            [
+                "2024-10-27T00:00:00Z",
+                "2024-10-27T02:00:00Z",
+                "2024-10-27T03:00:00Z",
+                "2024-10-27T04:00:00Z"
+            ]
+        );
+        let until = expand_in(
+            "FREQ=DAILY;UNTIL=20240402",
+            "2024-03-30T22:00:00Z",
+            10,
+            Europe2024,
+        );
+        assert_eq!(until.len(), 4);
+        assert_eq!(until[3], "2024-04-02T21:00:00Z");
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        for (rule, position, kind) in [
+            ("COUNT=2", 7, ParseErrorKind::InvalidRule),
+            ("FREQ=DAILY;FREQ=DAILY", 11, ParseErrorKind::InvalidRule),
+            (
+                "FREQ=DAILY;COUNT=2;UNTIL=20000101",
+                19,
+                ParseErrorKind::InvalidRule,
+            ),
+            ("FREQ=DAILY;INTERVAL=0", 20, ParseErrorKind::InvalidRule),
+            ("FREQ=DAILY;BYSETPOS=1", 11, ParseErrorKind::InvalidRule),
+            ("FREQ=WEEKLY;BYMONTHDAY=1", 12, ParseErrorKind::InvalidRule),
+            ("FREQ=MONTHLY;BYYEARDAY=1", 13, ParseErrorKind::InvalidRule),
+            ("FREQ=WEEKLY;BYDAY=1MO", 12, ParseErrorKind::InvalidRule),
+            (
+                "FREQ=YEARLY;BYWEEKNO=1;BYDAY=1MO",
+                23,
+                ParseErrorKind::InvalidRule,
+            ),
+            ("FREQ=FORTNIGHTLY", 5, ParseErrorKind::UnexpectedCharacter),
+            (
+                "FREQ=DAILY;BYDAY=XX",
+                17,
+                ParseErrorKind::UnexpectedCharacter,
+            ),
+            ("FREQ=DAILY;BYMONTH=13", 19, ParseErrorKind::InvalidMonth),
+            ("FREQ=MONTHLY;BYMONTHDAY=0", 24, ParseErrorKind::InvalidDay),
+            ("FREQ=YEARLY;BYWEEKNO=-54", 22, ParseErrorKind::InvalidWeek),
+            ("FREQ=YEARLY;BYDAY=54MO", 18, ParseErrorKind::InvalidWeekday),
+            (
+                "FREQ=DAILY;BYMINUTE=1,,2",
+                22,
+                ParseErrorKind::UnexpectedCharacter,
+            ),
+            ("FREQ=DAILY;UNTIL=20230229", 23, ParseErrorKind::InvalidDay),
+            (
+                "FREQ=DAILY;UNTIL=20230101T000060Z",
+                30,
+                ParseErrorKind::InvalidLeapSecond,
+            ),
+            ("FREQ=DAILY;", 11, ParseErrorKind::UnexpectedEnd),
+            ("FREQ", 4, ParseErrorKind::UnexpectedEnd),
+        ] {
+            let error = RecurrenceRule::parse(rule).unwrap_err();
+            assert_eq!(
+                (error.position(), error.kind()),
+                (position, kind),
+                "{}",
+                rule
+            );
+        }
+        let rule = RecurrenceRule::parse("rrule:freq=yearly;bymonth=2;byday=-1su;wkst=su").unwrap();
+        assert_eq!(rule.week_start, Weekday::Sunday);
+        assert_eq!(
+            rule.by_day,
+            [WeekdayNumber {
+                ordinal: -1,
+                weekday: Weekday::Sunday
+            }]
+        );
+    }
+}
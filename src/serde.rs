@@ -0,0 +1,319 @@
+//! Serialize [`Mark`] of any lane with serde, with the `serde` feature.
+//!
+//! A [`Mark`] is a plain integer, which does not say which lane it belongs to. The helper modules
+//! of [`integer`] and [`rfc3339`], one for each lane, are used with `#[serde(with = "...")]` to
+//! choose how a field is serialized:
+//!  - [`integer`] serializes marks as integers,
+//!  - [`rfc3339`] serializes marks as RFC 3339 strings in UTC, see
+//!    [`crate::iso8601::format_rfc3339`], where leap seconds round-trip exactly as second `60`.
+//!
+//! # Examples
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use timelane::Mark;
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Event {
+//!     #[serde(with = "timelane::serde::rfc3339::second")]
+//!     at: Mark,
+//!     #[serde(with = "timelane::serde::integer::day")]
+//!     until: Mark,
+//! }
+//! let event = Event { at: 536_544_004, until: 1 };
+//! let json = serde_json::to_string(&event).unwrap();
+//! assert_eq!(json, r#"{"at":"2016-12-31T23:59:60Z","until":1}"#);
+//! assert_eq!(serde_json::from_str::<Event>(&json).unwrap(), event);
+//! ```
+use core::fmt;
+use core::marker::PhantomData;
+
+use ::serde::de::{self, Visitor};
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::iso8601::{format_rfc3339, parse_rfc3339_in};
+use crate::lane::{starts_in_lane, Lane, Year};
+use crate::Mark;
+
+/// Serializes a [`Mark`] of any lane as an integer.
+fn serialize_integer<S: Serializer>(mark: &Mark, serializer: S) -> Result<S::Ok, S::Error> {
+    mark.serialize(serializer)
+}
+
+/// Deserializes a [`Mark`] of any lane from an integer.
+fn deserialize_integer<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Mark, D::Error> {
+    Mark::deserialize(deserializer)
+}
+
+/// Serializes a [`Mark`] of a lane as an RFC 3339 string, which fails outside the years 0000 to
+/// 9999.
+fn serialize_rfc3339<L: Lane, S: Serializer>(
+    mark: &Mark,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    // The marks far from the years of RFC 3339 may not even convert to a second
+    let fits = starts_in_lane::<L>(*mark) && {
+        let (second, nanosecond) = L::to_second_and_nanosecond(*mark);
+        (0..=9999).contains(&Year::from_second_and_nanosecond(second, nanosecond))
+    };
+    if !fits {
+        return Err(::serde::ser::Error::custom(
+            "the year of the mark does not fit in RFC 3339",
+        ));
+    }
+    serializer.collect_str(&format_rfc3339::<L>(*mark))
+}
+
+/// Deserializes a rounded down [`Mark`] of a lane from an RFC 3339 string.
+fn deserialize_rfc3339<'de, L: Lane, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Mark, D::Error> {
+    deserializer.deserialize_str(Rfc3339Visitor::<L>(PhantomData))
+}
+
+struct Rfc3339Visitor<L>(PhantomData<L>);
+
+impl<L: Lane> Visitor<'_> for Rfc3339Visitor<L> {
+    type Value = Mark;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an RFC 3339 date and time")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Mark, E> {
+        parse_rfc3339_in::<L>(value).map_err(E::custom)
+    }
+}
+
+macro_rules! lane_modules {
+    // The integer helpers are the same for all lanes, the other ones take the lane
+    (@call integer, $function:ident, $lane:ident, $($argument:expr),*) => {
+        super::super::$function($($argument),*)
+    };
+    (@call $kind:ident, $function:ident, $lane:ident, $($argument:expr),*) => {
+        super::super::$function::<crate::lane::$lane, _>($($argument),*)
+    };
+    (
+        $kind:ident,
+        $serialize:ident,
+        $deserialize:ident,
+        $format:literal,
+        $($module:ident: $lane:ident),*
+    ) => {
+        $(
+            #[doc = concat!(
+                "Serialize a [`Mark`] of the [`crate::lane::", stringify!($lane), "`] lane ",
+                $format, ".",
+            )]
+            pub mod $module {
+                use crate::Mark;
+
+                #[doc = concat!("Serializes a [`Mark`] ", $format, ".")]
+                pub fn serialize<S: ::serde::Serializer>(
+                    mark: &Mark,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    lane_modules!(@call $kind, $serialize, $lane, mark, serializer)
+                }
+
+                #[doc = concat!("Deserializes a [`Mark`] ", $format, ".")]
+                pub fn deserialize<'de, D: ::serde::Deserializer<'de>>(
+                    deserializer: D,
+                ) -> Result<Mark, D::Error> {
+                    lane_modules!(@call $kind, $deserialize, $lane, deserializer)
+                }
+            }
+        )*
+    };
+}
+
+/// Helper modules serializing [`Mark`] as integers.
+///
+/// # Examples
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct Config {
+///     #[serde(with = "timelane::serde::integer::minute")]
+///     start: timelane::Mark,
+/// }
+/// let config: Config = serde_json::from_str(r#"{"start":-1}"#).unwrap();
+/// assert_eq!(config.start, -1);
+/// ```
+pub mod integer {
+    lane_modules!(
+        integer,
+        serialize_integer,
+        deserialize_integer,
+        "as an integer",
+        year: Year,
+        month: Month,
+        day: Day,
+        hour: Hour,
+        minute: Minute,
+        second: Second,
+        millisecond: Millisecond,
+        microsecond: Microsecond,
+        nanosecond: Nanosecond
+    );
+}
+
+/// Helper modules serializing [`Mark`] as RFC 3339 strings.
+///
+/// Serializing fails for years before 0000 or after 9999, which do not fit in RFC 3339.
+/// Deserializing rounds the date and time down to the lane, and fails if it does not fit in the
+/// lane.
+///
+/// # Examples
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize)]
+/// struct Config {
+///     #[serde(with = "timelane::serde::rfc3339::millisecond")]
+///     start: timelane::Mark,
+/// }
+/// let config = Config { start: -1 };
+/// let json = serde_json::to_string(&config).unwrap();
+/// assert_eq!(json, r#"{"start":"1999-12-31T23:59:59.999Z"}"#);
+/// ```
+pub mod rfc3339 {
+    lane_modules!(
+        rfc3339,
+        serialize_rfc3339,
+        deserialize_rfc3339,
+        "as an RFC 3339 string",
+        year: Year,
+        month: Month,
+        day: Day,
+        hour: Hour,
+        minute: Minute,
+        second: Second,
+        millisecond: Millisecond,
+        microsecond: Microsecond,
+        nanosecond: Nanosecond
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use ::serde::{Deserialize, Serialize};
+
+    use crate::iso8601::parse_rfc3339;
+    use crate::lane::{Lane, Month};
+    use crate::{minute_to_second, Mark, LEAP_SECONDS_MARKS};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Marks {
+        #[serde(with = "crate::serde::rfc3339::second")]
+        second: Mark,
+        #[serde(with = "crate::serde::rfc3339::nanosecond")]
+        nanosecond: Mark,
+        #[serde(with = "crate::serde::rfc3339::month")]
+        month: Mark,
+        #[serde(with = "crate::serde::integer::second")]
+        integer: Mark,
+    }
+
+    #[test]
+    fn leap_seconds_roundtrip_through_json_and_messagepack() {
+        for leap_minute in LEAP_SECONDS_MARKS {
+            let leap = minute_to_second(leap_minute) - 1;
+            let marks = Marks {
+                second: leap,
+                nanosecond: crate::subsecond::second_to_nanosecond(leap) + 1,
+                month: Month::from_second_and_nanosecond(leap + 1, 0),
+                integer: leap,
+            };
+            let json = serde_json::to_string(&marks).unwrap();
+            assert!(json.contains(":60Z"), "{}", json);
+            assert_eq!(serde_json::from_str::<Marks>(&json).unwrap(), marks);
+            let bytes = rmp_serde::to_vec(&marks).unwrap();
+            assert_eq!(rmp_serde::from_slice::<Marks>(&bytes).unwrap(), marks);
+        }
+    }
+
+    #[test]
+    fn invalid_strings_are_rejected() {
+        let json = r#"{"second":"2015-12-31T23:59:60Z","nanosecond":"2000-01-01T00:00:00Z","month":"2000-01-01T00:00:00Z","integer":0}"#;
+        let error = serde_json::from_str::<Marks>(json).unwrap_err();
+        assert!(error.to_string().contains("leap second"), "{}", error);
+        let json = r#"{"second":0,"nanosecond":"2000-01-01T00:00:00Z","month":"2000-01-01T00:00:00Z","integer":0}"#;
+        assert!(serde_json::from_str::<Marks>(json).is_err());
+    }
+
+    #[test]
+    fn years_out_of_rfc3339_are_rejected() {
+        let marks = Marks {
+            second: 0,
+            nanosecond: parse_rfc3339("2000-01-01T00:00:00Z").unwrap(),
+            month: crate::year_to_month(10_000),
+            integer: 0,
+        };
+        assert!(serde_json::to_string(&marks).is_err());
+    }
+
+    #[test]
+    fn ends_of_the_lanes_are_rejected_or_roundtrip() {
+        use crate::serde::{integer, rfc3339};
+        use serde_json::value::Serializer;
+        type Serialize = fn(&Mark, Serializer) -> Result<serde_json::Value, serde_json::Error>;
+        type Deserialize = fn(serde_json::Value) -> Result<Mark, serde_json::Error>;
+        let modules: [(Serialize, Serialize, Deserialize); 9] = [
+            (
+                rfc3339::year::serialize,
+                integer::year::serialize,
+                integer::year::deserialize,
+            ),
+            (
+                rfc3339::month::serialize,
+                integer::month::serialize,
+                integer::month::deserialize,
+            ),
+            (
+                rfc3339::day::serialize,
+                integer::day::serialize,
+                integer::day::deserialize,
+            ),
+            (
+                rfc3339::hour::serialize,
+                integer::hour::serialize,
+                integer::hour::deserialize,
+            ),
+            (
+                rfc3339::minute::serialize,
+                integer::minute::serialize,
+                integer::minute::deserialize,
+            ),
+            (
+                rfc3339::second::serialize,
+                integer::second::serialize,
+                integer::second::deserialize,
+            ),
+            (
+                rfc3339::millisecond::serialize,
+                integer::millisecond::serialize,
+                integer::millisecond::deserialize,
+            ),
+            (
+                rfc3339::microsecond::serialize,
+                integer::microsecond::serialize,
+                integer::microsecond::deserialize,
+            ),
+            (
+                rfc3339::nanosecond::serialize,
+                integer::nanosecond::serialize,
+                integer::nanosecond::deserialize,
+            ),
+        ];
+        for (rfc3339, integer, from_integer) in modules {
+            for mark in [Mark::MIN, Mark::MAX] {
+                let error = rfc3339(&mark, Serializer).unwrap_err();
+                assert!(error.to_string().contains("RFC 3339"), "{}", error);
+                assert_eq!(
+                    from_integer(integer(&mark, Serializer).unwrap()).unwrap(),
+                    mark
+                );
+            }
+            assert!(rfc3339(&0, Serializer).is_ok());
+        }
+        assert!(rfc3339::year::serialize(&1_000_000_000_000, Serializer).is_err());
+    }
+}
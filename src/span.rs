@@ -0,0 +1,422 @@
+//! Half-open spans of marks within a lane, with intersection, union, difference and rescaling.
+//!
+//! A [`Span`] goes from a start [`Mark`] included to an end [`Mark`] excluded, so the spans of
+//! two consecutive days touch without overlapping. Rescaling a span to another lane rounds its
+//! ends either outward, to the smallest span containing it, or inward, to the largest span it
+//! contains: this is what the rounded down and rounded up [`crate::Scaler`] functions do to each
+//! end.
+//!
+//! # Examples
+//! ```
+//! use timelane::lane::{Day, Hour};
+//! use timelane::span::Span;
+//! let booked = Span::<Hour>::new(34, 40);
+//! let request = Span::<Hour>::new(38, 50);
+//! assert!(booked.overlaps(&request));
+//! assert_eq!(request.difference(&booked), (Span::new(38, 38), Span::new(40, 50)));
+//! assert_eq!(request.rescale_outward::<Day>(), Span::new(2, 4));
+//! assert!(request.rescale_inward::<Day>().is_empty());
+//! ```
+use core::marker::PhantomData;
+
+use crate::iter::Marks;
+use crate::lane::Lane;
+use crate::Mark;
+
+/// A half-open span of marks of the lane `L`, from a start [`Mark`] included to an end [`Mark`]
+/// excluded.
+///
+/// A span ending at or before its start is empty, and is stored ending at its start.
+///
+/// # Examples
+/// ```
+/// use timelane::lane::{Minute, Second};
+/// use timelane::span::Span;
+/// let leap_minute = timelane::LEAP_SECONDS_MARKS[26] - 1;
+/// let minute = Span::<Minute>::from_mark(leap_minute);
+/// assert_eq!(minute.length_in::<Second>(), 61);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Span<L> {
+    start: Mark,
+    end: Mark,
+    lane: PhantomData<L>,
+}
+
+impl<L> Span<L> {
+    /// Creates a span from `start` included to `end` excluded, which is empty if `end` is not
+    /// after `start`.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Day;
+    /// use timelane::span::Span;
+    /// assert_eq!(Span::<Day>::new(1, 32).len(), 31);
+    /// assert_eq!(Span::<Day>::new(5, 3), Span::new(5, 5));
+    /// ```
+    pub const fn new(start: Mark, end: Mark) -> Self {
+        Self {
+            start,
+            end: if end < start { start } else { end },
+            lane: PhantomData,
+        }
+    }
+
+    /// Creates the span of a single [`Mark`].
+    ///
+    /// # Panics
+    /// Panics if `mark` is [`Mark::MAX`], which has no end.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Month;
+    /// use timelane::span::Span;
+    /// assert_eq!(Span::<Month>::from_mark(2), Span::new(2, 3));
+    /// ```
+    pub const fn from_mark(mark: Mark) -> Self {
+        Self::new(mark, mark + 1)
+    }
+
+    /// Returns the first [`Mark`] of the span.
+    pub const fn start(&self) -> Mark {
+        self.start
+    }
+
+    /// Returns the [`Mark`] after the last one of the span.
+    pub const fn end(&self) -> Mark {
+        self.end
+    }
+
+    /// Returns the number of marks in the span.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Hour;
+    /// use timelane::span::Span;
+    /// assert_eq!(Span::<Hour>::new(-2, 2).len(), 4);
+    /// ```
+    pub const fn len(&self) -> Mark {
+        self.end - self.start
+    }
+
+    /// Returns `true` if the span contains no mark.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Hour;
+    /// use timelane::span::Span;
+    /// assert!(Span::<Hour>::new(2, 2).is_empty());
+    /// assert!(!Span::<Hour>::new(2, 3).is_empty());
+    /// ```
+    pub const fn is_empty(&self) -> bool {
+        self.end == self.start
+    }
+
+    /// Returns `true` if the span contains a [`Mark`].
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Day;
+    /// use timelane::span::Span;
+    /// let january = Span::<Day>::new(1, 32);
+    /// assert!(january.contains(31));
+    /// assert!(!january.contains(32));
+    /// ```
+    pub const fn contains(&self, mark: Mark) -> bool {
+        self.start <= mark && mark < self.end
+    }
+
+    /// Returns `true` if the span contains all the marks of another span, which is always the
+    /// case for an empty span.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Day;
+    /// use timelane::span::Span;
+    /// let january = Span::<Day>::new(1, 32);
+    /// assert!(january.contains_span(&Span::new(10, 20)));
+    /// assert!(!january.contains_span(&Span::new(30, 33)));
+    /// ```
+    pub const fn contains_span(&self, other: &Self) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    /// Returns `true` if the span shares at least one mark with another span.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Day;
+    /// use timelane::span::Span;
+    /// let span = Span::<Day>::new(1, 3);
+    /// assert!(span.overlaps(&Span::new(2, 5)));
+    /// assert!(!span.overlaps(&Span::new(3, 5)));
+    /// ```
+    pub const fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// Returns the span of the marks in both spans, which is empty if they do not overlap.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Day;
+    /// use timelane::span::Span;
+    /// let span = Span::<Day>::new(1, 10);
+    /// assert_eq!(span.intersection(&Span::new(5, 20)), Span::new(5, 10));
+    /// assert!(span.intersection(&Span::new(15, 20)).is_empty());
+    /// ```
+    pub const fn intersection(&self, other: &Self) -> Self {
+        let start = if self.start > other.start {
+            self.start
+        } else {
+            other.start
+        };
+        let end = if self.end < other.end {
+            self.end
+        } else {
+            other.end
+        };
+        Self::new(start, end)
+    }
+
+    /// Returns the span of the marks in either span, or `None` if a gap separates them.
+    ///
+    /// Touching spans, like two consecutive days, have a union.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Day;
+    /// use timelane::span::Span;
+    /// let span = Span::<Day>::new(1, 10);
+    /// assert_eq!(span.union(&Span::new(10, 20)), Some(Span::new(1, 20)));
+    /// assert_eq!(span.union(&Span::new(11, 20)), None);
+    /// ```
+    pub const fn union(&self, other: &Self) -> Option<Self> {
+        if other.is_empty() {
+            Some(Self::new(self.start, self.end))
+        } else if self.is_empty() {
+            Some(Self::new(other.start, other.end))
+        } else if self.start <= other.end && other.start <= self.end {
+            let start = if self.start < other.start {
+                self.start
+            } else {
+                other.start
+            };
+            let end = if self.end > other.end {
+                self.end
+            } else {
+                other.end
+            };
+            Some(Self::new(start, end))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the spans of the marks of this span that are not in another span: the part before
+    /// the other span and the part after it, each of them possibly empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Day;
+    /// use timelane::span::Span;
+    /// let span = Span::<Day>::new(1, 10);
+    /// assert_eq!(span.difference(&Span::new(4, 6)), (Span::new(1, 4), Span::new(6, 10)));
+    /// assert_eq!(span.difference(&Span::new(0, 6)), (Span::new(1, 1), Span::new(6, 10)));
+    /// ```
+    pub const fn difference(&self, other: &Self) -> (Self, Self) {
+        if other.is_empty() {
+            return (
+                Self::new(self.start, self.end),
+                Self::new(self.end, self.end),
+            );
+        }
+        let before_end = if self.end < other.start {
+            self.end
+        } else {
+            other.start
+        };
+        let after_start = if self.start > other.end {
+            self.start
+        } else {
+            other.end
+        };
+        (
+            Self::new(self.start, before_end),
+            Self::new(after_start, self.end),
+        )
+    }
+
+    /// Returns an iterator over the marks of the span.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Day;
+    /// use timelane::span::Span;
+    /// assert!(Span::<Day>::new(1, 4).marks().eq([1, 2, 3]));
+    /// ```
+    pub const fn marks(&self) -> Marks {
+        Marks::new(self.start, self.end)
+    }
+}
+
+impl<L: Lane> Span<L> {
+    /// Returns the smallest span of the lane `M` containing this span.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::{Day, Month};
+    /// use timelane::span::Span;
+    /// let days = Span::<Day>::new(20, 40);
+    /// assert_eq!(days.rescale_outward::<Month>(), Span::new(1, 3));
+    /// assert_eq!(Span::<Month>::new(1, 3).rescale_outward::<Day>(), Span::new(1, 61));
+    /// ```
+    pub fn rescale_outward<M: Lane>(&self) -> Span<M> {
+        if self.is_empty() {
+            let start = rescale::<L, M>(self.start, false);
+            return Span::new(start, start);
+        }
+        Span::new(
+            rescale::<L, M>(self.start, false),
+            rescale::<L, M>(self.end, true),
+        )
+    }
+
+    /// Returns the largest span of the lane `M` contained in this span, which is empty if no
+    /// mark of `M` fits in it.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::{Day, Month};
+    /// use timelane::span::Span;
+    /// let days = Span::<Day>::new(20, 70);
+    /// assert_eq!(days.rescale_inward::<Month>(), Span::new(2, 3));
+    /// assert!(Span::<Day>::new(20, 40).rescale_inward::<Month>().is_empty());
+    /// ```
+    pub fn rescale_inward<M: Lane>(&self) -> Span<M> {
+        Span::new(
+            rescale::<L, M>(self.start, true),
+            rescale::<L, M>(self.end, false),
+        )
+    }
+
+    /// Returns the number of whole marks of the lane `M` contained in the span, which is the
+    /// exact length of the span when `M` is a finer lane.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::{Hour, Month, Year};
+    /// use timelane::span::Span;
+    /// assert_eq!(Span::<Year>::from_mark(2000).length_in::<Hour>(), 366 * 24);
+    /// assert_eq!(Span::<Month>::new(6, 30).length_in::<Year>(), 1);
+    /// ```
+    pub fn length_in<M: Lane>(&self) -> Mark {
+        self.rescale_inward::<M>().len()
+    }
+}
+
+impl<L> From<Span<L>> for core::ops::Range<Mark> {
+    fn from(span: Span<L>) -> Self {
+        span.start..span.end
+    }
+}
+
+/// Returns the [`Mark`] of the lane `M` where a [`Mark`] of the lane `L` starts, rounded down
+/// or up.
+fn rescale<L: Lane, M: Lane>(mark: Mark, up: bool) -> Mark {
+    let (second, nanosecond) = L::to_second_and_nanosecond(mark);
+    if up {
+        M::from_second_and_nanosecond_up(second, nanosecond)
+    } else {
+        M::from_second_and_nanosecond(second, nanosecond)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lane::{Day, Hour, Millisecond, Minute, Month, Second, Year};
+    use crate::{minute_to_second, LEAP_SECONDS_MARKS};
+
+    fn naive_difference(span: Span<Day>, other: Span<Day>) -> Vec<Mark> {
+        span.marks().filter(|mark| !other.contains(*mark)).collect()
+    }
+
+    #[test]
+    fn algebra_matches_sets_of_marks() {
+        for start in -4..4 {
+            for end in -4..4 {
+                let span = Span::<Day>::new(start, end);
+                for other_start in -4..4 {
+                    for other_end in -4..4 {
+                        let other = Span::<Day>::new(other_start, other_end);
+                        let intersection = span.intersection(&other);
+                        assert!(intersection
+                            .marks()
+                            .eq(span.marks().filter(|mark| other.contains(*mark))));
+                        assert_eq!(span.overlaps(&other), !intersection.is_empty());
+                        assert_eq!(
+                            span.contains_span(&other),
+                            other.marks().all(|mark| span.contains(mark))
+                        );
+                        let (before, after) = span.difference(&other);
+                        assert!(before
+                            .marks()
+                            .chain(after.marks())
+                            .eq(naive_difference(span, other)));
+                        match span.union(&other) {
+                            Some(union) => assert!(union.marks().eq((-4..4)
+                                .filter(|mark| { span.contains(*mark) || other.contains(*mark) }))),
+                            None => assert!(span.end < other.start || other.end < span.start),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rescaling_rounds_outward_and_inward() {
+        for day in -800..800 {
+            for length in 0..70 {
+                let days = Span::<Day>::new(day, day + length);
+                let outward = days.rescale_outward::<Month>();
+                let inward = days.rescale_inward::<Month>();
+                assert!(outward.rescale_outward::<Day>().contains_span(&days));
+                assert!(days.contains_span(&inward.rescale_outward::<Day>()));
+                assert!(outward.contains_span(&inward));
+                if !days.is_empty() {
+                    assert!(!outward.is_empty());
+                    assert!(!Span::<Month>::new(outward.start + 1, outward.end)
+                        .rescale_outward::<Day>()
+                        .contains_span(&days));
+                    assert!(!Span::<Month>::new(outward.start, outward.end - 1)
+                        .rescale_outward::<Day>()
+                        .contains_span(&days));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lengths_count_leap_seconds() {
+        for leap_minute in LEAP_SECONDS_MARKS {
+            let minute = Span::<Minute>::from_mark(leap_minute - 1);
+            assert_eq!(minute.length_in::<Second>(), 61);
+            assert_eq!(minute.length_in::<Millisecond>(), 61_000);
+            let hour = minute.rescale_outward::<Hour>();
+            assert_eq!(hour.length_in::<Second>(), 3601);
+            let leap = Span::<Second>::from_mark(minute_to_second(leap_minute) - 1);
+            assert_eq!(leap.rescale_outward::<Minute>(), minute);
+            assert!(leap.rescale_inward::<Minute>().is_empty());
+        }
+        assert_eq!(Span::<Year>::new(2000, 2004).length_in::<Day>(), 1461);
+        assert_eq!(Span::<Day>::new(2, 400).length_in::<Year>(), 0);
+    }
+
+    #[test]
+    fn spans_convert_to_ranges() {
+        let range: core::ops::Range<Mark> = Span::<Day>::new(3, 7).into();
+        assert_eq!(range, 3..7);
+    }
+}
@@ -0,0 +1,410 @@
+//! Ordered sets of disjoint spans, like availability calendars, with the `alloc` feature.
+//!
+//! A [`SpanSet`] stores its marks as sorted, disjoint and non-touching [`Span`]: inserting a
+//! span merges it with the spans it overlaps or touches, and removing a span cuts it out of the
+//! spans it overlaps. Both take a logarithmic time, plus the time to drop the merged spans.
+//!
+//! # Examples
+//! ```
+//! use timelane::lane::{Day, Minute};
+//! use timelane::span::Span;
+//! use timelane::span_set::SpanSet;
+//! let busy: SpanSet<Minute> = [Span::new(540, 600), Span::new(610, 700), Span::new(720, 1020)]
+//!     .into_iter()
+//!     .collect();
+//! let day = Span::<Day>::from_mark(1).rescale_outward::<Minute>();
+//! let free = busy.complement(day);
+//! let slots: Vec<_> = free.iter().filter(|slot| slot.len() >= 30).collect();
+//! assert_eq!(slots, [Span::new(0, 540), Span::new(1020, 1440)]);
+//! ```
+use alloc::collections::btree_map::{self, BTreeMap};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+
+use crate::lane::Lane;
+use crate::span::Span;
+use crate::Mark;
+
+/// A set of marks of the lane `L`, stored as an ordered set of disjoint [`Span`].
+///
+/// # Examples
+/// ```
+/// use timelane::lane::Day;
+/// use timelane::span::Span;
+/// use timelane::span_set::SpanSet;
+/// let mut days = SpanSet::<Day>::new();
+/// days.insert(Span::new(1, 10));
+/// days.insert(Span::new(10, 20));
+/// days.remove(Span::new(5, 6));
+/// assert!(days.iter().eq([Span::new(1, 5), Span::new(6, 20)]));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SpanSet<L> {
+    /// The end of each span, by start.
+    spans: BTreeMap<Mark, Mark>,
+    lane: PhantomData<L>,
+}
+
+impl<L> SpanSet<L> {
+    /// Creates an empty set.
+    pub const fn new() -> Self {
+        Self {
+            spans: BTreeMap::new(),
+            lane: PhantomData,
+        }
+    }
+
+    /// Returns `true` if the set contains no mark.
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Returns the number of disjoint spans in the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Day;
+    /// use timelane::span::Span;
+    /// use timelane::span_set::SpanSet;
+    /// let days: SpanSet<Day> = [Span::new(1, 3), Span::new(3, 5), Span::new(6, 7)]
+    ///     .into_iter()
+    ///     .collect();
+    /// assert_eq!(days.span_count(), 2);
+    /// ```
+    pub fn span_count(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Returns the span of the set containing a [`Mark`], if any.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Day;
+    /// use timelane::span::Span;
+    /// use timelane::span_set::SpanSet;
+    /// let days: SpanSet<Day> = [Span::new(1, 3), Span::new(6, 7)].into_iter().collect();
+    /// assert_eq!(days.span_containing(2), Some(Span::new(1, 3)));
+    /// assert_eq!(days.span_containing(3), None);
+    /// ```
+    pub fn span_containing(&self, mark: Mark) -> Option<Span<L>> {
+        let (&start, &end) = self.spans.range(..=mark).next_back()?;
+        (end > mark).then(|| Span::new(start, end))
+    }
+
+    /// Returns `true` if the set contains a [`Mark`].
+    pub fn contains(&self, mark: Mark) -> bool {
+        self.span_containing(mark).is_some()
+    }
+
+    /// Returns `true` if the set contains all the marks of a span.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Day;
+    /// use timelane::span::Span;
+    /// use timelane::span_set::SpanSet;
+    /// let days: SpanSet<Day> = [Span::new(1, 3), Span::new(6, 9)].into_iter().collect();
+    /// assert!(days.contains_span(Span::new(6, 8)));
+    /// assert!(!days.contains_span(Span::new(2, 7)));
+    /// ```
+    pub fn contains_span(&self, span: Span<L>) -> bool {
+        span.is_empty()
+            || self
+                .span_containing(span.start())
+                .is_some_and(|containing| containing.contains_span(&span))
+    }
+
+    /// Adds the marks of a span to the set, merging it with the spans it overlaps or touches.
+    pub fn insert(&mut self, span: Span<L>) {
+        if span.is_empty() {
+            return;
+        }
+        let (mut start, mut end) = (span.start(), span.end());
+        if let Some((&previous_start, &previous_end)) = self.spans.range(..=start).next_back() {
+            if previous_end >= start {
+                start = previous_start;
+                end = end.max(previous_end);
+            }
+        }
+        while let Some((&next_start, &next_end)) = self.spans.range(start..=end).next() {
+            self.spans.remove(&next_start);
+            end = end.max(next_end);
+        }
+        self.spans.insert(start, end);
+    }
+
+    /// Removes the marks of a span from the set, splitting the span of the set containing it if
+    /// needed.
+    pub fn remove(&mut self, span: Span<L>) {
+        if span.is_empty() {
+            return;
+        }
+        let (start, end) = (span.start(), span.end());
+        if let Some((&previous_start, &previous_end)) = self.spans.range(..start).next_back() {
+            if previous_end > start {
+                self.spans.insert(previous_start, start);
+                if previous_end > end {
+                    self.spans.insert(end, previous_end);
+                    return;
+                }
+            }
+        }
+        while let Some((&next_start, &next_end)) = self.spans.range(start..end).next() {
+            self.spans.remove(&next_start);
+            if next_end > end {
+                self.spans.insert(end, next_end);
+            }
+        }
+    }
+
+    /// Adds all the marks of another set to this set.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Day;
+    /// use timelane::span::Span;
+    /// use timelane::span_set::SpanSet;
+    /// let mut days: SpanSet<Day> = [Span::new(1, 3), Span::new(6, 9)].into_iter().collect();
+    /// days.merge(&[Span::new(3, 6)].into_iter().collect());
+    /// assert!(days.iter().eq([Span::new(1, 9)]));
+    /// ```
+    pub fn merge(&mut self, other: &Self) {
+        for span in other {
+            self.insert(span);
+        }
+    }
+
+    /// Returns the set of the marks of a bounding span that are not in this set.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Day;
+    /// use timelane::span::Span;
+    /// use timelane::span_set::SpanSet;
+    /// let days: SpanSet<Day> = [Span::new(1, 3), Span::new(6, 9)].into_iter().collect();
+    /// let free = days.complement(Span::new(0, 8));
+    /// assert!(free.iter().eq([Span::new(0, 1), Span::new(3, 6)]));
+    /// ```
+    pub fn complement(&self, bound: Span<L>) -> Self {
+        let mut complement = Self::new();
+        let (mut cursor, end) = (bound.start(), bound.end());
+        for span in self.overlapping(bound) {
+            if span.start() > cursor {
+                complement.spans.insert(cursor, span.start());
+            }
+            cursor = span.end();
+        }
+        if cursor < end {
+            complement.spans.insert(cursor, end);
+        }
+        complement
+    }
+
+    /// Returns an iterator over the spans of the set overlapping a bounding span, in order.
+    ///
+    /// The spans are not cut to the bound.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::Day;
+    /// use timelane::span::Span;
+    /// use timelane::span_set::SpanSet;
+    /// let days: SpanSet<Day> = [Span::new(1, 3), Span::new(6, 9), Span::new(12, 13)]
+    ///     .into_iter()
+    ///     .collect();
+    /// assert!(days.overlapping(Span::new(2, 7)).eq([Span::new(1, 3), Span::new(6, 9)]));
+    /// ```
+    pub fn overlapping(&self, bound: Span<L>) -> impl DoubleEndedIterator<Item = Span<L>> + '_ {
+        // The span starting before the bound may still overlap it
+        let start = match self.spans.range(..=bound.start()).next_back() {
+            Some((&start, &end)) if end > bound.start() && !bound.is_empty() => start,
+            _ => bound.start(),
+        };
+        self.spans
+            .range(start..bound.end())
+            .map(|(&start, &end)| Span::new(start, end))
+    }
+
+    /// Returns an iterator over the spans of the set, in order.
+    pub fn iter(&self) -> Spans<'_, L> {
+        Spans {
+            spans: self.spans.iter(),
+            lane: PhantomData,
+        }
+    }
+}
+
+impl<L: Lane> SpanSet<L> {
+    /// Returns the smallest set of the lane `M` containing this set, rounding each span outward.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::{Day, Hour};
+    /// use timelane::span::Span;
+    /// use timelane::span_set::SpanSet;
+    /// let hours: SpanSet<Hour> = [Span::new(2, 3), Span::new(20, 30)].into_iter().collect();
+    /// assert!(hours.rescale_outward::<Day>().iter().eq([Span::new(1, 3)]));
+    /// ```
+    pub fn rescale_outward<M: Lane>(&self) -> SpanSet<M> {
+        self.iter()
+            .map(|span| span.rescale_outward::<M>())
+            .collect()
+    }
+
+    /// Returns the largest set of the lane `M` contained in this set, rounding each span inward.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::{Day, Hour};
+    /// use timelane::span::Span;
+    /// use timelane::span_set::SpanSet;
+    /// let hours: SpanSet<Hour> = [Span::new(2, 3), Span::new(20, 50)].into_iter().collect();
+    /// assert!(hours.rescale_inward::<Day>().iter().eq([Span::new(2, 3)]));
+    /// ```
+    pub fn rescale_inward<M: Lane>(&self) -> SpanSet<M> {
+        self.iter().map(|span| span.rescale_inward::<M>()).collect()
+    }
+}
+
+impl<L> FromIterator<Span<L>> for SpanSet<L> {
+    fn from_iter<I: IntoIterator<Item = Span<L>>>(spans: I) -> Self {
+        let mut set = Self::new();
+        set.extend(spans);
+        set
+    }
+}
+
+impl<L> Extend<Span<L>> for SpanSet<L> {
+    fn extend<I: IntoIterator<Item = Span<L>>>(&mut self, spans: I) {
+        for span in spans {
+            self.insert(span);
+        }
+    }
+}
+
+impl<'a, L> IntoIterator for &'a SpanSet<L> {
+    type Item = Span<L>;
+    type IntoIter = Spans<'a, L>;
+
+    fn into_iter(self) -> Spans<'a, L> {
+        self.iter()
+    }
+}
+
+/// An iterator over the spans of a [`SpanSet`], in order.
+#[derive(Clone, Debug)]
+pub struct Spans<'a, L> {
+    spans: btree_map::Iter<'a, Mark, Mark>,
+    lane: PhantomData<L>,
+}
+
+impl<L> Iterator for Spans<'_, L> {
+    type Item = Span<L>;
+
+    fn next(&mut self) -> Option<Span<L>> {
+        let (&start, &end) = self.spans.next()?;
+        Some(Span::new(start, end))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.spans.size_hint()
+    }
+}
+
+impl<L> DoubleEndedIterator for Spans<'_, L> {
+    fn next_back(&mut self) -> Option<Span<L>> {
+        let (&start, &end) = self.spans.next_back()?;
+        Some(Span::new(start, end))
+    }
+}
+
+impl<L> ExactSizeIterator for Spans<'_, L> {}
+
+impl<L> FusedIterator for Spans<'_, L> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lane::{Day, Hour, Minute, Month};
+    use crate::test_util::xorshift;
+
+    /// A set of the marks from 0 to 64 excluded, one bit for each mark.
+    fn bits(set: &SpanSet<Day>) -> u64 {
+        set.iter()
+            .flat_map(|span| span.marks())
+            .fold(0, |bits, mark| bits | 1 << mark)
+    }
+
+    fn span_bits(span: Span<Day>) -> u64 {
+        span.marks().fold(0, |bits, mark| bits | 1 << mark)
+    }
+
+    fn assert_canonical(set: &SpanSet<Day>) {
+        let spans: Vec<_> = set.iter().collect();
+        assert!(spans.iter().all(|span| !span.is_empty()));
+        assert!(spans.windows(2).all(|pair| pair[0].end() < pair[1].start()));
+    }
+
+    #[test]
+    fn insertions_and_removals_match_bit_sets() {
+        let mut state = 12345;
+        let mut random = |bound: u64| (xorshift(&mut state) % bound) as Mark;
+        for _ in 0..200 {
+            let mut set = SpanSet::<Day>::new();
+            let mut expected = 0u64;
+            for _ in 0..30 {
+                let start = random(52);
+                let span = Span::new(start, start + random(12));
+                if random(3) == 0 {
+                    set.remove(span);
+                    expected &= !span_bits(span);
+                } else {
+                    set.insert(span);
+                    expected |= span_bits(span);
+                }
+                assert_canonical(&set);
+                assert_eq!(bits(&set), expected);
+                for mark in 0..64 {
+                    assert_eq!(set.contains(mark), expected & 1 << mark != 0);
+                }
+                let bound = Span::new(random(64), random(64));
+                let complement = set.complement(bound);
+                assert_canonical(&complement);
+                assert_eq!(bits(&complement), span_bits(bound) & !expected);
+                let overlapping: Vec<_> = set.overlapping(bound).collect();
+                let naive: Vec<_> = set.iter().filter(|span| span.overlaps(&bound)).collect();
+                assert_eq!(overlapping, naive);
+                assert_eq!(
+                    set.contains_span(bound),
+                    expected & span_bits(bound) == span_bits(bound)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn merging_joins_touching_spans() {
+        let mut set: SpanSet<Day> = [Span::new(0, 2), Span::new(4, 6)].into_iter().collect();
+        let other: SpanSet<Day> = [Span::new(2, 4), Span::new(10, 12)].into_iter().collect();
+        set.merge(&other);
+        assert!(set.iter().eq([Span::new(0, 6), Span::new(10, 12)]));
+        assert!(set.iter().rev().eq([Span::new(10, 12), Span::new(0, 6)]));
+        assert_eq!(set.iter().len(), 2);
+    }
+
+    #[test]
+    fn free_slots_are_found_in_coarser_lanes() {
+        let busy: SpanSet<Minute> = [Span::new(0, 30), Span::new(45, 24 * 60 + 15)]
+            .into_iter()
+            .collect();
+        let week = Span::<Day>::new(1, 8).rescale_outward::<Minute>();
+        let free = busy.complement(week);
+        assert_eq!(free.span_containing(40), Some(Span::new(30, 45)));
+        let free_hours = free.rescale_inward::<Hour>();
+        assert_eq!(free_hours.iter().next(), Some(Span::new(25, 7 * 24)));
+        let free_days = free.rescale_inward::<Day>();
+        assert!(free_days.iter().eq([Span::new(3, 8)]));
+        let busy_months = busy.rescale_outward::<Month>();
+        assert!(busy_months.iter().eq([Span::new(1, 2)]));
+    }
+}
@@ -0,0 +1,1185 @@
+//! Format and parse [`Mark`] of any [`Lane`] with `strftime` and `strptime` style patterns.
+//!
+//! A [`Pattern`] is a text where conversion specifications starting with `%` stand for the fields
+//! of a date and time. The other characters are copied as-is when formatting, and must be found
+//! as-is when parsing, except whitespace which matches any amount of whitespace.
+//!
+//! | Specification | Field | Example |
+//! |---|---|---|
+//! | `%Y` | Year, at least four digits | `2017` |
+//! | `%C` | Century, the year divided by 100 | `20` |
+//! | `%y` | Year of century, parsed as 1969 to 2068 without `%C` | `17` |
+//! | `%G`, `%g` | Year and year of century of the ISO 8601 week | `2016`, `16` |
+//! | `%m` | Month of year | `01` |
+//! | `%b`, `%h`, `%B` | Abbreviated and full English month name | `Jan`, `January` |
+//! | `%d`, `%e` | Day of month, padded with zeros or spaces | `01`, ` 1` |
+//! | `%j` | Day of year | `001` |
+//! | `%a`, `%A` | Abbreviated and full English day of week name | `Sun`, `Sunday` |
+//! | `%u`, `%w` | Day of week, Monday is 1 and Sunday 7, or Sunday is 0 | `7`, `0` |
+//! | `%U`, `%W` | Week of year, starting on the first Sunday or Monday | `01`, `00` |
+//! | `%V` | Week of the ISO 8601 week-numbering year | `52` |
+//! | `%H`, `%k` | Hour of day, padded with zeros or spaces | `00`, ` 0` |
+//! | `%I`, `%l` | Hour of the 12-hour clock, padded with zeros or spaces | `12`, `12` |
+//! | `%p`, `%P` | `AM` or `PM`, in uppercase or lowercase | `AM`, `am` |
+//! | `%M` | Minute of hour | `00` |
+//! | `%S` | Second of minute, 60 during leap seconds | `00` |
+//! | `%N` | Nanoseconds, `%3N` keeps only the milliseconds | `123000000` |
+//! | `%s` | Seconds since 1970, ignoring leap seconds like POSIX | `1483228800` |
+//! | `%z`, `%:z` | UTC offset | `+0100`, `+01:00` |
+//! | `%Z` | `UTC`, or the UTC offset when not zero | `UTC` |
+//! | `%F`, `%T` | `%Y-%m-%d`, `%H:%M:%S` | `2017-01-01`, `00:00:00` |
+//! | `%D`, `%x` | `%m/%d/%y` | `01/01/17` |
+//! | `%R`, `%X` | `%H:%M`, `%H:%M:%S` | `00:00`, `00:00:00` |
+//! | `%r` | `%I:%M:%S %p` | `12:00:00 AM` |
+//! | `%c` | `%a %b %e %H:%M:%S %Y` | `Sun Jan  1 00:00:00 2017` |
+//! | `%n`, `%t`, `%%` | A newline, a tab and a `%` | |
+//!
+//! Numeric specifications accept a flag between the `%` and the conversion: `-` removes the
+//! padding, `_` pads with spaces and `0` pads with zeros. They also accept a width up to 64,
+//! which is the minimum number of digits when formatting and the maximum number of digits when
+//! parsing.
+//!
+//! Patterns are checked when created with [`Pattern::new`], which is a `const fn`: the
+//! [`crate::strftime!`] macro creates a pattern in a constant, so invalid patterns fail the build.
+//!
+//! # Examples
+//! ```
+//! use timelane::lane::Second;
+//! use timelane::strftime;
+//! let pattern = strftime!("%d/%b/%Y:%H:%M:%S %z");
+//! let mark = pattern.parse::<Second>("31/Dec/2016:23:59:60 +0000").unwrap();
+//! assert_eq!(pattern.format::<Second>(mark).to_string(), "31/Dec/2016:23:59:60 +0000");
+//! assert_eq!(
+//!     pattern.format::<Second>(mark).with_offset(60).to_string(),
+//!     "01/Jan/2017:00:59:60 +0100"
+//! );
+//! ```
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::civil::{
+    day_to_weekday, day_to_year_month_day, days_in_month, days_in_year, iso_week_date_to_day,
+    iso_year_to_first_monday, minute_to_day_hour_minute, posix_to_second,
+    second_to_minute_and_second, second_to_posix, weeks_in_iso_year, year_month_day_to_day,
+    year_ordinal_to_day, MONTH_NAMES, UNIX_EPOCH_DAY, WEEKDAY_NAMES,
+};
+use crate::lane::Lane;
+use crate::parse::{
+    check_range, check_year, count_digits, digits, expect_end, parse_name, peek, skip_whitespace,
+    DateTimeFields, ParseError, ParseErrorKind,
+};
+use crate::{day_to_hour, hour_to_minute, Mark, EPOCH_YEAR};
+
+/// Creates a [`Pattern`] checked at compile time.
+///
+/// # Examples
+/// ```
+/// use timelane::lane::Day;
+/// use timelane::strftime;
+/// assert_eq!(strftime!("%Y-%j").format::<Day>(32).to_string(), "2000-032");
+/// ```
+///
+/// ```compile_fail
+/// use timelane::strftime;
+/// let pattern = strftime!("%Y-%Q");
+/// ```
+#[macro_export]
+macro_rules! strftime {
+    ($pattern:expr) => {{
+        const PATTERN: $crate::strftime::Pattern<'static> =
+            match $crate::strftime::Pattern::new($pattern) {
+                Ok(pattern) => pattern,
+                Err(_) => panic!("invalid strftime pattern"),
+            };
+        PATTERN
+    }};
+}
+
+/// A checked `strftime` pattern.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Pattern<'a> {
+    text: &'a str,
+}
+
+impl<'a> Pattern<'a> {
+    /// Checks a pattern, returns an error pointing at the first invalid conversion specification.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::strftime::Pattern;
+    /// use timelane::parse::ParseErrorKind;
+    /// assert!(Pattern::new("%Y-%m-%dT%H:%M:%S.%3N%:z").is_ok());
+    /// let error = Pattern::new("%Y-%Q").unwrap_err();
+    /// assert_eq!(error.kind(), ParseErrorKind::UnexpectedCharacter);
+    /// assert_eq!(error.position(), 4);
+    /// ```
+    pub const fn new(text: &'a str) -> Result<Self, ParseError> {
+        let bytes = text.as_bytes();
+        let mut position = 0;
+        while position < bytes.len() {
+            let (_, next) = tri!(token(bytes, position));
+            position = next;
+        }
+        Ok(Self { text })
+    }
+
+    /// Returns the text of the pattern.
+    pub const fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    /// Returns a value formatting a [`Mark`] of a lane with this pattern, in UTC.
+    ///
+    /// The mark is formatted at its start: a day [`Mark`] is formatted at midnight.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::{Day, Nanosecond};
+    /// use timelane::strftime;
+    /// assert_eq!(strftime!("%F %T").format::<Day>(1).to_string(), "2000-01-01 00:00:00");
+    /// assert_eq!(strftime!("%T.%6N").format::<Nanosecond>(-1).to_string(), "23:59:59.999999");
+    /// ```
+    pub fn format<L: Lane>(&self, mark: Mark) -> Formatted<'a, L> {
+        Formatted {
+            pattern: *self,
+            mark,
+            offset: 0,
+            lane: PhantomData,
+        }
+    }
+
+    /// Parses a text with this pattern into a rounded down [`Mark`] of a lane.
+    ///
+    /// Missing fields default to the start of their enclosing field, and the year defaults to
+    /// [`EPOCH_YEAR`]. When the text has a UTC offset, the mark is moved back to UTC.
+    ///
+    /// When both a date and a day of week are parsed, the day of week must match the date.
+    ///
+    /// # Examples
+    /// ```
+    /// use timelane::lane::{Hour, Second};
+    /// use timelane::strftime;
+    /// assert_eq!(strftime!("%H:%M").parse::<Second>("00:01"), Ok(60));
+    /// assert_eq!(strftime!("%Y %j %H:%M").parse::<Hour>("2000 001 01:59"), Ok(1));
+    /// assert_eq!(strftime!("%s").parse::<Second>("946684800"), Ok(0));
+    /// ```
+    pub fn parse<L: Lane>(&self, input: &str) -> Result<Mark, ParseError> {
+        let mut fields = ParsedFields::default();
+        let position = parse_pattern(self.text, input.as_bytes(), 0, &mut fields)?;
+        expect_end(input.as_bytes(), position)?;
+        let (second, nanosecond) = fields.resolve()?;
+        if second < L::MIN_SECOND || second > L::MAX_SECOND {
+            return Err(ParseError::new(0, ParseErrorKind::OutOfRange));
+        }
+        Ok(L::from_second_and_nanosecond(second, nanosecond))
+    }
+}
+
+impl fmt::Display for Pattern<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.text)
+    }
+}
+
+/// A [`Mark`] of a lane formatted with a [`Pattern`], see [`Pattern::format`].
+#[derive(Clone, Copy, Debug)]
+pub struct Formatted<'a, L> {
+    pattern: Pattern<'a>,
+    mark: Mark,
+    offset: Mark,
+    lane: PhantomData<L>,
+}
+
+impl<L> Formatted<'_, L> {
+    /// Formats the local time at a UTC offset, in minutes, instead of UTC.
+    pub fn with_offset(mut self, offset: Mark) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+impl<L: Lane> fmt::Display for Formatted<'_, L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (second, nanosecond) = L::to_second_and_nanosecond(self.mark);
+        let fields = FormattedFields::new(second, nanosecond, self.offset);
+        format_pattern(f, self.pattern.text, &fields)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Padding {
+    Default,
+    None,
+    Space,
+    Zero,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Token {
+    Literal {
+        start: usize,
+        end: usize,
+    },
+    Conversion {
+        padding: Padding,
+        /// The width, zero when not specified.
+        width: usize,
+        colon: bool,
+        conversion: u8,
+    },
+}
+
+/// The largest width of a conversion specification, far more than the digits of any number.
+const MAX_WIDTH: usize = 64;
+
+/// Reads the token starting at a position, returns it with the position of the next one.
+const fn token(bytes: &[u8], position: usize) -> Result<(Token, usize), ParseError> {
+    if bytes[position] != b'%' {
+        let mut end = position;
+        while end < bytes.len() && bytes[end] != b'%' {
+            end += 1;
+        }
+        return Ok((
+            Token::Literal {
+                start: position,
+                end,
+            },
+            end,
+        ));
+    }
+    let mut position = position + 1;
+    let padding = match tri!(peek(bytes, position)) {
+        b'-' => Padding::None,
+        b'_' => Padding::Space,
+        b'0' => Padding::Zero,
+        _ => Padding::Default,
+    };
+    if !matches!(padding, Padding::Default) {
+        position += 1;
+    }
+    let width_position = position;
+    let width_digits = count_digits(bytes, position);
+    let width = if width_digits > 0 {
+        let (width, next) = tri!(digits(bytes, position, width_digits));
+        let width = tri!(check_range(
+            width,
+            0,
+            MAX_WIDTH as Mark,
+            width_position,
+            ParseErrorKind::OutOfRange
+        ));
+        position = next;
+        width as usize
+    } else {
+        0
+    };
+    let colon = tri!(peek(bytes, position)) == b':';
+    if colon {
+        position += 1;
+    }
+    let conversion = tri!(peek(bytes, position));
+    if !matches!(
+        conversion,
+        b'a' | b'A'
+            | b'b'
+            | b'B'
+            | b'c'
+            | b'C'
+            | b'd'
+            | b'D'
+            | b'e'
+            | b'F'
+            | b'g'
+            | b'G'
+            | b'h'
+            | b'H'
+            | b'I'
+            | b'j'
+            | b'k'
+            | b'l'
+            | b'm'
+            | b'M'
+            | b'n'
+            | b'N'
+            | b'p'
+            | b'P'
+            | b'r'
+            | b'R'
+            | b's'
+            | b'S'
+            | b't'
+            | b'T'
+            | b'u'
+            | b'U'
+            | b'V'
+            | b'w'
+            | b'W'
+            | b'x'
+            | b'X'
+            | b'y'
+            | b'Y'
+            | b'z'
+            | b'Z'
+            | b'%'
+    ) {
+        return Err(ParseError::new(
+            position,
+            ParseErrorKind::UnexpectedCharacter,
+        ));
+    }
+    if colon && conversion != b'z' {
+        return Err(ParseError::new(
+            position - 1,
+            ParseErrorKind::UnexpectedCharacter,
+        ));
+    }
+    if conversion == b'N' && width > 9 {
+        return Err(ParseError::new(
+            width_position,
+            ParseErrorKind::UnexpectedCharacter,
+        ));
+    }
+    Ok((
+        Token::Conversion {
+            padding,
+            width,
+            colon,
+            conversion,
+        },
+        position + 1,
+    ))
+}
+
+/// Iterates over the tokens of a pattern already checked by [`Pattern::new`].
+struct Tokens<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        if self.position >= self.bytes.len() {
+            return None;
+        }
+        let (token, next) = token(self.bytes, self.position).ok()?;
+        self.position = next;
+        Some(token)
+    }
+}
+
+const fn tokens(pattern: &str) -> Tokens<'_> {
+    Tokens {
+        bytes: pattern.as_bytes(),
+        position: 0,
+    }
+}
+
+/// Returns the pattern a composite conversion stands for.
+const fn composite(conversion: u8) -> Option<&'static str> {
+    match conversion {
+        b'c' => Some("%a %b %e %H:%M:%S %Y"),
+        b'D' | b'x' => Some("%m/%d/%y"),
+        b'F' => Some("%Y-%m-%d"),
+        b'r' => Some("%I:%M:%S %p"),
+        b'R' => Some("%H:%M"),
+        b'T' | b'X' => Some("%H:%M:%S"),
+        _ => None,
+    }
+}
+
+/// Returns the default width and padding of a numeric conversion.
+const fn numeric_format(conversion: u8) -> Option<(usize, Padding)> {
+    match conversion {
+        b'Y' | b'G' => Some((4, Padding::Zero)),
+        b'j' => Some((3, Padding::Zero)),
+        b'C' | b'd' | b'g' | b'H' | b'I' | b'm' | b'M' | b'S' | b'U' | b'V' | b'W' | b'y' => {
+            Some((2, Padding::Zero))
+        }
+        b'e' | b'k' | b'l' => Some((2, Padding::Space)),
+        b's' | b'u' | b'w' => Some((1, Padding::Zero)),
+        _ => None,
+    }
+}
+
+/// The fields of a date and time to format.
+struct FormattedFields {
+    year: Mark,
+    month: Mark,
+    day: Mark,
+    ordinal: Mark,
+    weekday: Mark,
+    iso_year: Mark,
+    iso_week: Mark,
+    hour: Mark,
+    minute: Mark,
+    second: Mark,
+    nanosecond: Mark,
+    offset: Mark,
+    timestamp: i128,
+}
+
+impl FormattedFields {
+    fn new(second: Mark, nanosecond: Mark, offset: Mark) -> Self {
+        let (utc_minute, second_of_minute) = second_to_minute_and_second(second);
+        let timestamp = second_to_posix(second);
+        let (day_mark, hour, minute) = minute_to_day_hour_minute(utc_minute + offset);
+        let (year, month, day) = day_to_year_month_day(day_mark);
+        let iso_year = if day_mark < iso_year_to_first_monday(year) {
+            year - 1
+        } else if day_mark >= iso_year_to_first_monday(year + 1) {
+            year + 1
+        } else {
+            year
+        };
+        Self {
+            year,
+            month,
+            day,
+            ordinal: day_mark - year_ordinal_to_day(year, 1) + 1,
+            weekday: day_to_weekday(day_mark),
+            iso_year,
+            iso_week: (day_mark - iso_year_to_first_monday(iso_year)) / 7 + 1,
+            hour,
+            minute,
+            second: second_of_minute,
+            nanosecond,
+            offset,
+            timestamp,
+        }
+    }
+
+    fn number(&self, conversion: u8) -> i128 {
+        let number = match conversion {
+            b'Y' => self.year,
+            b'C' => self.year.div_euclid(100),
+            b'y' => self.year.rem_euclid(100),
+            b'G' => self.iso_year,
+            b'g' => self.iso_year.rem_euclid(100),
+            b'm' => self.month,
+            b'd' | b'e' => self.day,
+            b'j' => self.ordinal,
+            b'u' => self.weekday,
+            b'w' => self.weekday % 7,
+            b'U' => (self.ordinal - 1 + 7 - self.weekday % 7) / 7,
+            b'W' => (self.ordinal - 1 + 7 - (self.weekday - 1)) / 7,
+            b'V' => self.iso_week,
+            b'H' | b'k' => self.hour,
+            b'I' | b'l' => (self.hour + 11) % 12 + 1,
+            b'M' => self.minute,
+            b'S' => self.second,
+            b's' => return self.timestamp,
+            _ => unreachable!("not a numeric conversion"),
+        };
+        number as i128
+    }
+}
+
+fn format_pattern(
+    f: &mut fmt::Formatter<'_>,
+    pattern: &str,
+    fields: &FormattedFields,
+) -> fmt::Result {
+    for token in tokens(pattern) {
+        match token {
+            Token::Literal { start, end } => f.write_str(&pattern[start..end])?,
+            Token::Conversion {
+                padding,
+                width,
+                colon,
+                conversion,
+            } => format_conversion(f, padding, width, colon, conversion, fields)?,
+        }
+    }
+    Ok(())
+}
+
+fn format_conversion(
+    f: &mut fmt::Formatter<'_>,
+    padding: Padding,
+    width: usize,
+    colon: bool,
+    conversion: u8,
+    fields: &FormattedFields,
+) -> fmt::Result {
+    if let Some(pattern) = composite(conversion) {
+        return format_pattern(f, pattern, fields);
+    }
+    if let Some((default_width, default_padding)) = numeric_format(conversion) {
+        let width = if width > 0 { width } else { default_width };
+        let padding = if padding == Padding::Default {
+            default_padding
+        } else {
+            padding
+        };
+        return format_number(f, fields.number(conversion), width, padding);
+    }
+    match conversion {
+        b'a' => f.write_str(&WEEKDAY_NAMES[fields.weekday as usize - 1][..3]),
+        b'A' => f.write_str(WEEKDAY_NAMES[fields.weekday as usize - 1]),
+        b'b' | b'h' => f.write_str(&MONTH_NAMES[fields.month as usize - 1][..3]),
+        b'B' => f.write_str(MONTH_NAMES[fields.month as usize - 1]),
+        b'p' => f.write_str(if fields.hour < 12 { "AM" } else { "PM" }),
+        b'P' => f.write_str(if fields.hour < 12 { "am" } else { "pm" }),
+        b'N' => {
+            let width = if width > 0 { width } else { 9 };
+            let mut nanosecond = fields.nanosecond;
+            for _ in width..9 {
+                nanosecond /= 10;
+            }
+            format_number(f, nanosecond as i128, width, Padding::Zero)
+        }
+        b'z' => format_offset(f, fields.offset, colon),
+        b'Z' if fields.offset == 0 => f.write_str("UTC"),
+        b'Z' => format_offset(f, fields.offset, true),
+        b'n' => f.write_str("\n"),
+        b't' => f.write_str("\t"),
+        b'%' => f.write_str("%"),
+        _ => unreachable!("conversions are checked by Pattern::new"),
+    }
+}
+
+fn format_number(
+    f: &mut fmt::Formatter<'_>,
+    value: i128,
+    width: usize,
+    padding: Padding,
+) -> fmt::Result {
+    let sign = if value < 0 { "-" } else { "" };
+    let value = value.unsigned_abs();
+    let digits = value.checked_ilog10().map_or(1, |log| log as usize + 1);
+    let padding_width = width.saturating_sub(digits + sign.len());
+    match padding {
+        Padding::None => write!(f, "{}{}", sign, value),
+        Padding::Space => write!(f, "{:padding_width$}{}{}", "", sign, value),
+        Padding::Zero | Padding::Default => {
+            write!(f, "{}{:0>padding_width$}{}", sign, "", value)
+        }
+    }
+}
+
+fn format_offset(f: &mut fmt::Formatter<'_>, offset: Mark, colon: bool) -> fmt::Result {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.unsigned_abs();
+    if colon {
+        write!(f, "{}{:02}:{:02}", sign, offset / 60, offset % 60)
+    } else {
+        write!(f, "{}{:02}{:02}", sign, offset / 60, offset % 60)
+    }
+}
+
+/// A parsed field, with the position where it was found.
+type Field = Option<(Mark, usize)>;
+
+/// The fields found while parsing a text with a pattern.
+#[derive(Default)]
+struct ParsedFields {
+    year: Field,
+    century: Field,
+    year_of_century: Field,
+    iso_year: Field,
+    iso_year_of_century: Field,
+    month: Field,
+    day: Field,
+    ordinal: Field,
+    weekday: Field,
+    sunday_week: Field,
+    monday_week: Field,
+    iso_week: Field,
+    hour: Field,
+    hour_of_12: Field,
+    afternoon: Option<bool>,
+    minute: Field,
+    second: Field,
+    nanosecond: Field,
+    offset: Field,
+    timestamp: Field,
+}
+
+/// Returns the year of a full year field, or of century and year of century fields.
+fn resolve_year(year: Field, century: Field, year_of_century: Field) -> Field {
+    match (year, century, year_of_century) {
+        (Some(year), _, _) => Some(year),
+        (None, Some((century, position)), year_of_century) => Some((
+            century * 100 + year_of_century.map_or(0, |(year, _)| year),
+            position,
+        )),
+        (None, None, Some((year, position))) => {
+            Some((if year < 69 { 2000 + year } else { 1900 + year }, position))
+        }
+        (None, None, None) => None,
+    }
+}
+
+impl ParsedFields {
+    /// Returns the UTC second [`Mark`] and the nanoseconds after it described by the fields.
+    fn resolve(&self) -> Result<(Mark, Mark), ParseError> {
+        let nanosecond = self.nanosecond.map_or(0, |(nanosecond, _)| nanosecond);
+        if let Some((timestamp, position)) = self.timestamp {
+            let day = UNIX_EPOCH_DAY + timestamp.div_euclid(86_400);
+            check_year(day / 365 + EPOCH_YEAR, position)?;
+            return Ok((posix_to_second(timestamp), nanosecond));
+        }
+        let (year, year_position) =
+            resolve_year(self.year, self.century, self.year_of_century).unwrap_or((EPOCH_YEAR, 0));
+        let year = check_year(year, year_position)?;
+        let day = self.resolve_day(year)?;
+        if let Some((weekday, position)) = self.weekday {
+            let explicit_date =
+                self.month.is_some() || self.day.is_some() || self.ordinal.is_some();
+            if explicit_date && day_to_weekday(day) != weekday {
+                return Err(ParseError::new(position, ParseErrorKind::MismatchedWeekday));
+            }
+        }
+        let hour = match (self.hour, self.hour_of_12) {
+            (Some((hour, _)), _) => hour,
+            (None, Some((hour, _))) => {
+                hour % 12 + if self.afternoon == Some(true) { 12 } else { 0 }
+            }
+            (None, None) => {
+                if self.afternoon == Some(true) {
+                    12
+                } else {
+                    0
+                }
+            }
+        };
+        let minute = self.minute.map_or(0, |(minute, _)| minute);
+        let (second, second_position) = self.second.unwrap_or((0, 0));
+        let minute = hour_to_minute(day_to_hour(day) + hour) + minute;
+        let fields = self.date_time_fields(
+            day,
+            self.offset.map_or(0, |(offset, _)| offset),
+            minute,
+            second,
+            second_position,
+        );
+        Ok((fields.to_second()?, nanosecond))
+    }
+
+    fn date_time_fields(
+        &self,
+        day: Mark,
+        offset: Mark,
+        minute: Mark,
+        second: Mark,
+        second_position: usize,
+    ) -> DateTimeFields {
+        DateTimeFields {
+            day,
+            hour: 0,
+            minute: minute - hour_to_minute(day_to_hour(day)),
+            second,
+            nanosecond: 0,
+            offset,
+            start_position: 0,
+            second_position,
+        }
+    }
+
+    /// Returns the local day [`Mark`] described by the date fields.
+    fn resolve_day(&self, year: Mark) -> Result<Mark, ParseError> {
+        if self.month.is_some() || self.day.is_some() {
+            let month = self.month.map_or(1, |(month, _)| month);
+            let (day, position) = self.day.unwrap_or((1, 0));
+            check_range(
+                day,
+                1,
+                days_in_month(year, month),
+                position,
+                ParseErrorKind::InvalidDay,
+            )?;
+            return Ok(year_month_day_to_day(year, month, day));
+        }
+        if let Some((ordinal, position)) = self.ordinal {
+            check_range(
+                ordinal,
+                1,
+                days_in_year(year),
+                position,
+                ParseErrorKind::InvalidDay,
+            )?;
+            return Ok(year_ordinal_to_day(year, ordinal));
+        }
+        if let Some((week, position)) = self.iso_week {
+            let (iso_year, iso_year_position) =
+                resolve_year(self.iso_year, self.century, self.iso_year_of_century)
+                    .unwrap_or((year, 0));
+            let iso_year = check_year(iso_year, iso_year_position)?;
+            check_range(
+                week,
+                1,
+                weeks_in_iso_year(iso_year),
+                position,
+                ParseErrorKind::InvalidWeek,
+            )?;
+            let weekday = self.weekday.map_or(1, |(weekday, _)| weekday);
+            return Ok(iso_week_date_to_day(iso_year, week, weekday));
+        }
+        let january_1st = year_ordinal_to_day(year, 1);
+        let weekday = self.weekday.map_or(1, |(weekday, _)| weekday);
+        if let Some((week, _)) = self.sunday_week {
+            let first_sunday = january_1st + (7 - day_to_weekday(january_1st) % 7) % 7;
+            return Ok(first_sunday + (week - 1) * 7 + weekday % 7);
+        }
+        if let Some((week, _)) = self.monday_week {
+            let first_monday = january_1st + (7 - (day_to_weekday(january_1st) - 1)) % 7;
+            return Ok(first_monday + (week - 1) * 7 + weekday - 1);
+        }
+        Ok(january_1st)
+    }
+}
+
+/// Parses a text with a pattern, returns the position after the parsed text.
+fn parse_pattern(
+    pattern: &str,
+    bytes: &[u8],
+    mut position: usize,
+    fields: &mut ParsedFields,
+) -> Result<usize, ParseError> {
+    for token in tokens(pattern) {
+        position = match token {
+            Token::Literal { start, end } => {
+                parse_literal(&pattern.as_bytes()[start..end], bytes, position)?
+            }
+            Token::Conversion {
+                padding,
+                width,
+                conversion,
+                ..
+            } => parse_conversion(bytes, position, padding, width, conversion, fields)?,
+        };
+    }
+    Ok(position)
+}
+
+fn parse_literal(literal: &[u8], bytes: &[u8], mut position: usize) -> Result<usize, ParseError> {
+    for &expected in literal {
+        if expected.is_ascii_whitespace() {
+            position = skip_whitespace(bytes, position);
+        } else if peek(bytes, position)? == expected {
+            position += 1;
+        } else {
+            return Err(ParseError::new(
+                position,
+                ParseErrorKind::UnexpectedCharacter,
+            ));
+        }
+    }
+    Ok(position)
+}
+
+/// Parses a number of at most `max_digits` digits, with an optional sign when `signed` is set.
+fn parse_number(
+    bytes: &[u8],
+    position: usize,
+    max_digits: usize,
+    signed: bool,
+) -> Result<(Mark, usize), ParseError> {
+    let sign = peek(bytes, position)?;
+    let negative = signed && sign == b'-';
+    let start = if signed && (sign == b'-' || sign == b'+') {
+        position + 1
+    } else {
+        position
+    };
+    let count = count_digits(bytes, start).min(max_digits);
+    if count == 0 {
+        peek(bytes, start)?;
+        return Err(ParseError::new(start, ParseErrorKind::UnexpectedCharacter));
+    }
+    let (value, next) = digits(bytes, start, count)?;
+    Ok((if negative { -value } else { value }, next))
+}
+
+/// Parses a `Z`, `+HH`, `+HHMM` or `+HH:MM` UTC offset, returns the offset in minutes.
+fn parse_offset(bytes: &[u8], position: usize) -> Result<(Mark, usize), ParseError> {
+    let sign = peek(bytes, position)?;
+    if sign.eq_ignore_ascii_case(&b'Z') {
+        return Ok((0, position + 1));
+    }
+    if sign != b'+' && sign != b'-' {
+        return Err(ParseError::new(
+            position,
+            ParseErrorKind::UnexpectedCharacter,
+        ));
+    }
+    let (hours, mut next) = digits(bytes, position + 1, 2)?;
+    check_range(hours, 0, 23, position + 1, ParseErrorKind::InvalidOffset)?;
+    let mut minutes = 0;
+    let colon = next < bytes.len() && bytes[next] == b':';
+    if colon || count_digits(bytes, next) >= 2 {
+        if colon {
+            next += 1;
+        }
+        let (value, after) = digits(bytes, next, 2)?;
+        minutes = check_range(value, 0, 59, next, ParseErrorKind::InvalidOffset)?;
+        next = after;
+    }
+    let offset = hours * 60 + minutes;
+    Ok((if sign == b'-' { -offset } else { offset }, next))
+}
+
+fn parse_conversion(
+    bytes: &[u8],
+    position: usize,
+    padding: Padding,
+    width: usize,
+    conversion: u8,
+    fields: &mut ParsedFields,
+) -> Result<usize, ParseError> {
+    if let Some(pattern) = composite(conversion) {
+        return parse_pattern(pattern, bytes, position, fields);
+    }
+    if let Some((default_width, default_padding)) = numeric_format(conversion) {
+        let padding = if padding == Padding::Default {
+            default_padding
+        } else {
+            padding
+        };
+        let position = if padding == Padding::Zero {
+            position
+        } else {
+            skip_whitespace(bytes, position)
+        };
+        let max_digits = match (width, conversion) {
+            (0, b's') => usize::MAX,
+            (0, _) => default_width,
+            (width, _) => width,
+        };
+        let signed = matches!(conversion, b'Y' | b'G' | b's');
+        let (value, next) = parse_number(bytes, position, max_digits, signed)?;
+        let field = Some((value, position));
+        let (min, max, kind) = match conversion {
+            b'm' => (1, 12, ParseErrorKind::InvalidMonth),
+            b'd' | b'e' => (1, 31, ParseErrorKind::InvalidDay),
+            b'j' => (1, 366, ParseErrorKind::InvalidDay),
+            b'u' => (1, 7, ParseErrorKind::InvalidWeekday),
+            b'w' => (0, 6, ParseErrorKind::InvalidWeekday),
+            b'U' | b'W' => (0, 53, ParseErrorKind::InvalidWeek),
+            b'V' => (1, 53, ParseErrorKind::InvalidWeek),
+            b'H' | b'k' => (0, 23, ParseErrorKind::InvalidHour),
+            b'I' | b'l' => (1, 12, ParseErrorKind::InvalidHour),
+            b'M' => (0, 59, ParseErrorKind::InvalidMinute),
+            b'S' => (0, 60, ParseErrorKind::InvalidSecond),
+            _ => (Mark::MIN, Mark::MAX, ParseErrorKind::OutOfRange),
+        };
+        check_range(value, min, max, position, kind)?;
+        match conversion {
+            b'Y' => fields.year = field,
+            b'C' => fields.century = field,
+            b'y' => fields.year_of_century = field,
+            b'G' => fields.iso_year = field,
+            b'g' => fields.iso_year_of_century = field,
+            b'm' => fields.month = field,
+            b'd' | b'e' => fields.day = field,
+            b'j' => fields.ordinal = field,
+            b'u' => fields.weekday = field,
+            b'w' => fields.weekday = Some((if value == 0 { 7 } else { value }, position)),
+            b'U' => fields.sunday_week = field,
+            b'W' => fields.monday_week = field,
+            b'V' => fields.iso_week = field,
+            b'H' | b'k' => fields.hour = field,
+            b'I' | b'l' => fields.hour_of_12 = field,
+            b'M' => fields.minute = field,
+            b'S' => fields.second = field,
+            b's' => fields.timestamp = field,
+            _ => unreachable!("not a numeric conversion"),
+        }
+        return Ok(next);
+    }
+    match conversion {
+        b'a' | b'A' => {
+            let (weekday, next) = parse_name(bytes, position, &WEEKDAY_NAMES)?;
+            fields.weekday = Some((weekday, position));
+            Ok(next)
+        }
+        b'b' | b'B' | b'h' => {
+            let (month, next) = parse_name(bytes, position, &MONTH_NAMES)?;
+            fields.month = Some((month, position));
+            Ok(next)
+        }
+        b'p' | b'P' => {
+            let (index, next) = parse_name(bytes, position, &["AM", "PM"])?;
+            fields.afternoon = Some(index == 2);
+            Ok(next)
+        }
+        b'N' => {
+            let max_digits = if width > 0 { width } else { 9 };
+            let count = count_digits(bytes, position).min(max_digits);
+            if count == 0 {
+                peek(bytes, position)?;
+                return Err(ParseError::new(
+                    position,
+                    ParseErrorKind::UnexpectedCharacter,
+                ));
+            }
+            let (mut nanosecond, next) = digits(bytes, position, count)?;
+            for _ in count..9 {
+                nanosecond *= 10;
+            }
+            fields.nanosecond = Some((nanosecond, position));
+            Ok(next)
+        }
+        b'z' => {
+            let (offset, next) = parse_offset(bytes, position)?;
+            fields.offset = Some((offset, position));
+            Ok(next)
+        }
+        b'Z' => {
+            for name in ["UTC", "GMT", "UT", "Z"] {
+                let end = position + name.len();
+                if end <= bytes.len() && bytes[position..end].eq_ignore_ascii_case(name.as_bytes())
+                {
+                    return Ok(end);
+                }
+            }
+            if position < bytes.len() && (bytes[position] == b'+' || bytes[position] == b'-') {
+                let (offset, next) = parse_offset(bytes, position)?;
+                fields.offset = Some((offset, position));
+                return Ok(next);
+            }
+            peek(bytes, position)?;
+            Err(ParseError::new(
+                position,
+                ParseErrorKind::UnexpectedCharacter,
+            ))
+        }
+        b'n' | b't' => Ok(skip_whitespace(bytes, position)),
+        b'%' => parse_literal(b"%", bytes, position),
+        _ => unreachable!("conversions are checked by Pattern::new"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iso8601::{parse_rfc3339, parse_rfc3339_second};
+    use crate::lane::{Day, Month, Nanosecond, Second, Year};
+
+    fn error(result: Result<Mark, ParseError>) -> (usize, ParseErrorKind) {
+        let error = result.unwrap_err();
+        (error.position(), error.kind())
+    }
+
+    #[test]
+    fn common_log_format_roundtrips() {
+        let pattern = strftime!("%d/%b/%Y:%H:%M:%S %z");
+        let mark = pattern
+            .parse::<Second>("10/Oct/2000:13:55:36 -0700")
+            .unwrap();
+        assert_eq!(Ok(mark), parse_rfc3339_second("2000-10-10T13:55:36-07:00"));
+        assert_eq!(
+            pattern
+                .format::<Second>(mark)
+                .with_offset(-7 * 60)
+                .to_string(),
+            "10/Oct/2000:13:55:36 -0700"
+        );
+    }
+
+    #[test]
+    fn all_fields_are_formatted() {
+        let mark = parse_rfc3339("2017-01-01T15:04:05.123456789Z").unwrap();
+        let format = |pattern: &str| {
+            Pattern::new(pattern)
+                .unwrap()
+                .format::<Nanosecond>(mark)
+                .to_string()
+        };
+        assert_eq!(format("%Y %C %y %G %g"), "2017 20 17 2016 16");
+        assert_eq!(format("%m %b %h %B"), "01 Jan Jan January");
+        assert_eq!(format("%d %e %j"), "01  1 001");
+        assert_eq!(format("%a %A %u %w"), "Sun Sunday 7 0");
+        assert_eq!(format("%U %W %V"), "01 00 52");
+        assert_eq!(format("%H %k %I %l %p %P"), "15 15 03  3 PM pm");
+        assert_eq!(format("%M %S %N %3N %6N"), "04 05 123456789 123 123456");
+        assert_eq!(format("%s"), "1483283045");
+        assert_eq!(format("%z %:z %Z"), "+0000 +00:00 UTC");
+        assert_eq!(format("%F %T"), "2017-01-01 15:04:05");
+        assert_eq!(
+            format("%D %x %R %X %r"),
+            "01/01/17 01/01/17 15:04 15:04:05 03:04:05 PM"
+        );
+        assert_eq!(format("%c"), "Sun Jan  1 15:04:05 2017");
+        assert_eq!(format("%%%n%t"), "%\n\t");
+    }
+
+    #[test]
+    fn padding_flags_change_numbers() {
+        let mark = parse_rfc3339_second("2017-01-05T03:04:05Z").unwrap();
+        let format = |pattern: &str| {
+            Pattern::new(pattern)
+                .unwrap()
+                .format::<Second>(mark)
+                .to_string()
+        };
+        assert_eq!(format("%d %-d %_d %0e %e %-e"), "05 5  5 05  5 5");
+        assert_eq!(format("%6Y %_6Y %-j"), "002017   2017 5");
+    }
+
+    #[test]
+    fn negative_years_keep_their_width() {
+        let day = year_month_day_to_day(-1, 1, 1);
+        assert_eq!(strftime!("%Y").format::<Day>(day).to_string(), "-001");
+        assert_eq!(strftime!("%5Y").format::<Day>(day).to_string(), "-0001");
+        assert_eq!(strftime!("%5Y").parse::<Day>("-0001"), Ok(day));
+    }
+
+    #[test]
+    fn leap_seconds_are_formatted_as_second_60() {
+        let mark = parse_rfc3339_second("2016-12-31T23:59:60Z").unwrap();
+        assert_eq!(
+            strftime!("%F %T").format::<Second>(mark).to_string(),
+            "2016-12-31 23:59:60"
+        );
+        assert_eq!(
+            strftime!("%s").format::<Second>(mark).to_string(),
+            strftime!("%s").format::<Second>(mark + 1).to_string()
+        );
+    }
+
+    #[test]
+    fn unix_timestamps_are_formatted_at_the_ends_of_the_lane() {
+        assert_eq!(
+            strftime!("%s").format::<Second>(Mark::MAX).to_string(),
+            "9223372037801460602"
+        );
+        assert_eq!(
+            strftime!("%s").format::<Second>(Mark::MIN).to_string(),
+            "-9223372035908090986"
+        );
+    }
+
+    #[test]
+    fn larger_lanes_are_formatted_at_their_start() {
+        assert_eq!(
+            strftime!("%F %T").format::<Year>(2017).to_string(),
+            "2017-01-01 00:00:00"
+        );
+        assert_eq!(
+            strftime!("%F %T").format::<Month>(2).to_string(),
+            "2000-02-01 00:00:00"
+        );
+    }
+
+    #[test]
+    fn parsing_rounds_down_to_the_lane() {
+        let pattern = strftime!("%F %T");
+        assert_eq!(pattern.parse::<Day>("2000-01-01 23:59:59"), Ok(1));
+        assert_eq!(pattern.parse::<Month>("2000-02-29 23:59:59"), Ok(2));
+        assert_eq!(pattern.parse::<Year>("2000-12-31 23:59:59"), Ok(2000));
+        assert_eq!(
+            strftime!("%T.%N").parse::<Nanosecond>("00:00:01.5"),
+            Ok(1_500_000_000)
+        );
+    }
+
+    #[test]
+    fn parsing_accepts_names_in_any_case_and_length() {
+        let expected = parse_rfc3339_second("2017-01-01T00:00:00Z");
+        for text in [
+            "Sun Jan 1 2017",
+            "SUNDAY JANUARY 1 2017",
+            "sun january  1 2017",
+        ] {
+            assert_eq!(strftime!("%a %b %e %Y").parse::<Second>(text), expected);
+        }
+    }
+
+    #[test]
+    fn parsing_checks_day_of_week() {
+        assert_eq!(
+            error(strftime!("%a %F").parse::<Second>("Mon 2017-01-01")),
+            (0, ParseErrorKind::MismatchedWeekday)
+        );
+    }
+
+    #[test]
+    fn parsing_resolves_week_dates() {
+        let expected = parse_rfc3339_second("2017-01-01T00:00:00Z");
+        assert_eq!(
+            strftime!("%G-W%V-%u").parse::<Second>("2016-W52-7"),
+            expected
+        );
+        assert_eq!(strftime!("%Y %U %w").parse::<Second>("2017 01 0"), expected);
+        assert_eq!(strftime!("%Y %W %u").parse::<Second>("2017 00 7"), expected);
+        assert_eq!(strftime!("%Y %j").parse::<Second>("2017 001"), expected);
+    }
+
+    #[test]
+    fn parsing_resolves_12_hour_clock_and_two_digits_years() {
+        let pattern = strftime!("%D %r");
+        assert_eq!(
+            pattern.parse::<Second>("01/01/17 12:00:00 AM"),
+            parse_rfc3339_second("2017-01-01T00:00:00Z")
+        );
+        assert_eq!(
+            pattern.parse::<Second>("01/01/69 12:30:00 PM"),
+            parse_rfc3339_second("1969-01-01T12:30:00Z")
+        );
+    }
+
+    #[test]
+    fn parsing_accepts_unix_timestamps() {
+        assert_eq!(
+            strftime!("%s").parse::<Second>("1483228800"),
+            parse_rfc3339_second("2017-01-01T00:00:00Z")
+        );
+        assert_eq!(
+            strftime!("%s").parse::<Second>("-1"),
+            parse_rfc3339_second("1969-12-31T23:59:59Z")
+        );
+    }
+
+    #[test]
+    fn parsing_checks_leap_seconds() {
+        let pattern = strftime!("%F %T%z");
+        assert!(pattern.parse::<Second>("2016-12-31 23:59:60+00:00").is_ok());
+        assert_eq!(
+            error(pattern.parse::<Second>("2015-12-31 23:59:60Z")),
+            (17, ParseErrorKind::InvalidLeapSecond)
+        );
+    }
+
+    #[test]
+    fn parsing_reports_error_positions() {
+        let pattern = strftime!("%F %T");
+        assert_eq!(
+            error(pattern.parse::<Second>("2017-13-01 00:00:00")),
+            (5, ParseErrorKind::InvalidMonth)
+        );
+        assert_eq!(
+            error(pattern.parse::<Second>("2017-02-29 00:00:00")),
+            (8, ParseErrorKind::InvalidDay)
+        );
+        assert_eq!(
+            error(pattern.parse::<Second>("2017-02-28 00:00")),
+            (16, ParseErrorKind::UnexpectedEnd)
+        );
+        assert_eq!(
+            error(pattern.parse::<Second>("2017-02-28 00:00:00 ")),
+            (19, ParseErrorKind::TrailingCharacters)
+        );
+        assert_eq!(
+            error(strftime!("%Y").parse::<Nanosecond>("2300")),
+            (0, ParseErrorKind::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        let error = |pattern| {
+            let error = Pattern::new(pattern).unwrap_err();
+            (error.position(), error.kind())
+        };
+        assert_eq!(error("%"), (1, ParseErrorKind::UnexpectedEnd));
+        assert_eq!(error("%-"), (2, ParseErrorKind::UnexpectedEnd));
+        assert_eq!(error("%Y%q"), (3, ParseErrorKind::UnexpectedCharacter));
+        assert_eq!(error("%:Y"), (1, ParseErrorKind::UnexpectedCharacter));
+        assert_eq!(error("%10N"), (1, ParseErrorKind::UnexpectedCharacter));
+        assert_eq!(error("%065Y"), (2, ParseErrorKind::OutOfRange));
+        assert!(Pattern::new("%064Y").is_ok());
+    }
+}
@@ -150,3 +150,54 @@ pub const fn millisecond_to_second_up(mark: Mark) -> Mark {
 pub const fn second_to_millisecond(mark: Mark) -> Mark {
     mark * 1_000
 }
+
+/// Converts a nanosecond [`Mark`] to a rounded down day [`Mark`].
+///
+/// This gives the same day as [`nanosecond_to_second`] and [`crate::second_to_day`].
+///
+/// # Examples
+/// ```
+/// use timelane::subsecond::nanosecond_to_day;
+/// assert_eq!(nanosecond_to_day(-1), 0);
+/// assert_eq!(nanosecond_to_day(0), 1);
+/// assert_eq!(nanosecond_to_day(86_400_000_000_000), 2);
+/// use timelane::Mark;
+/// assert_eq!(nanosecond_to_day(Mark::MIN), -106_751);
+/// assert_eq!(nanosecond_to_day(Mark::MAX), 106_752);
+/// ```
+pub const fn nanosecond_to_day(mark: Mark) -> Mark {
+    crate::second_to_day(nanosecond_to_second(mark))
+}
+
+/// Converts a nanosecond [`Mark`] to a rounded up day [`Mark`].
+///
+/// This gives the same day as [`nanosecond_to_second_up`] and [`crate::second_to_day_up`].
+///
+/// # Examples
+/// ```
+/// use timelane::subsecond::nanosecond_to_day_up;
+/// assert_eq!(nanosecond_to_day_up(-1), 1);
+/// assert_eq!(nanosecond_to_day_up(0), 1);
+/// assert_eq!(nanosecond_to_day_up(1), 2);
+/// use timelane::Mark;
+/// assert_eq!(nanosecond_to_day_up(Mark::MIN), -106_750);
+/// assert_eq!(nanosecond_to_day_up(Mark::MAX), 106_753);
+/// ```
+pub const fn nanosecond_to_day_up(mark: Mark) -> Mark {
+    crate::second_to_day_up(nanosecond_to_second_up(mark))
+}
+
+/// Converts a day [`Mark`] to a nanosecond [`Mark`].
+///
+/// This gives the same nanosecond as [`crate::day_to_second`] and [`second_to_nanosecond`].
+///
+/// # Examples
+/// ```
+/// use timelane::subsecond::day_to_nanosecond;
+/// assert_eq!(day_to_nanosecond(0), -86_400_000_000_000);
+/// assert_eq!(day_to_nanosecond(1), 0);
+/// assert_eq!(day_to_nanosecond(2), 86_400_000_000_000);
+/// ```
+pub const fn day_to_nanosecond(mark: Mark) -> Mark {
+    second_to_nanosecond(crate::day_to_second(mark))
+}
@@ -0,0 +1,330 @@
+//! Conversions between [`Mark`] of any lane and [`SystemTime`] or [`Duration`], with the `std`
+//! feature.
+//!
+//! [`SystemTime`] counts POSIX time, where every day lasts 86 400 seconds: leap seconds do not
+//! exist there. [`LeapSeconds`] chooses how the seconds around a leap second are mapped between
+//! POSIX time and the second lane.
+//!
+//! A [`Duration`] is elapsed time: it converts to and from a number of nanosecond or second
+//! marks, such as the difference between two marks, leap seconds included.
+//!
+//! # Examples
+//! ```
+//! use std::time::{Duration, SystemTime};
+//! use timelane::lane::{Day, Second};
+//! use timelane::system::{from_system_time, to_system_time, LeapSeconds};
+//! let time = SystemTime::UNIX_EPOCH + Duration::from_secs(946_684_800);
+//! assert_eq!(from_system_time::<Second>(time, LeapSeconds::Posix), Some(0));
+//! assert_eq!(to_system_time::<Day>(1, LeapSeconds::Posix), Some(time));
+//! ```
+use std::time::{Duration, SystemTime};
+
+use crate::civil::{posix_to_second, second_to_posix};
+use crate::lane::{starts_in_lane, Lane};
+use crate::parse::MAX_SECOND_ZYEAR;
+use crate::{minute_to_second, Mark, LEAP_SECONDS_MARKS};
+
+/// How leap seconds are mapped to and from POSIX time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LeapSeconds {
+    /// Like POSIX: a leap second has the same POSIX time as the midnight following it, and a
+    /// POSIX time is never mapped to a leap second.
+    ///
+    /// This is how most systems step their clock over a leap second.
+    Posix,
+    /// The leap second is spread over the 24 hours from noon to noon around it, where each POSIX
+    /// second lasts 86 401 / 86 400 seconds.
+    ///
+    /// This matches the clocks synchronized with the smeared NTP servers of Google or Amazon.
+    Smear,
+}
+
+/// The number of nanoseconds in a second.
+const NANOSECONDS: i128 = 1_000_000_000;
+
+/// Returns the rounded down [`Mark`] of a lane containing a [`SystemTime`], or `None` when it
+/// does not fit in the lane.
+///
+/// # Examples
+/// ```
+/// use std::time::{Duration, SystemTime};
+/// use timelane::lane::Nanosecond;
+/// use timelane::system::{from_system_time, LeapSeconds};
+/// let time = SystemTime::UNIX_EPOCH + Duration::new(946_684_799, 500_000_000);
+/// assert_eq!(from_system_time::<Nanosecond>(time, LeapSeconds::Posix), Some(-500_000_000));
+/// ```
+pub fn from_system_time<L: Lane>(time: SystemTime, leap_seconds: LeapSeconds) -> Option<Mark> {
+    let posix = match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(duration) => duration.as_nanos() as i128,
+        Err(error) => -(error.duration().as_nanos() as i128),
+    };
+    let (second, nanosecond) = from_posix(posix, leap_seconds)?;
+    if second < L::MIN_SECOND || second > L::MAX_SECOND {
+        return None;
+    }
+    Some(L::from_second_and_nanosecond(second, nanosecond))
+}
+
+/// Returns the [`SystemTime`] where a [`Mark`] of a lane starts, or `None` when it does not start
+/// between [`Lane::MIN_SECOND`] and [`Lane::MAX_SECOND`], or does not fit in a [`SystemTime`].
+///
+/// # Examples
+/// ```
+/// use std::time::{Duration, SystemTime};
+/// use timelane::lane::Second;
+/// use timelane::system::{to_system_time, LeapSeconds};
+/// use timelane::iso8601::parse_rfc3339_second;
+/// let leap = parse_rfc3339_second("2016-12-31T23:59:60Z").unwrap();
+/// assert_eq!(
+///     to_system_time::<Second>(leap, LeapSeconds::Posix),
+///     Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_483_228_800))
+/// );
+/// assert_eq!(
+///     to_system_time::<Second>(leap, LeapSeconds::Smear),
+///     Some(SystemTime::UNIX_EPOCH + Duration::new(1_483_228_799, 500_005_786))
+/// );
+/// ```
+pub fn to_system_time<L: Lane>(mark: Mark, leap_seconds: LeapSeconds) -> Option<SystemTime> {
+    if !starts_in_lane::<L>(mark) {
+        return None;
+    }
+    let (second, nanosecond) = L::to_second_and_nanosecond(mark);
+    let posix = to_posix(second, nanosecond, leap_seconds);
+    let duration = Duration::new(
+        u64::try_from(posix.unsigned_abs() / NANOSECONDS as u128).ok()?,
+        (posix.unsigned_abs() % NANOSECONDS as u128) as u32,
+    );
+    if posix >= 0 {
+        SystemTime::UNIX_EPOCH.checked_add(duration)
+    } else {
+        SystemTime::UNIX_EPOCH.checked_sub(duration)
+    }
+}
+
+/// Returns the rounded down [`Mark`] of a lane containing the current time.
+///
+/// # Panics
+/// Panics if the current time does not fit in the lane, which only happens with a 32-bit
+/// [`Mark`] in the subsecond lanes.
+///
+/// # Examples
+/// ```
+/// use timelane::lane::{Day, Year};
+/// use timelane::system::{now, LeapSeconds};
+/// use timelane::{day_to_month, month_to_year};
+/// let day = now::<Day>(LeapSeconds::Posix);
+/// assert_eq!(now::<Year>(LeapSeconds::Posix), month_to_year(day_to_month(day)));
+/// ```
+pub fn now<L: Lane>(leap_seconds: LeapSeconds) -> Mark {
+    from_system_time::<L>(SystemTime::now(), leap_seconds)
+        .expect("the current time does not fit in the lane")
+}
+
+/// Returns the number of nanosecond marks in a [`Duration`], or `None` if it overflows.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use timelane::system::duration_to_nanoseconds;
+/// assert_eq!(duration_to_nanoseconds(Duration::from_millis(1_500)), Some(1_500_000_000));
+/// ```
+pub const fn duration_to_nanoseconds(duration: Duration) -> Option<Mark> {
+    let nanoseconds = duration.as_nanos();
+    if nanoseconds > Mark::MAX as u128 {
+        None
+    } else {
+        Some(nanoseconds as Mark)
+    }
+}
+
+/// Returns the rounded down number of second marks in a [`Duration`], or `None` if it overflows.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use timelane::system::duration_to_seconds;
+/// assert_eq!(duration_to_seconds(Duration::from_millis(1_500)), Some(1));
+/// ```
+pub const fn duration_to_seconds(duration: Duration) -> Option<Mark> {
+    let seconds = duration.as_secs();
+    if seconds > Mark::MAX as u64 {
+        None
+    } else {
+        Some(seconds as Mark)
+    }
+}
+
+/// Returns the [`Duration`] of a number of nanosecond marks, or `None` if it is negative.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use timelane::system::nanoseconds_to_duration;
+/// use timelane::subsecond::second_to_nanosecond;
+/// let duration = nanoseconds_to_duration(second_to_nanosecond(61) - second_to_nanosecond(0));
+/// assert_eq!(duration, Some(Duration::from_secs(61)));
+/// ```
+pub const fn nanoseconds_to_duration(nanoseconds: Mark) -> Option<Duration> {
+    if nanoseconds < 0 {
+        None
+    } else {
+        Some(Duration::from_nanos(nanoseconds as u64))
+    }
+}
+
+/// Returns the [`Duration`] of a number of second marks, or `None` if it is negative.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use timelane::system::seconds_to_duration;
+/// use timelane::iso8601::parse_rfc3339_second;
+/// let start = parse_rfc3339_second("2016-12-31T23:59:00Z").unwrap();
+/// let end = parse_rfc3339_second("2017-01-01T00:00:00Z").unwrap();
+/// assert_eq!(seconds_to_duration(end - start), Some(Duration::from_secs(61)));
+/// ```
+pub const fn seconds_to_duration(seconds: Mark) -> Option<Duration> {
+    if seconds < 0 {
+        None
+    } else {
+        Some(Duration::from_secs(seconds as u64))
+    }
+}
+
+/// Returns the first second [`Mark`] of the 86 401 seconds smeared around the leap second
+/// inserted at the end of a minute [`Mark`].
+const fn smear_start(leap_minute: Mark) -> Mark {
+    minute_to_second(leap_minute) - 43_201
+}
+
+/// Returns the POSIX time in nanoseconds of a second [`Mark`] and the nanoseconds after it.
+fn to_posix(second: Mark, nanosecond: Mark, leap_seconds: LeapSeconds) -> i128 {
+    if leap_seconds == LeapSeconds::Smear {
+        for leap_minute in LEAP_SECONDS_MARKS {
+            let start = smear_start(leap_minute);
+            if second >= start && second < start + 86_401 {
+                let elapsed = (second - start) as i128 * NANOSECONDS + nanosecond as i128;
+                return second_to_posix(start) * NANOSECONDS + elapsed * 86_400 / 86_401;
+            }
+        }
+    }
+    second_to_posix(second) * NANOSECONDS + nanosecond as i128
+}
+
+/// Returns the second [`Mark`] and the nanoseconds after it of a POSIX time in nanoseconds, or
+/// `None` if it overflows.
+fn from_posix(posix: i128, leap_seconds: LeapSeconds) -> Option<(Mark, Mark)> {
+    let posix_second = Mark::try_from(posix.div_euclid(NANOSECONDS)).ok()?;
+    if (posix_second / 86_400).unsigned_abs() > MAX_SECOND_ZYEAR.unsigned_abs() * 365 {
+        return None;
+    }
+    if leap_seconds == LeapSeconds::Smear {
+        for leap_minute in LEAP_SECONDS_MARKS {
+            let start = smear_start(leap_minute);
+            let smeared = posix - second_to_posix(start) * NANOSECONDS;
+            if (0..86_400 * NANOSECONDS).contains(&smeared) {
+                // Rounded up to the first nanosecond whose smeared time is this POSIX time
+                let elapsed = (smeared * 86_401 + 86_399) / 86_400;
+                return Some((
+                    start + (elapsed / NANOSECONDS) as Mark,
+                    (elapsed % NANOSECONDS) as Mark,
+                ));
+            }
+        }
+    }
+    Some((
+        posix_to_second(posix_second),
+        posix.rem_euclid(NANOSECONDS) as Mark,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iso8601::{parse_rfc3339, parse_rfc3339_second};
+    use crate::lane::{Day, Nanosecond, Second, Year};
+
+    fn posix(seconds: u64, nanoseconds: u32) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::new(seconds, nanoseconds)
+    }
+
+    #[test]
+    fn posix_time_skips_leap_seconds() {
+        let leap = parse_rfc3339_second("2016-12-31T23:59:60Z").unwrap();
+        let time = |second| to_system_time::<Second>(second, LeapSeconds::Posix).unwrap();
+        assert_eq!(time(leap - 1), posix(1_483_228_799, 0));
+        assert_eq!(time(leap), posix(1_483_228_800, 0));
+        assert_eq!(time(leap + 1), posix(1_483_228_800, 0));
+        let mark = |time| from_system_time::<Second>(time, LeapSeconds::Posix).unwrap();
+        assert_eq!(mark(posix(1_483_228_799, 999_999_999)), leap - 1);
+        assert_eq!(mark(posix(1_483_228_800, 0)), leap + 1);
+    }
+
+    #[test]
+    fn smeared_time_spreads_leap_seconds() {
+        let noon = parse_rfc3339_second("2016-12-31T12:00:00Z").unwrap();
+        let time = |second| to_system_time::<Second>(second, LeapSeconds::Smear).unwrap();
+        assert_eq!(time(noon - 1), posix(1_483_185_599, 0));
+        assert_eq!(time(noon), posix(1_483_185_600, 0));
+        assert_eq!(time(noon + 1), posix(1_483_185_600, 999_988_426));
+        assert_eq!(time(noon + 86_401), posix(1_483_272_000, 0));
+        let mark = |time| from_system_time::<Second>(time, LeapSeconds::Smear).unwrap();
+        assert_eq!(mark(posix(1_483_185_599, 999_999_999)), noon - 1);
+        assert_eq!(mark(posix(1_483_185_600, 999_988_425)), noon);
+        assert_eq!(mark(posix(1_483_185_600, 999_988_426)), noon + 1);
+        assert_eq!(mark(posix(1_483_271_999, 999_999_999)), noon + 86_400);
+        assert_eq!(mark(posix(1_483_272_000, 0)), noon + 86_401);
+    }
+
+    #[test]
+    fn system_time_roundtrips_through_marks() {
+        let leap = parse_rfc3339("2016-12-31T23:59:60Z").unwrap();
+        for leap_seconds in [LeapSeconds::Posix, LeapSeconds::Smear] {
+            for nanosecond in
+                (leap - 50_000_000_000_000..leap + 50_000_000_000_000).step_by(99_999_999_977)
+            {
+                let time = to_system_time::<Nanosecond>(nanosecond, leap_seconds).unwrap();
+                let mark = from_system_time::<Nanosecond>(time, leap_seconds).unwrap();
+                assert_eq!(to_system_time::<Nanosecond>(mark, leap_seconds), Some(time));
+            }
+        }
+    }
+
+    #[test]
+    fn system_times_before_1970_are_supported() {
+        let time = SystemTime::UNIX_EPOCH - Duration::from_secs(86_400);
+        let day = from_system_time::<Day>(time, LeapSeconds::Posix).unwrap();
+        assert_eq!(day, crate::civil::UNIX_EPOCH_DAY - 1);
+        assert_eq!(to_system_time::<Day>(day, LeapSeconds::Posix), Some(time));
+    }
+
+    #[test]
+    fn system_times_out_of_the_lane_are_rejected() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(20_000_000_000);
+        assert_eq!(
+            from_system_time::<Nanosecond>(time, LeapSeconds::Posix),
+            None
+        );
+        assert!(from_system_time::<Second>(time, LeapSeconds::Posix).is_some());
+    }
+
+    #[test]
+    fn marks_out_of_the_lane_are_rejected() {
+        for leap_seconds in [LeapSeconds::Posix, LeapSeconds::Smear] {
+            assert_eq!(to_system_time::<Day>(Mark::MAX / 2, leap_seconds), None);
+            assert_eq!(to_system_time::<Day>(Mark::MIN / 2, leap_seconds), None);
+            assert_eq!(to_system_time::<Year>(Mark::MAX, leap_seconds), None);
+            assert_eq!(to_system_time::<Year>(Mark::MIN, leap_seconds), None);
+            let nanosecond = Mark::MAX - 2_000_000_000;
+            assert!(to_system_time::<Nanosecond>(nanosecond, leap_seconds).is_some());
+        }
+    }
+
+    #[test]
+    fn negative_durations_are_rejected() {
+        assert_eq!(nanoseconds_to_duration(-1), None);
+        assert_eq!(seconds_to_duration(-1), None);
+        assert_eq!(duration_to_seconds(Duration::MAX), None);
+        assert_eq!(duration_to_nanoseconds(Duration::MAX), None);
+    }
+}
@@ -0,0 +1,38 @@
+//! Helpers shared by the tests of several modules.
+use crate::Mark;
+
+/// Returns a pseudo-random number for each call.
+pub(crate) fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+/// Checks the scalers between the months and the days of a calendar against a walk over
+/// `months` months after and before month one, which starts on `first_day`.
+pub(crate) fn assert_month_walk(
+    months: Mark,
+    first_day: Mark,
+    days_in_month: impl Fn(Mark) -> Mark,
+    month_to_day: impl Fn(Mark) -> Mark,
+    day_to_month: impl Fn(Mark) -> Mark,
+    day_to_month_up: impl Fn(Mark) -> Mark,
+) {
+    let mut first_day_of_month = first_day;
+    for month in 1..=months {
+        let days = days_in_month(month);
+        assert_eq!(month_to_day(month), first_day_of_month, "{}", month);
+        let last_day_of_month = first_day_of_month + days - 1;
+        assert_eq!(day_to_month(last_day_of_month), month, "{}", month);
+        assert_eq!(day_to_month_up(last_day_of_month), month + 1, "{}", month);
+        assert_eq!(day_to_month_up(first_day_of_month), month, "{}", month);
+        first_day_of_month += days;
+    }
+    let mut first_day_of_month = first_day;
+    for month in (1 - months..=0).rev() {
+        first_day_of_month -= days_in_month(month);
+        assert_eq!(month_to_day(month), first_day_of_month, "{}", month);
+        assert_eq!(day_to_month(first_day_of_month), month, "{}", month);
+    }
+}
@@ -0,0 +1,172 @@
+//! Conversions between [`LaneMark`] and the date and time types of `time`, with the `time`
+//! feature.
+//!
+//! A `time` [`OffsetDateTime`] of any UTC offset converts to a rounded down [`LaneMark`] of any
+//! lane, and a [`LaneMark`] converts to the UTC [`OffsetDateTime`] where it starts. A [`Date`]
+//! converts to and from a day [`LaneMark`].
+//!
+//! `time` cannot represent leap seconds: a [`LaneMark`] starting during a leap second converts to
+//! the last nanosecond before it, `23:59:59.999999999`. This keeps the date, and the order of the
+//! converted marks, but does not round-trip.
+//!
+//! # Examples
+//! ```
+//! use time::macros::datetime;
+//! use time::OffsetDateTime;
+//! use timelane::lane::{LaneMark, Second};
+//! let second = LaneMark::<Second>::try_from(datetime!(2016-12-31 23:59:59 UTC)).unwrap();
+//! assert_eq!(second.mark(), 536_544_003);
+//! let leap = LaneMark::<Second>::new(second.mark() + 1);
+//! assert_eq!(
+//!     OffsetDateTime::try_from(leap).unwrap(),
+//!     datetime!(2016-12-31 23:59:59.999_999_999 UTC)
+//! );
+//! ```
+use ::time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time};
+
+use crate::lane::{ConversionError, Day, Lane, LaneMark};
+use crate::Mark;
+
+impl<L: Lane> TryFrom<OffsetDateTime> for LaneMark<L> {
+    type Error = ConversionError;
+
+    fn try_from(date_time: OffsetDateTime) -> Result<Self, ConversionError> {
+        let offset = date_time.offset();
+        // The UTC offset is removed in the minute and second lanes, where the fields can overflow
+        LaneMark::from_civil(
+            (
+                date_time.year() as Mark,
+                date_time.month() as Mark,
+                date_time.day() as Mark,
+            ),
+            (
+                date_time.hour() as Mark,
+                date_time.minute() as Mark - offset.whole_minutes() as Mark,
+                date_time.second() as Mark - offset.seconds_past_minute() as Mark,
+            ),
+            date_time.nanosecond() as Mark,
+        )
+    }
+}
+
+impl<L: Lane> TryFrom<LaneMark<L>> for OffsetDateTime {
+    type Error = ConversionError;
+
+    fn try_from(mark: LaneMark<L>) -> Result<Self, ConversionError> {
+        let ((year, month, day), (hour, minute, second), nanosecond) = mark.to_civil();
+        let date = date(year, month, day)?;
+        let (second, nanosecond) = if second == 60 {
+            (59, 999_999_999)
+        } else {
+            (second, nanosecond)
+        };
+        let time = Time::from_hms_nano(hour as u8, minute as u8, second as u8, nanosecond as u32)
+            .map_err(|_| ConversionError::OutOfRange)?;
+        Ok(PrimitiveDateTime::new(date, time).assume_utc())
+    }
+}
+
+impl TryFrom<Date> for LaneMark<Day> {
+    type Error = ConversionError;
+
+    fn try_from(date: Date) -> Result<Self, ConversionError> {
+        LaneMark::from_civil(
+            (
+                date.year() as Mark,
+                date.month() as Mark,
+                date.day() as Mark,
+            ),
+            (0, 0, 0),
+            0,
+        )
+    }
+}
+
+impl TryFrom<LaneMark<Day>> for Date {
+    type Error = ConversionError;
+
+    fn try_from(mark: LaneMark<Day>) -> Result<Self, ConversionError> {
+        let ((year, month, day), _, _) = mark.to_civil();
+        date(year, month, day)
+    }
+}
+
+/// Returns the [`Date`] of a year, a one-based month and a one-based day of month.
+fn date(year: Mark, month: Mark, day: Mark) -> Result<Date, ConversionError> {
+    let year = i32::try_from(year).map_err(|_| ConversionError::OutOfRange)?;
+    let month = Month::try_from(month as u8).map_err(|_| ConversionError::OutOfRange)?;
+    Date::from_calendar_date(year, month, day as u8).map_err(|_| ConversionError::OutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use ::time::macros::{date, datetime};
+
+    use super::*;
+    use crate::iso8601::parse_rfc3339;
+    use crate::lane::{Minute, Nanosecond, Second};
+    use crate::{minute_to_second, LEAP_SECONDS_MARKS};
+
+    #[test]
+    fn leap_seconds_are_clamped() {
+        for leap_minute in LEAP_SECONDS_MARKS {
+            let leap = minute_to_second(leap_minute) - 1;
+            let before = OffsetDateTime::try_from(LaneMark::<Second>::new(leap - 1)).unwrap();
+            let during = OffsetDateTime::try_from(LaneMark::<Second>::new(leap)).unwrap();
+            let after = OffsetDateTime::try_from(LaneMark::<Second>::new(leap + 1)).unwrap();
+            assert_eq!(during - before, ::time::Duration::nanoseconds(999_999_999));
+            assert_eq!(after - during, ::time::Duration::nanoseconds(1));
+            assert_eq!(
+                LaneMark::<Second>::try_from(before).unwrap().mark(),
+                leap - 1
+            );
+            assert_eq!(
+                LaneMark::<Second>::try_from(during).unwrap().mark(),
+                leap - 1
+            );
+            assert_eq!(
+                LaneMark::<Second>::try_from(after).unwrap().mark(),
+                leap + 1
+            );
+        }
+    }
+
+    #[test]
+    fn offsets_are_removed() {
+        let date_time = datetime!(2017-01-01 00:59:59.5 +01:00:30);
+        let nanosecond = LaneMark::<Nanosecond>::try_from(date_time).unwrap();
+        assert_eq!(
+            nanosecond.mark(),
+            parse_rfc3339("2016-12-31T23:59:29.5Z").unwrap()
+        );
+        let minute = LaneMark::<Minute>::try_from(date_time).unwrap();
+        assert_eq!(
+            OffsetDateTime::try_from(minute).unwrap(),
+            datetime!(2016-12-31 23:59 UTC)
+        );
+    }
+
+    #[test]
+    fn dates_roundtrip() {
+        for day in -700_000..700_000 {
+            let date = Date::try_from(LaneMark::<Day>::new(day)).unwrap();
+            assert_eq!(LaneMark::<Day>::try_from(date).unwrap().mark(), day);
+        }
+        assert_eq!(
+            Date::try_from(LaneMark::<Day>::new(1)),
+            Ok(date!(2000 - 01 - 01))
+        );
+    }
+
+    #[test]
+    fn out_of_range_marks_are_rejected() {
+        assert_eq!(
+            Date::try_from(LaneMark::<Day>::new(3_000_000)),
+            Err(ConversionError::OutOfRange)
+        );
+        assert_eq!(
+            LaneMark::<Nanosecond>::try_from(datetime!(9999-12-31 23:59:59 UTC)),
+            Err(ConversionError::OutOfRange)
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Time zones, mapping UTC minute marks to the local minute marks of a wall clock.
+//!
+//! A local minute [`Mark`] counts minutes like a UTC minute [`Mark`] but on the wall clock of a
+//! time zone: its day, hour and minute are the ones read locally. A [`Zone`] only tells the UTC
+//! offset in effect at a UTC minute, and derives from it how local minutes map back to UTC,
+//! including the local minutes skipped or repeated when the offset changes.
+//!
+//! # Examples
+//! ```
+//! use timelane::zone::{FixedOffset, LocalMinute, Zone};
+//! let zone = FixedOffset(-5 * 60);
+//! assert_eq!(zone.local_minute(600), 300);
+//! assert_eq!(zone.utc_minutes(300), LocalMinute::Unique(600));
+//! ```
+use crate::Mark;
+
+/// A time zone, telling the UTC offset in effect at any UTC minute [`Mark`].
+///
+/// Offsets must stay within a day of UTC, and must not change more than once within any two days,
+/// for [`Zone::utc_minutes`] to find all the UTC minutes of a local minute.
+pub trait Zone {
+    /// Returns the UTC offset, in minutes, in effect at a UTC minute [`Mark`].
+    fn offset(&self, utc_minute: Mark) -> Mark;
+
+    /// Returns the local minute [`Mark`] of a UTC minute [`Mark`].
+    fn local_minute(&self, utc_minute: Mark) -> Mark {
+        utc_minute + self.offset(utc_minute)
+    }
+
+    /// Returns the UTC minute marks where the wall clock shows a local minute [`Mark`].
+    fn utc_minutes(&self, local_minute: Mark) -> LocalMinute {
+        let before = self.offset(local_minute - 1440);
+        let after = self.offset(local_minute + 1440);
+        if before == after {
+            return LocalMinute::Unique(local_minute - before);
+        }
+        let with_before = local_minute - before;
+        let with_after = local_minute - after;
+        match (
+            self.offset(with_before) == before,
+            self.offset(with_after) == after,
+        ) {
+            (true, true) => LocalMinute::Repeated(with_before, with_after),
+            (true, false) => LocalMinute::Unique(with_before),
+            (false, true) => LocalMinute::Unique(with_after),
+            (false, false) => LocalMinute::Skipped(with_before),
+        }
+    }
+
+    /// Returns the first UTC minute [`Mark`] where the wall clock shows a local minute
+    /// [`Mark`], or the UTC minute of a skipped local minute with the offset before the gap.
+    ///
+    /// This follows RFC 5545: a skipped local time is moved forward by the length of the gap,
+    /// and a repeated one uses its first occurrence.
+    fn utc_minute(&self, local_minute: Mark) -> Mark {
+        match self.utc_minutes(local_minute) {
+            LocalMinute::Unique(minute)
+            | LocalMinute::Repeated(minute, _)
+            | LocalMinute::Skipped(minute) => minute,
+        }
+    }
+}
+
+impl<Z: Zone + ?Sized> Zone for &Z {
+    fn offset(&self, utc_minute: Mark) -> Mark {
+        (**self).offset(utc_minute)
+    }
+}
+
+/// The UTC minute marks where the wall clock of a [`Zone`] shows a local minute [`Mark`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LocalMinute {
+    /// The local minute happens once, at this UTC minute.
+    Unique(Mark),
+    /// The local minute happens twice, at these UTC minutes, when the clock is set back.
+    Repeated(Mark, Mark),
+    /// The local minute is skipped when the clock is set forward. This is the UTC minute it would
+    /// be with the offset in effect before the change, where the clock shows a later minute.
+    Skipped(Mark),
+}
+
+/// The UTC time zone.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Utc;
+
+impl Zone for Utc {
+    fn offset(&self, _utc_minute: Mark) -> Mark {
+        0
+    }
+}
+
+/// A time zone with a constant UTC offset, in minutes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FixedOffset(pub Mark);
+
+impl Zone for FixedOffset {
+    fn offset(&self, _utc_minute: Mark) -> Mark {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A zone at +1 hour, and at +2 hours from minute 1000 to minute 5000.
+    struct Summer;
+
+    impl Zone for Summer {
+        fn offset(&self, utc_minute: Mark) -> Mark {
+            if (1000..5000).contains(&utc_minute) {
+                120
+            } else {
+                60
+            }
+        }
+    }
+
+    #[test]
+    fn skipped_and_repeated_local_minutes_are_found() {
+        assert_eq!(Summer.utc_minutes(1059), LocalMinute::Unique(999));
+        assert_eq!(Summer.utc_minutes(1060), LocalMinute::Skipped(1000));
+        assert_eq!(Summer.utc_minutes(1119), LocalMinute::Skipped(1059));
+        assert_eq!(Summer.utc_minutes(1120), LocalMinute::Unique(1000));
+        assert_eq!(Summer.utc_minute(1060), 1000);
+        assert_eq!(Summer.utc_minutes(5059), LocalMinute::Unique(4939));
+        assert_eq!(Summer.utc_minutes(5060), LocalMinute::Repeated(4940, 5000));
+        assert_eq!(Summer.utc_minutes(5119), LocalMinute::Repeated(4999, 5059));
+        assert_eq!(Summer.utc_minutes(5120), LocalMinute::Unique(5060));
+        for utc_minute in 0..6000 {
+            let local_minute = Summer.local_minute(utc_minute);
+            match Summer.utc_minutes(local_minute) {
+                LocalMinute::Unique(minute) => assert_eq!(minute, utc_minute),
+                LocalMinute::Repeated(first, second) => {
+                    assert!(first == utc_minute || second == utc_minute)
+                }
+                LocalMinute::Skipped(_) => panic!("{} is not skipped", local_minute),
+            }
+        }
+    }
+
+    #[test]
+    fn constant_zones_never_skip() {
+        let zones: [&dyn Zone; 2] = [&Utc, &FixedOffset(-90)];
+        for zone in zones {
+            for utc_minute in -100..100 {
+                let local_minute = zone.local_minute(utc_minute);
+                assert_eq!(
+                    zone.utc_minutes(local_minute),
+                    LocalMinute::Unique(utc_minute)
+                );
+            }
+        }
+    }
+}